@@ -0,0 +1,25 @@
+#[macro_use]
+extern crate criterion;
+extern crate jvm_class_file_parser;
+
+use std::fs;
+
+use criterion::Criterion;
+use jvm_class_file_parser::ClassFile;
+
+fn parse_class_file(c: &mut Criterion, filepath: &str) {
+    let bytes = fs::read(filepath).unwrap();
+
+    c.bench_function(&format!("parse {}", filepath), |b| {
+        b.iter(|| ClassFile::from_file(&mut &bytes[..]).unwrap())
+    });
+}
+
+fn parse_class_files(c: &mut Criterion) {
+    // mostly ASCII Utf8 constants
+    parse_class_file(c, "classes/HelloWorld.class");
+    parse_class_file(c, "classes/Lambda.class");
+}
+
+criterion_group!(benches, parse_class_files);
+criterion_main!(benches);
@@ -0,0 +1,6 @@
+// test class where a generic method is overridden, which generates a bridge method
+public class Bridge implements Comparable<Bridge> {
+    public int compareTo(Bridge other) {
+        return 0;
+    }
+}
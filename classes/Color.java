@@ -0,0 +1,6 @@
+// test enum class
+public enum Color {
+    RED,
+    GREEN,
+    BLUE
+}
@@ -9,4 +9,7 @@ public class ExceptionThrows {
         } catch (Exception e) {
         }
     }
+
+    void declareExceptions() throws java.io.IOException, InterruptedException {
+    }
 }
@@ -0,0 +1,4 @@
+import java.util.List;
+
+public class Generic<T, U extends Number & Comparable<U>, L extends List<? super T>> {
+}
@@ -0,0 +1,8 @@
+import java.util.function.Supplier;
+
+public class Lambda {
+    public static void main(String[] args) {
+        Supplier<String> supplier = () -> "Hello, World!";
+        System.out.println(supplier.get());
+    }
+}
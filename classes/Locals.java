@@ -0,0 +1,16 @@
+public class Locals {
+    public static double sum(int count) {
+        int i = 0;
+        int j = 2;
+        long total = 0;
+        double scale = 1.5;
+        long extra = 7;
+
+        for (; i < count; i++) {
+            total += i;
+            j += 1000;
+        }
+
+        return total * scale + j + extra;
+    }
+}
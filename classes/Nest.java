@@ -0,0 +1,5 @@
+public class Nest {
+    class Inner {}
+
+    private static class Hidden {}
+}
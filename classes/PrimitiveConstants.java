@@ -0,0 +1,13 @@
+class PrimitiveConstants {
+    static float floatOne() {
+        return 1.0f;
+    }
+
+    static long longZero() {
+        return 0L;
+    }
+
+    static double doubleOne() {
+        return 1.0;
+    }
+}
@@ -0,0 +1,5 @@
+public sealed class Shape permits Circle, Square {}
+
+final class Circle extends Shape {}
+
+final class Square extends Shape {}
@@ -0,0 +1,9 @@
+// constant values held by ConstantValue attributes of static final fields
+
+class StaticConstantValues {
+    static final int INTEGER_CONSTANT = 65535;
+    static final float FLOAT_CONSTANT = 42.0f;
+    static final long LONG_CONSTANT = 42L;
+    static final double DOUBLE_CONSTANT = -1;
+    static final String STRING_CONSTANT = "fourty two";
+}
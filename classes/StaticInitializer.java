@@ -0,0 +1,3 @@
+public class StaticInitializer {
+    static Object lock = new Object();
+}
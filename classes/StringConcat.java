@@ -0,0 +1,6 @@
+// test class where string concatenation is compiled to an invokedynamic
+public class StringConcat {
+    static void greet(String name) {
+        System.out.println("Hello, " + name + "!");
+    }
+}
@@ -0,0 +1,11 @@
+public class TryFinally {
+    private int count;
+
+    public void run() {
+        try {
+            count = 1;
+        } finally {
+            count = 2;
+        }
+    }
+}
@@ -0,0 +1,16 @@
+import java.lang.annotation.ElementType;
+import java.lang.annotation.Retention;
+import java.lang.annotation.RetentionPolicy;
+import java.lang.annotation.Target;
+
+@Target(ElementType.TYPE_USE)
+@Retention(RetentionPolicy.RUNTIME)
+@interface NonNull {}
+
+public class TypeAnnotated {
+    @NonNull String name;
+
+    Object cast(Object o) {
+        return (@NonNull String) o;
+    }
+}
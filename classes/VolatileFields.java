@@ -0,0 +1,4 @@
+public class VolatileFields {
+    private volatile int x;
+    protected transient String name;
+}
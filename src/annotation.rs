@@ -0,0 +1,422 @@
+use std::io;
+use std::io::Read;
+
+use parsing;
+use util::{io_err, Contextable};
+use ConstantPoolIndex;
+
+/// An annotation, as found in the `RuntimeVisibleAnnotations` family of
+/// attributes.
+///
+/// See Chapter 4.7.16 of the JVM specification for details.
+///
+/// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-4.html#jvms-4.7.16
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Annotation {
+    pub type_index: ConstantPoolIndex,
+    pub element_value_pairs: Vec<ElementValuePair>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ElementValuePair {
+    pub element_name_index: ConstantPoolIndex,
+    pub value: ElementValue,
+}
+
+/// The value of an element of an annotation.
+///
+/// See Chapter 4.7.16.1 of the JVM specification for details.
+///
+/// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-4.html#jvms-4.7.16.1
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ElementValue {
+    Const {
+        tag: char,
+        const_value_index: ConstantPoolIndex,
+    },
+    EnumConst {
+        type_name_index: ConstantPoolIndex,
+        const_name_index: ConstantPoolIndex,
+    },
+    Class {
+        class_info_index: ConstantPoolIndex,
+    },
+    Annotation(Annotation),
+    Array(Vec<ElementValue>),
+}
+
+/// A type annotation, as found in the `RuntimeVisibleTypeAnnotations` and
+/// `RuntimeInvisibleTypeAnnotations` attributes.
+///
+/// See Chapter 4.7.20 of the JVM specification for details.
+///
+/// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-4.html#jvms-4.7.20
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TypeAnnotation {
+    pub target_type: TargetType,
+    pub target_info: TargetInfo,
+    pub target_path: Vec<TypePathEntry>,
+    pub annotation: Annotation,
+}
+
+/// The kind of target of a type annotation.
+///
+/// See Tables 4.7.20-A, 4.7.20-B, and 4.7.20-C of the JVM specification for
+/// details.
+///
+/// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-4.html#jvms-4.7.20-400
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TargetType {
+    ClassTypeParameter,
+    MethodTypeParameter,
+    ClassExtends,
+    ClassTypeParameterBound,
+    MethodTypeParameterBound,
+    Field,
+    MethodReturn,
+    MethodReceiver,
+    MethodFormalParameter,
+    Throws,
+    LocalVariable,
+    ResourceVariable,
+    ExceptionParameter,
+    Instanceof,
+    New,
+    ConstructorReference,
+    MethodReference,
+    Cast,
+    ConstructorInvocationTypeArgument,
+    MethodInvocationTypeArgument,
+    ConstructorReferenceTypeArgument,
+    MethodReferenceTypeArgument,
+}
+
+/// The part of a type annotation that identifies the type that it annotates.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TargetInfo {
+    TypeParameter {
+        type_parameter_index: u8,
+    },
+    Supertype {
+        supertype_index: u16,
+    },
+    TypeParameterBound {
+        type_parameter_index: u8,
+        bound_index: u8,
+    },
+    Empty,
+    FormalParameter {
+        formal_parameter_index: u8,
+    },
+    Throws {
+        throws_type_index: u16,
+    },
+    Localvar {
+        table: Vec<LocalvarTargetEntry>,
+    },
+    Catch {
+        exception_table_index: u16,
+    },
+    Offset {
+        offset: u16,
+    },
+    TypeArgument {
+        offset: u16,
+        type_argument_index: u8,
+    },
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LocalvarTargetEntry {
+    pub start_pc: u16,
+    pub length: u16,
+    pub index: u16,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TypePathEntry {
+    pub type_path_kind: u8,
+    pub type_argument_index: u8,
+}
+
+impl Annotation {
+    /// Parses the given `RuntimeVisibleAnnotations` or
+    /// `RuntimeInvisibleAnnotations` attribute info bytes into the
+    /// annotations that they describe.
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Vec<Annotation>> {
+        let mut reader = bytes;
+
+        let num_annotations =
+            parsing::read_u16(&mut reader).context("Failed to read annotations count.")?;
+
+        let mut annotations = Vec::with_capacity(num_annotations as usize);
+        for _ in 0..num_annotations {
+            annotations.push(read_annotation(&mut reader)?);
+        }
+
+        Ok(annotations)
+    }
+}
+
+impl TypeAnnotation {
+    /// Parses the given `RuntimeVisibleTypeAnnotations` or
+    /// `RuntimeInvisibleTypeAnnotations` attribute info bytes into the type
+    /// annotations that they describe.
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Vec<TypeAnnotation>> {
+        let mut reader = bytes;
+
+        let num_annotations =
+            parsing::read_u16(&mut reader).context("Failed to read type annotations count.")?;
+
+        let mut annotations = Vec::with_capacity(num_annotations as usize);
+        for _ in 0..num_annotations {
+            annotations.push(read_type_annotation(&mut reader)?);
+        }
+
+        Ok(annotations)
+    }
+}
+
+impl TargetInfo {
+    /// Returns the offset of the instruction that the annotated type appears
+    /// in, for type annotations of a `Code` attribute that target a single
+    /// instruction (ex. a cast or `instanceof`).
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::TargetInfo;
+    /// #
+    /// let target_info = TargetInfo::TypeArgument {
+    ///     offset: 1,
+    ///     type_argument_index: 0,
+    /// };
+    ///
+    /// assert_eq!(Some(1), target_info.offset());
+    /// assert_eq!(None, TargetInfo::Empty.offset());
+    /// ```
+    pub fn offset(&self) -> Option<u16> {
+        match *self {
+            TargetInfo::Offset { offset } | TargetInfo::TypeArgument { offset, .. } => Some(offset),
+            _ => None,
+        }
+    }
+}
+
+impl TargetType {
+    /// Returns the target type that corresponds to the given `target_type`
+    /// value of a type annotation.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::TargetType;
+    /// #
+    /// assert_eq!(Some(TargetType::Field), TargetType::from_u8(0x13));
+    /// assert_eq!(Some(TargetType::Cast), TargetType::from_u8(0x47));
+    /// assert_eq!(None, TargetType::from_u8(0x30));
+    /// ```
+    pub fn from_u8(target_type: u8) -> Option<TargetType> {
+        use TargetType::*;
+
+        match target_type {
+            0x00 => Some(ClassTypeParameter),
+            0x01 => Some(MethodTypeParameter),
+            0x10 => Some(ClassExtends),
+            0x11 => Some(ClassTypeParameterBound),
+            0x12 => Some(MethodTypeParameterBound),
+            0x13 => Some(Field),
+            0x14 => Some(MethodReturn),
+            0x15 => Some(MethodReceiver),
+            0x16 => Some(MethodFormalParameter),
+            0x17 => Some(Throws),
+            0x40 => Some(LocalVariable),
+            0x41 => Some(ResourceVariable),
+            0x42 => Some(ExceptionParameter),
+            0x43 => Some(Instanceof),
+            0x44 => Some(New),
+            0x45 => Some(ConstructorReference),
+            0x46 => Some(MethodReference),
+            0x47 => Some(Cast),
+            0x48 => Some(ConstructorInvocationTypeArgument),
+            0x49 => Some(MethodInvocationTypeArgument),
+            0x4A => Some(ConstructorReferenceTypeArgument),
+            0x4B => Some(MethodReferenceTypeArgument),
+            _ => None,
+        }
+    }
+
+    /// Returns the `target_type` value of a type annotation that corresponds to
+    /// the target type. This is the inverse of `from_u8`.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::TargetType;
+    /// #
+    /// assert_eq!(0x13, TargetType::Field.to_u8());
+    /// assert_eq!(0x47, TargetType::Cast.to_u8());
+    /// ```
+    pub fn to_u8(self) -> u8 {
+        use TargetType::*;
+
+        match self {
+            ClassTypeParameter => 0x00,
+            MethodTypeParameter => 0x01,
+            ClassExtends => 0x10,
+            ClassTypeParameterBound => 0x11,
+            MethodTypeParameterBound => 0x12,
+            Field => 0x13,
+            MethodReturn => 0x14,
+            MethodReceiver => 0x15,
+            MethodFormalParameter => 0x16,
+            Throws => 0x17,
+            LocalVariable => 0x40,
+            ResourceVariable => 0x41,
+            ExceptionParameter => 0x42,
+            Instanceof => 0x43,
+            New => 0x44,
+            ConstructorReference => 0x45,
+            MethodReference => 0x46,
+            Cast => 0x47,
+            ConstructorInvocationTypeArgument => 0x48,
+            MethodInvocationTypeArgument => 0x49,
+            ConstructorReferenceTypeArgument => 0x4A,
+            MethodReferenceTypeArgument => 0x4B,
+        }
+    }
+}
+
+pub(crate) fn read_annotation<R: Read>(file: &mut R) -> io::Result<Annotation> {
+    let type_index = parsing::read_cp_index(file).context("Failed to read annotation type.")?;
+    let num_element_value_pairs =
+        parsing::read_u16(file).context("Failed to read annotation element count.")?;
+
+    let mut element_value_pairs = Vec::with_capacity(num_element_value_pairs as usize);
+    for _ in 0..num_element_value_pairs {
+        let element_name_index =
+            parsing::read_cp_index(file).context("Failed to read annotation element name.")?;
+        let value = read_element_value(file)?;
+
+        element_value_pairs.push(ElementValuePair {
+            element_name_index,
+            value,
+        });
+    }
+
+    Ok(Annotation {
+        type_index,
+        element_value_pairs,
+    })
+}
+
+pub(crate) fn read_element_value<R: Read>(file: &mut R) -> io::Result<ElementValue> {
+    let tag = parsing::read_u8(file).context("Failed to read element value tag.")? as char;
+
+    let value = match tag {
+        'B' | 'C' | 'D' | 'F' | 'I' | 'J' | 'S' | 'Z' | 's' => ElementValue::Const {
+            tag,
+            const_value_index: parsing::read_cp_index(file)?,
+        },
+        'e' => ElementValue::EnumConst {
+            type_name_index: parsing::read_cp_index(file)?,
+            const_name_index: parsing::read_cp_index(file)?,
+        },
+        'c' => ElementValue::Class {
+            class_info_index: parsing::read_cp_index(file)?,
+        },
+        '@' => ElementValue::Annotation(read_annotation(file)?),
+        '[' => {
+            let num_values = parsing::read_u16(file)?;
+
+            let mut values = Vec::with_capacity(num_values as usize);
+            for _ in 0..num_values {
+                values.push(read_element_value(file)?);
+            }
+
+            ElementValue::Array(values)
+        }
+        _ => {
+            return Err(io_err(format!(
+                "Encountered unknown element value tag: {}",
+                tag
+            )))
+        }
+    };
+
+    Ok(value)
+}
+
+pub(crate) fn read_type_annotation<R: Read>(file: &mut R) -> io::Result<TypeAnnotation> {
+    use TargetType::*;
+
+    let target_type_value =
+        parsing::read_u8(file).context("Failed to read type annotation target type.")?;
+    let target_type = TargetType::from_u8(target_type_value).ok_or_else(|| {
+        io_err(format!(
+            "Encountered unknown type annotation target type: {:#X}",
+            target_type_value
+        ))
+    })?;
+
+    let target_info = match target_type {
+        ClassTypeParameter | MethodTypeParameter => TargetInfo::TypeParameter {
+            type_parameter_index: parsing::read_u8(file)?,
+        },
+        ClassExtends => TargetInfo::Supertype {
+            supertype_index: parsing::read_u16(file)?,
+        },
+        ClassTypeParameterBound | MethodTypeParameterBound => TargetInfo::TypeParameterBound {
+            type_parameter_index: parsing::read_u8(file)?,
+            bound_index: parsing::read_u8(file)?,
+        },
+        Field | MethodReturn | MethodReceiver => TargetInfo::Empty,
+        MethodFormalParameter => TargetInfo::FormalParameter {
+            formal_parameter_index: parsing::read_u8(file)?,
+        },
+        Throws => TargetInfo::Throws {
+            throws_type_index: parsing::read_u16(file)?,
+        },
+        LocalVariable | ResourceVariable => {
+            let table_length = parsing::read_u16(file)?;
+
+            let mut table = Vec::with_capacity(table_length as usize);
+            for _ in 0..table_length {
+                table.push(LocalvarTargetEntry {
+                    start_pc: parsing::read_u16(file)?,
+                    length: parsing::read_u16(file)?,
+                    index: parsing::read_u16(file)?,
+                });
+            }
+
+            TargetInfo::Localvar { table }
+        }
+        ExceptionParameter => TargetInfo::Catch {
+            exception_table_index: parsing::read_u16(file)?,
+        },
+        Instanceof | New | ConstructorReference | MethodReference => TargetInfo::Offset {
+            offset: parsing::read_u16(file)?,
+        },
+        Cast
+        | ConstructorInvocationTypeArgument
+        | MethodInvocationTypeArgument
+        | ConstructorReferenceTypeArgument
+        | MethodReferenceTypeArgument => TargetInfo::TypeArgument {
+            offset: parsing::read_u16(file)?,
+            type_argument_index: parsing::read_u8(file)?,
+        },
+    };
+
+    let path_length = parsing::read_u8(file).context("Failed to read type path.")?;
+
+    let mut target_path = Vec::with_capacity(path_length as usize);
+    for _ in 0..path_length {
+        target_path.push(TypePathEntry {
+            type_path_kind: parsing::read_u8(file)?,
+            type_argument_index: parsing::read_u8(file)?,
+        });
+    }
+
+    let annotation = read_annotation(file)?;
+
+    Ok(TypeAnnotation {
+        target_type,
+        target_info,
+        target_path,
+        annotation,
+    })
+}
@@ -0,0 +1,54 @@
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tokio::io::{AsyncRead, ReadBuf};
+
+use class_file::ClassFile;
+use parsing;
+
+const READ_CHUNK_SIZE: usize = 4096;
+
+/// A future that reads an async reader to its end and then parses the read
+/// bytes as a class file.
+pub struct ReadClassFile<'a, R: 'a> {
+    file: &'a mut R,
+    bytes: Vec<u8>,
+}
+
+impl<'a, R: AsyncRead + Unpin> ReadClassFile<'a, R> {
+    pub fn new(file: &'a mut R) -> ReadClassFile<'a, R> {
+        ReadClassFile {
+            file,
+            bytes: Vec::new(),
+        }
+    }
+}
+
+impl<'a, R: AsyncRead + Unpin> Future for ReadClassFile<'a, R> {
+    type Output = io::Result<ClassFile>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        loop {
+            let mut chunk = [0; READ_CHUNK_SIZE];
+            let mut buf = ReadBuf::new(&mut chunk);
+
+            match Pin::new(&mut *this.file).poll_read(cx, &mut buf) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                Poll::Ready(Ok(())) => {
+                    if buf.filled().is_empty() {
+                        break;
+                    }
+
+                    this.bytes.extend_from_slice(buf.filled());
+                }
+            }
+        }
+
+        Poll::Ready(parsing::read_class_file(&mut this.bytes.as_slice()))
+    }
+}
@@ -1,16 +1,135 @@
-use std::{convert::TryInto, io};
+use std::{
+    collections::{BTreeSet, HashSet},
+    convert::TryInto,
+    io,
+};
 
+use annotation::*;
 use bytecode::*;
+use debug_info::*;
 
 use crate::ClassFile;
-use {parsing, ConstantPoolIndex};
+use constant_pool::*;
+use control_flow::{self, ControlFlowGraph};
+use method::Method;
+use util::{io_err, Contextable};
+use {bytecode, maxes, parsing, writing};
 
 const EXCEPTION_ENTRY_LENGTH: usize = 8;
 
+/// The largest number of bytes of code that a method can have, since the
+/// offsets into the code are stored as u2 values.
+///
+/// See Chapter 4.7.3 of the JVM specification for details.
+///
+/// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-4.html#jvms-4.7.3
+const MAX_CODE_LENGTH: usize = 65535;
+
+const STRING_CONCAT_FACTORY: &str = "java/lang/invoke/StringConcatFactory";
+const MAKE_CONCAT_WITH_CONSTANTS: &str = "makeConcatWithConstants";
+
+const READ_MAX_STACK: &str = "Failed to read max stack.";
+const READ_MAX_LOCALS: &str = "Failed to read max locals.";
+const READ_CODE: &str = "Failed to read code.";
+const READ_EXCEPTION_TABLE: &str = "Failed to read exception table.";
+const READ_CODE_ATTRIBUTES: &str = "Failed to read code attributes.";
+
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct Attribute {
     pub attribute_name_index: ConstantPoolIndex,
     pub info: Vec<u8>,
+
+    /// The decoded form of the attribute, if it is of a known kind and the
+    /// attributes of the class file have been decoded.
+    ///
+    /// The info bytes remain the source of truth, and are what get written
+    /// out when the class file is written.
+    ///
+    /// See `ClassFile::decode_attributes` for details.
+    pub structured: Option<StructuredAttribute>,
+
+    /// The offset of the attribute from the start of the class file that it was
+    /// parsed from, if offsets were recorded while parsing. See
+    /// `ParseOptions::record_offsets` for details.
+    pub file_offset: Option<usize>,
+}
+
+/// The decoded form of an attribute of a known kind.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum StructuredAttribute {
+    Code(Code),
+    ConstantValue(ConstantPoolIndex),
+    Exceptions(Vec<ConstantPoolIndex>),
+    Signature(ConstantPoolIndex),
+    SourceFile(ConstantPoolIndex),
+}
+
+impl StructuredAttribute {
+    /// Decodes the given attribute, if it is of a known kind.
+    ///
+    /// Returns `None` if the attribute is not of a known kind, and an error if
+    /// its info bytes are malformed.
+    ///
+    /// Code is decoded leniently, so any opcode that is not supported is
+    /// decoded as an `Unknown` instruction. See `Code::from_bytes_lenient`.
+    pub fn decode(
+        attribute: &Attribute,
+        class_file: &ClassFile,
+    ) -> io::Result<Option<StructuredAttribute>> {
+        let mut info = attribute.info.as_slice();
+
+        let structured = match attribute.name(class_file) {
+            "Code" => {
+                let mut code = Code::from_bytes_lenient(&attribute.info)?;
+                code.attributes.decode(class_file)?;
+
+                StructuredAttribute::Code(code)
+            }
+            "ConstantValue" => {
+                StructuredAttribute::ConstantValue(parsing::read_cp_index(&mut info)?)
+            }
+            "Exceptions" => {
+                let count = parsing::read_u16(&mut info)?;
+
+                let exceptions = (0..count)
+                    .map(|_| parsing::read_cp_index(&mut info))
+                    .collect::<io::Result<Vec<ConstantPoolIndex>>>()?;
+
+                StructuredAttribute::Exceptions(exceptions)
+            }
+            "Signature" => StructuredAttribute::Signature(parsing::read_cp_index(&mut info)?),
+            "SourceFile" => StructuredAttribute::SourceFile(parsing::read_cp_index(&mut info)?),
+            _ => return Ok(None),
+        };
+
+        Ok(Some(structured))
+    }
+}
+
+impl Attribute {
+    /// Returns the name of the attribute.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let code_attribute = &class_file.methods[0].attributes.attributes[0];
+    ///
+    /// assert_eq!("Code", code_attribute.name(&class_file));
+    /// ```
+    pub fn name<'a>(&self, class_file: &'a ClassFile) -> &'a str {
+        class_file.get_constant_utf8(self.attribute_name_index)
+    }
+
+    /// Returns the offset of the attribute from the start of the class file that it
+    /// was parsed from, or `None` if offsets were not recorded. See
+    /// `ParseOptions::record_offsets` for details.
+    pub fn file_offset(&self) -> Option<usize> {
+        self.file_offset
+    }
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -19,6 +138,26 @@ pub struct AttributeSet {
 }
 
 impl AttributeSet {
+    /// Decodes each of the attributes that is of a known kind into its
+    /// structured form.
+    ///
+    /// See `ClassFile::decode_attributes` for details.
+    pub fn decode(&mut self, class_file: &ClassFile) -> io::Result<()> {
+        for attr in self.attributes.iter_mut() {
+            attr.structured = match StructuredAttribute::decode(attr, class_file) {
+                Ok(structured) => structured,
+                Err(e) => {
+                    let description =
+                        format!("Failed to decode {} attribute.", attr.name(class_file));
+
+                    return Err(e).context(description);
+                }
+            };
+        }
+
+        Ok(())
+    }
+
     /// Find an attribute with the specified name
     pub fn find_attribute<T: AsRef<str>>(
         &self,
@@ -26,9 +165,9 @@ impl AttributeSet {
         attribute_name: T,
     ) -> Option<&Attribute> {
         // we can index this more efficiently
-        self.attributes.iter().find(|attr| {
-            class_file.get_constant_utf8(attr.attribute_name_index) == attribute_name.as_ref()
-        })
+        self.attributes
+            .iter()
+            .find(|attr| attr.name(class_file) == attribute_name.as_ref())
     }
 
     pub fn get_signature(&self, class_file: &ClassFile) -> Option<String> {
@@ -44,9 +183,115 @@ impl AttributeSet {
             class_file.get_constant_utf8(index as usize).to_string()
         })
     }
+
+    /// Returns the type annotations held by the `RuntimeVisibleTypeAnnotations`
+    /// attribute, or an empty list if there is no such attribute.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::{ClassFile, TargetType};
+    /// #
+    /// let mut file = File::open("classes/TypeAnnotated.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let field = &class_file.fields[0];
+    /// let annotations = field
+    ///     .attributes
+    ///     .get_visible_type_annotations(&class_file)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(TargetType::Field, annotations[0].target_type);
+    /// ```
+    pub fn get_visible_type_annotations(
+        &self,
+        class_file: &ClassFile,
+    ) -> io::Result<Vec<TypeAnnotation>> {
+        self.get_type_annotations(class_file, "RuntimeVisibleTypeAnnotations")
+    }
+
+    /// Returns the type annotations held by the
+    /// `RuntimeInvisibleTypeAnnotations` attribute, or an empty list if there
+    /// is no such attribute.
+    pub fn get_invisible_type_annotations(
+        &self,
+        class_file: &ClassFile,
+    ) -> io::Result<Vec<TypeAnnotation>> {
+        self.get_type_annotations(class_file, "RuntimeInvisibleTypeAnnotations")
+    }
+
+    /// Returns the entries of the `LineNumberTable` attributes, or an empty
+    /// list if there are no such attributes.
+    ///
+    /// A Code attribute may have several `LineNumberTable` attributes, in which
+    /// case their entries are concatenated.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let code = class_file.methods[0].get_code(&class_file).unwrap().unwrap();
+    /// let line_numbers = code.attributes.get_line_number_table(&class_file).unwrap();
+    ///
+    /// assert_eq!(1, line_numbers[0].line_number);
+    /// ```
+    pub fn get_line_number_table(
+        &self,
+        class_file: &ClassFile,
+    ) -> io::Result<Vec<LineNumberTableEntry>> {
+        let mut entries = Vec::new();
+
+        for attr in self.attributes.iter() {
+            if attr.name(class_file) == "LineNumberTable" {
+                entries.extend(
+                    LineNumberTableEntry::from_bytes(&attr.info)
+                        .context("Failed to read LineNumberTable attribute.")?,
+                );
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// Returns the entries of the `LocalVariableTable` attributes, or an empty
+    /// list if there are no such attributes.
+    ///
+    /// A Code attribute may have several `LocalVariableTable` attributes, in
+    /// which case their entries are concatenated.
+    pub fn get_local_variable_table(
+        &self,
+        class_file: &ClassFile,
+    ) -> io::Result<Vec<LocalVariableTableEntry>> {
+        let mut entries = Vec::new();
+
+        for attr in self.attributes.iter() {
+            if attr.name(class_file) == "LocalVariableTable" {
+                entries.extend(
+                    LocalVariableTableEntry::from_bytes(&attr.info)
+                        .context("Failed to read LocalVariableTable attribute.")?,
+                );
+            }
+        }
+
+        Ok(entries)
+    }
+
+    fn get_type_annotations(
+        &self,
+        class_file: &ClassFile,
+        attribute_name: &str,
+    ) -> io::Result<Vec<TypeAnnotation>> {
+        match self.find_attribute(class_file, attribute_name) {
+            Some(attr) => TypeAnnotation::from_bytes(&attr.info)
+                .context(format!("Failed to read {} attribute.", attribute_name)),
+            None => Ok(vec![]),
+        }
+    }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Code {
     pub max_stack: u16,
     pub max_locals: u16,
@@ -56,47 +301,721 @@ pub struct Code {
 }
 
 impl Code {
+    /// Parses the given Code attribute info bytes.
+    ///
+    /// Returns an error if the bytes are truncated or if any of the lengths
+    /// they declare would run past the end of the bytes.
     pub fn from_bytes(bytes: &[u8]) -> io::Result<Code> {
-        let max_stack = u16::from_be_bytes([bytes[0], bytes[1]]);
-        let max_locals = u16::from_be_bytes([bytes[2], bytes[3]]);
+        read_code(bytes, Bytecode::from_bytes).map(|(code, _)| code)
+    }
+
+    /// Parses the given Code attribute info bytes, decoding any opcode that
+    /// is not supported as an `Unknown` instruction rather than panicking.
+    ///
+    /// See `Bytecode::from_bytes_lenient` for details.
+    pub fn from_bytes_lenient(bytes: &[u8]) -> io::Result<Code> {
+        read_code(bytes, Bytecode::from_bytes_lenient).map(|(code, _)| code)
+    }
+
+    /// Converts the code into the Code attribute info bytes that represent
+    /// it. This is the inverse of `from_bytes`.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::{AttributeSet, Code};
+    /// # use jvm_class_file_parser::Bytecode::*;
+    /// #
+    /// let code = Code {
+    ///     max_stack: 0,
+    ///     max_locals: 0,
+    ///     code: vec![(0, Return)],
+    ///     exception_table: vec![],
+    ///     attributes: AttributeSet { attributes: vec![] },
+    /// };
+    ///
+    /// let bytes = code.to_bytes().unwrap();
+    ///
+    /// assert_eq!(code, Code::from_bytes(&bytes).unwrap());
+    /// ```
+    pub fn to_bytes(&self) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+
+        writing::write_code(&mut bytes, self)?;
+
+        Ok(bytes)
+    }
+
+    /// Returns the type annotations held by the `RuntimeVisibleTypeAnnotations`
+    /// attribute of the code, or an empty list if there is no such attribute.
+    ///
+    /// These annotate the types used within the body of the method, such as
+    /// those of casts, local variables, and caught exceptions.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::{ClassFile, TargetType};
+    /// #
+    /// let mut file = File::open("classes/TypeAnnotated.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let cast = class_file.find_method("cast", "(Ljava/lang/Object;)Ljava/lang/Object;").unwrap();
+    /// let code = cast.get_code(&class_file).unwrap().unwrap();
+    /// let annotations = code.get_visible_type_annotations(&class_file).unwrap();
+    ///
+    /// assert_eq!(TargetType::Cast, annotations[0].target_type);
+    /// ```
+    pub fn get_visible_type_annotations(
+        &self,
+        class_file: &ClassFile,
+    ) -> io::Result<Vec<TypeAnnotation>> {
+        self.attributes.get_visible_type_annotations(class_file)
+    }
+
+    /// Returns the type annotations held by the
+    /// `RuntimeInvisibleTypeAnnotations` attribute of the code, or an empty
+    /// list if there is no such attribute.
+    pub fn get_invisible_type_annotations(
+        &self,
+        class_file: &ClassFile,
+    ) -> io::Result<Vec<TypeAnnotation>> {
+        self.attributes.get_invisible_type_annotations(class_file)
+    }
+
+    /// Returns the instructions whose offsets fall within the range from
+    /// `start_pc` (inclusive) to `end_pc` (exclusive), as used by the ranges
+    /// of exception table entries.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::{AttributeSet, Code};
+    /// # use jvm_class_file_parser::Bytecode::*;
+    /// #
+    /// let code = Code {
+    ///     max_stack: 1,
+    ///     max_locals: 1,
+    ///     code: vec![(0, Aload_0), (1, Invokespecial(1)), (4, Return)],
+    ///     exception_table: vec![],
+    ///     attributes: AttributeSet { attributes: vec![] },
+    /// };
+    ///
+    /// assert_eq!(
+    ///     vec![&(1, Invokespecial(1))],
+    ///     code.instructions_in_range(1, 4)
+    /// );
+    /// ```
+    pub fn instructions_in_range(&self, start_pc: u16, end_pc: u16) -> Vec<&(usize, Bytecode)> {
+        let start_pc = start_pc as usize;
+        let end_pc = end_pc as usize;
+
+        self.code
+            .iter()
+            .skip_while(|(pc, _)| *pc < start_pc)
+            .take_while(|(pc, _)| *pc < end_pc)
+            .collect()
+    }
+
+    /// Returns the try/catch blocks of the code, which are formed by grouping
+    /// the exception table entries that protect the same range of code.
+    ///
+    /// The blocks are in the order of the first exception table entry of each
+    /// block, and the handlers of each block are in the order that they are
+    /// checked in.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/ExceptionThrows.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let method = class_file.find_method("throwException", "()V").unwrap();
+    /// let code = method.get_code(&class_file).unwrap().unwrap();
+    ///
+    /// let blocks = code.try_catch_blocks(&class_file);
+    ///
+    /// assert_eq!("java/lang/Exception", blocks[0].handlers[0].catch_type);
+    /// ```
+    pub fn try_catch_blocks(&self, class_file: &ClassFile) -> Vec<TryCatchBlock> {
+        let mut blocks: Vec<TryCatchBlock> = vec![];
+
+        for entry in self.exception_table.iter() {
+            let catch_type = match entry.catch_type {
+                0 => "any".to_string(),
+                index => class_file
+                    .get_constant_class_str(index as usize)
+                    .to_string(),
+            };
+
+            let handler = CatchHandler {
+                handler_pc: entry.handler_pc,
+                catch_type,
+            };
+
+            match blocks
+                .iter_mut()
+                .find(|block| block.start_pc == entry.start_pc && block.end_pc == entry.end_pc)
+            {
+                Some(block) => block.handlers.push(handler),
+                None => blocks.push(TryCatchBlock {
+                    start_pc: entry.start_pc,
+                    end_pc: entry.end_pc,
+                    handlers: vec![handler],
+                }),
+            }
+        }
+
+        blocks
+    }
+
+    /// Builds the control flow graph of the code, splitting it into basic
+    /// blocks linked by their branches, fall-throughs, and exception
+    /// handlers.
+    ///
+    /// Returns an error if the code uses legacy subroutines (`jsr`, `jsr_w`,
+    /// or `ret`), since their control flow can not be found without a data
+    /// flow analysis, or if it branches into the middle of an instruction.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let code = class_file.methods[0].get_code(&class_file).unwrap().unwrap();
+    /// let graph = code.control_flow_graph().unwrap();
+    ///
+    /// assert_eq!(1, graph.blocks.len());
+    /// assert_eq!((0, 5), (graph.blocks[0].start_pc, graph.blocks[0].end_pc));
+    /// ```
+    pub fn control_flow_graph(&self) -> io::Result<ControlFlowGraph> {
+        control_flow::build_control_flow_graph(self)
+    }
+
+    /// Computes the maximum operand stack depth and number of local variable
+    /// slots needed by the code, as the body of the given method. These are
+    /// returned as a `(max_stack, max_locals)` pair.
+    ///
+    /// Returns an error if the stack depth at an instruction differs between
+    /// the paths that reach it, or if an instruction would pop more values
+    /// than are on the stack.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let constructor = &class_file.methods[0];
+    /// let code = constructor.get_code(&class_file).unwrap().unwrap();
+    ///
+    /// assert_eq!((1, 1), code.compute_maxes(&class_file, constructor).unwrap());
+    /// ```
+    pub fn compute_maxes(&self, class_file: &ClassFile, method: &Method) -> io::Result<(u16, u16)> {
+        maxes::compute_maxes(self, class_file, method)
+    }
+
+    /// Returns an iterator over the instructions of the code, along with their
+    /// offsets and the offset of the instruction that execution falls through
+    /// to after them.
+    ///
+    /// The fall-through offset is `None` for instructions that never fall
+    /// through, such as `goto`, `athrow`, and the return instructions, and for
+    /// the last instruction of the code.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::{AttributeSet, Code};
+    /// # use jvm_class_file_parser::Bytecode::*;
+    /// #
+    /// let code = Code {
+    ///     max_stack: 1,
+    ///     max_locals: 1,
+    ///     code: vec![(0, Aload_0), (1, Invokespecial(1)), (4, Return)],
+    ///     exception_table: vec![],
+    ///     attributes: AttributeSet { attributes: vec![] },
+    /// };
+    ///
+    /// assert_eq!(
+    ///     vec![
+    ///         (0, &Aload_0, Some(1)),
+    ///         (1, &Invokespecial(1), Some(4)),
+    ///         (4, &Return, None),
+    ///     ],
+    ///     code.iter_with_next().collect::<Vec<_>>()
+    /// );
+    /// ```
+    pub fn iter_with_next(&self) -> impl Iterator<Item = (usize, &Bytecode, Option<usize>)> {
+        self.code
+            .iter()
+            .enumerate()
+            .map(move |(i, (pc, bytecode))| {
+                let next_pc = if bytecode.falls_through() {
+                    self.code.get(i + 1).map(|(next_pc, _)| *next_pc)
+                } else {
+                    None
+                };
+
+                (*pc, bytecode, next_pc)
+            })
+    }
+
+    /// Checks that each of the `ldc`, `ldc_w`, and `ldc2_w` instructions in
+    /// the code references a kind of constant that it is allowed to load.
+    ///
+    /// `ldc` and `ldc_w` may only load Integer, Float, String, Class,
+    /// MethodType, MethodHandle, and non long or double Dynamic constants,
+    /// while `ldc2_w` may only load Long, Double, and long or double Dynamic
+    /// constants.
+    ///
+    /// See the `ldc`, `ldc_w`, and `ldc2_w` sections of Chapter 6.5 of the JVM
+    /// specification for details.
+    ///
+    /// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-6.html#jvms-6.5.ldc
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/HelloWorld.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let main = &class_file.methods[1];
+    /// let code = main.get_code(&class_file).unwrap().unwrap();
+    ///
+    /// assert!(code.validate_constant_loads(&class_file).is_ok());
+    /// ```
+    pub fn validate_constant_loads(&self, class_file: &ClassFile) -> io::Result<()> {
+        use Bytecode::*;
+        use ConstantPoolEntry::*;
+
+        for (pc, bytecode) in self.code.iter() {
+            let index = match bytecode.loaded_constant_index() {
+                Some(index) => index,
+                None => continue,
+            };
+            let is_wide = matches!(*bytecode, Ldc2_w(_));
+
+            if index == 0 || index > class_file.constant_pool.len() {
+                return Err(io_err(format!(
+                    "The constant load at pc {} references an invalid constant pool index: {}",
+                    pc, index
+                )));
+            }
+
+            let constant = class_file.get_constant(index);
+            let is_valid = match *constant {
+                ConstantInteger { .. }
+                | ConstantFloat { .. }
+                | ConstantString { .. }
+                | ConstantClass { .. }
+                | ConstantMethodType { .. }
+                | ConstantMethodHandle { .. } => !is_wide,
+                ConstantLong { .. } | ConstantDouble { .. } => is_wide,
+                ConstantDynamic {
+                    name_and_type_index,
+                    ..
+                } => {
+                    let context = format!(
+                        "Failed to resolve the dynamic constant loaded at pc {}.",
+                        pc
+                    );
 
-        let code_length = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as usize;
+                    let name_and_type = CpIndex::new(name_and_type_index as ConstantPoolIndex)
+                        .and_then(|index| index.resolve(class_file))
+                        .context(context.as_str())?;
 
-        let code_start = 8;
-        let code_end = code_start + code_length;
-        let code_bytes = &bytes[code_start..code_end];
+                    let is_category_2 = match *name_and_type {
+                        ConstantNameAndType {
+                            descriptor_index, ..
+                        } => {
+                            let descriptor = CpIndex::new(descriptor_index)
+                                .and_then(|index| index.resolve(class_file))
+                                .context(context.as_str())?;
 
-        let code = Bytecode::from_bytes(code_bytes);
+                            match *descriptor {
+                                ConstantUtf8 { ref string } => string == "J" || string == "D",
+                                _ => false,
+                            }
+                        }
+                        _ => false,
+                    };
 
-        let exception_table_length =
-            u16::from_be_bytes([bytes[code_end], bytes[code_end + 1]]) as usize;
+                    is_category_2 == is_wide
+                }
+                _ => false,
+            };
+
+            if !is_valid {
+                return Err(io_err(format!(
+                    "The constant load \"{}\" at pc {} references a constant that it cannot load. Found: {:?}",
+                    bytecode.to_string(*pc as u16),
+                    pc,
+                    constant
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that each branch instruction in the code branches to the start
+    /// of an instruction.
+    ///
+    /// The offsets of `goto`, `jsr`, and the conditional branches are 2 bytes,
+    /// so an instruction that is meant to branch more than 32767 bytes away
+    /// wraps around to some other offset. Such branches are reported along
+    /// with the wide form of instruction that should be used instead.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::{AttributeSet, Bytecode::*, Code};
+    /// #
+    /// let code = Code {
+    ///     max_stack: 0,
+    ///     max_locals: 0,
+    ///     code: vec![(0, Goto(3)), (3, Return)],
+    ///     exception_table: vec![],
+    ///     attributes: AttributeSet { attributes: vec![] },
+    /// };
+    ///
+    /// assert!(code.validate_branch_offsets().is_ok());
+    /// ```
+    pub fn validate_branch_offsets(&self) -> io::Result<()> {
+        use Bytecode::*;
+
+        let instruction_starts: HashSet<usize> = self.code.iter().map(|(pc, _)| *pc).collect();
+        let is_instruction_start =
+            |target: i64| target >= 0 && instruction_starts.contains(&(target as usize));
+
+        for (pc, bytecode) in self.code.iter() {
+            let target = match bytecode.branch_target(*pc) {
+                Some(target) => target as i64,
+                None => continue,
+            };
+
+            if is_instruction_start(target) {
+                continue;
+            }
+
+            let mnemonic = bytecode.to_string(0);
+            let mnemonic = mnemonic.split_whitespace().next().unwrap_or("");
+
+            let wide_form = match *bytecode {
+                Goto(_) => Some("goto_w"),
+                Jsr(_) => Some("jsr_w"),
+                Ifeq(_) | Ifne(_) => Some("the opposite condition branching over a goto_w"),
+                _ => None,
+            };
+
+            // the intended target is a multiple of 2^16 away from the one
+            // that the truncated offset points to
+            let intended_target = [target - 0x1_0000, target + 0x1_0000]
+                .iter()
+                .cloned()
+                .find(|t| is_instruction_start(*t));
+
+            if let (Some(wide_form), Some(intended_target)) = (wide_form, intended_target) {
+                return Err(io_err(format!(
+                    "The {} at pc {} has an offset of {} to pc {}, which does not fit in 2 bytes. Use {} instead.",
+                    mnemonic,
+                    pc,
+                    intended_target - *pc as i64,
+                    intended_target,
+                    wide_form
+                )));
+            }
+
+            return Err(io_err(format!(
+                "The {} at pc {} branches to {}, which is not the start of an instruction.",
+                mnemonic, pc, target
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the local variable slots that are loaded from, stored to,
+    /// incremented, or returned to by the instructions of the code. Both of
+    /// the slots of a `long` or `double` variable are included.
+    ///
+    /// This does not include the slots of parameters that are never used by
+    /// the code. See `Method::used_local_slots` for a version that does, and
+    /// that also supports the instructions that cannot be decoded.
+    ///
+    /// Fails if the code contains an `Unknown` instruction, since its operands
+    /// are not known.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let code = class_file.methods[0].get_code(&class_file).unwrap().unwrap();
+    ///
+    /// assert_eq!(
+    ///     vec![0],
+    ///     code.used_local_slots().unwrap().into_iter().collect::<Vec<u16>>()
+    /// );
+    /// ```
+    pub fn used_local_slots(&self) -> io::Result<BTreeSet<u16>> {
+        if let Some((pc, _)) = self
+            .code
+            .iter()
+            .find(|(_, bytecode)| matches!(*bytecode, Bytecode::Unknown(_)))
+        {
+            return Err(io_err(format!(
+                "The local variables used by the unknown instruction at pc {} are not known.",
+                pc
+            )));
+        }
+
+        bytecode::used_local_slots(&Bytecode::to_bytes(&self.code))
+    }
+
+    /// Checks that the catch type of each entry of the exception table is
+    /// either 0, meaning that it catches any exception (as is done for
+    /// `finally` blocks), or the index of a Class constant.
+    ///
+    /// See Chapter 4.7.3 of the JVM specification for details.
+    ///
+    /// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-4.html#jvms-4.7.3
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/TryFinally.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let run = class_file.find_method("run", "()V").unwrap();
+    /// let code = run.get_code(&class_file).unwrap().unwrap();
+    ///
+    /// assert!(code.validate_catch_types(&class_file).is_ok());
+    /// ```
+    pub fn validate_catch_types(&self, class_file: &ClassFile) -> io::Result<()> {
+        for entry in self.exception_table.iter() {
+            let index = entry.catch_type as usize;
+            if index == 0 {
+                continue;
+            }
 
-        let mut exception_table = Vec::with_capacity(exception_table_length);
-        for i in 0..exception_table_length {
-            let entry_start = (code_end + 2) + i * EXCEPTION_ENTRY_LENGTH;
-            let entry_end = entry_start + EXCEPTION_ENTRY_LENGTH;
+            let constant = match index
+                .checked_sub(1)
+                .and_then(|i| class_file.constant_pool.get(i))
+            {
+                Some(constant) => constant,
+                None => {
+                    return Err(io_err(format!(
+                        "The exception handler at pc {} has an invalid catch type index: {}",
+                        entry.handler_pc, index
+                    )))
+                }
+            };
+
+            if !matches!(*constant, ConstantPoolEntry::ConstantClass { .. }) {
+                return Err(io_err(format!(
+                    "The exception handler at pc {} has a catch type that is not a Class constant. Found: {:?}",
+                    entry.handler_pc, constant
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the string concatenations done by the code using
+    /// `invokedynamic` instructions bootstrapped by
+    /// `StringConcatFactory.makeConcatWithConstants`, as the offset of each
+    /// instruction along with its recipe.
+    ///
+    /// In a recipe, each `\u{1}` is replaced by an argument of the call site
+    /// and each `\u{2}` is replaced by a constant.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/StringConcat.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let greet = class_file.find_method("greet", "(Ljava/lang/String;)V").unwrap();
+    /// let code = greet.get_code(&class_file).unwrap().unwrap();
+    ///
+    /// assert_eq!(
+    ///     vec![(4, "Hello, \u{1}!".to_string())],
+    ///     code.find_string_concats(&class_file)
+    /// );
+    /// ```
+    pub fn find_string_concats(&self, class_file: &ClassFile) -> Vec<(usize, String)> {
+        use ConstantPoolEntry::*;
+
+        let bootstrap_methods = class_file.get_bootstrap_methods();
+
+        let mut concats = Vec::new();
+        for (pc, bytecode) in self.code.iter() {
+            let call_site = match *bytecode {
+                Bytecode::Invokedynamic(call_site) => call_site as usize,
+                _ => continue,
+            };
+
+            let bootstrap_method = match *class_file.get_constant(call_site) {
+                ConstantInvokeDynamic {
+                    bootstrap_method_attr_index,
+                    ..
+                } => &bootstrap_methods[bootstrap_method_attr_index as usize],
+                ref constant => panic!(
+                    "The invokedynamic at pc {} did not point to a ConstantInvokeDynamic. Found: {:?}",
+                    pc, constant
+                ),
+            };
+
+            let handle = class_file.resolve_method_handle(bootstrap_method.bootstrap_method_ref);
+            if handle.class_name != STRING_CONCAT_FACTORY
+                || handle.name != MAKE_CONCAT_WITH_CONSTANTS
+            {
+                continue;
+            }
 
-            let entry = ExceptionTableEntry::from_bytes(&bytes[entry_start..entry_end]);
+            let recipe =
+                bootstrap_method.bootstrap_arguments.first().map(|index| {
+                    match *class_file.get_constant(*index) {
+                        ConstantString { string_index } => {
+                            class_file.get_constant_utf8(string_index)
+                        }
+                        ref constant => panic!(
+                            "The string concatenation recipe was not a ConstantString. Found: {:?}",
+                            constant
+                        ),
+                    }
+                });
 
-            exception_table.push(entry);
+            if let Some(recipe) = recipe {
+                concats.push((*pc, recipe.to_string()));
+            }
         }
 
-        let attributes_start = (code_end + 2) + exception_table_length * EXCEPTION_ENTRY_LENGTH;
+        concats
+    }
+}
+
+/// A function that decodes the bytes of a method body into its instructions,
+/// such as `Bytecode::from_bytes`.
+type BytecodeDecoder = fn(&[u8]) -> io::Result<Vec<(usize, Bytecode)>>;
+
+/// Parses the given Code attribute info bytes, also returning the offset at
+/// which the nested attributes of the Code attribute start.
+fn read_code(bytes: &[u8], decode: BytecodeDecoder) -> io::Result<(Code, usize)> {
+    let mut reader = bytes;
+
+    let max_stack = parsing::read_u16(&mut reader).context(READ_MAX_STACK)?;
+    let max_locals = parsing::read_u16(&mut reader).context(READ_MAX_LOCALS)?;
+
+    let code_length = parsing::read_u32(&mut reader).context(READ_CODE)? as usize;
+    if code_length > MAX_CODE_LENGTH {
+        return Err(io_err(format!(
+            "{} The declared code length of {} exceeds the maximum code length of {}.",
+            READ_CODE, code_length, MAX_CODE_LENGTH
+        )));
+    }
+    if code_length > reader.len() {
+        return Err(io_err(format!(
+            "{} The declared code length of {} exceeds the {} remaining bytes of the attribute.",
+            READ_CODE,
+            code_length,
+            reader.len()
+        )));
+    }
+
+    let (code_bytes, rest) = reader.split_at(code_length);
+    reader = rest;
+
+    let code = decode(code_bytes).context(READ_CODE)?;
+
+    let exception_table_length =
+        parsing::read_u16(&mut reader).context(READ_EXCEPTION_TABLE)? as usize;
+
+    let mut exception_table = Vec::with_capacity(exception_table_length);
+    for _ in 0..exception_table_length {
+        let entry_bytes = parsing::read_n_bytes(&mut reader, EXCEPTION_ENTRY_LENGTH)
+            .context(READ_EXCEPTION_TABLE)?;
 
-        let mut attribute_bytes = &bytes[attributes_start..];
-        let attributes = parsing::read_attributes(&mut attribute_bytes)?;
+        let entry = ExceptionTableEntry::from_bytes(&entry_bytes);
 
-        Ok(Code {
+        exception_table.push(entry);
+    }
+
+    let attributes_start = bytes.len() - reader.len();
+
+    let budget = reader.len();
+    let attributes =
+        parsing::read_nested_attributes(&mut reader, budget).context(READ_CODE_ATTRIBUTES)?;
+
+    Ok((
+        Code {
             max_stack,
             max_locals,
             code,
             exception_table,
             attributes,
-        })
-    }
+        },
+        attributes_start,
+    ))
+}
+
+/// Parses the given Code attribute info bytes without decoding the
+/// instructions, returning the code with no instructions along with the bytes
+/// of its instructions.
+pub(crate) fn read_code_undecoded(bytes: &[u8]) -> io::Result<(Code, &[u8])> {
+    let (code, _) = read_code(bytes, |_| Ok(Vec::new()))?;
+
+    // the instructions follow the max stack, max locals, and code length,
+    // which have already been checked
+    let code_length = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as usize;
+
+    Ok((code, &bytes[8..8 + code_length]))
+}
+
+/// Returns a copy of the given Code attribute info bytes, with only the nested
+/// attributes that match the given predicate kept.
+pub(crate) fn retain_code_attributes<F: FnMut(&Attribute) -> bool>(
+    bytes: &[u8],
+    predicate: F,
+) -> io::Result<Vec<u8>> {
+    // the instructions are copied as is, so they do not need to be decoded
+    let (code, attributes_start) = read_code(bytes, |_| Ok(Vec::new()))?;
+
+    let attributes = code
+        .attributes
+        .attributes
+        .into_iter()
+        .filter(predicate)
+        .collect::<Vec<Attribute>>();
+
+    let mut new_bytes = bytes[..attributes_start].to_vec();
+    writing::write_attributes(&mut new_bytes, &attributes)?;
+
+    Ok(new_bytes)
+}
+
+/// A protected range of code along with the handlers of the exceptions thrown
+/// within it, as in a source level `try` statement and its `catch` clauses.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TryCatchBlock {
+    pub start_pc: u16,
+    pub end_pc: u16,
+    pub handlers: Vec<CatchHandler>,
+}
+
+/// A handler of the exceptions thrown within the range of a `TryCatchBlock`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CatchHandler {
+    pub handler_pc: u16,
+    /// The name of the class of the exceptions that are caught, or `"any"`
+    /// if all exceptions are caught (ex. for a `finally` clause).
+    pub catch_type: String,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ExceptionTableEntry {
     pub start_pc: u16,
     pub end_pc: u16,
@@ -119,3 +1038,121 @@ impl ExceptionTableEntry {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Code;
+
+    #[test]
+    fn code_from_bytes_with_code_length_past_end() {
+        let bytes = vec![
+            0, 1, // max stack
+            0, 1, // max locals
+            0, 0, 0, 200, // code length
+            42, 183, 0, 1, 177, // code
+        ];
+
+        let result = Code::from_bytes(&bytes);
+
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("exceeds the 5 remaining bytes"));
+    }
+
+    #[test]
+    fn code_from_bytes_with_code_length_over_maximum() {
+        let mut bytes = vec![
+            0, 1, // max stack
+            0, 1, // max locals
+            0, 1, 0, 0, // code length
+        ];
+        bytes.extend(vec![0; 65536]);
+        bytes.extend(vec![0, 0, 0, 0]);
+
+        let result = Code::from_bytes(&bytes);
+
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("code length of 65536 exceeds the maximum code length of 65535"));
+    }
+
+    #[test]
+    fn code_from_bytes_with_nested_attribute_count_too_high() {
+        let bytes = vec![
+            0, 1, // max stack
+            0, 1, // max locals
+            0, 0, 0, 1,   // code length
+            177, // code
+            0, 0, // exception table length
+            0, 2, // attributes count
+            0, 7, 0, 0, 0, 2, 0, 0, // attribute
+        ];
+
+        let result = Code::from_bytes(&bytes);
+
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains(
+            "The attribute count of 2 is too large, since only 0 bytes remain for attribute 1."
+        ));
+    }
+
+    #[test]
+    fn code_from_bytes_with_nested_attribute_length_too_long() {
+        let bytes = vec![
+            0, 1, // max stack
+            0, 1, // max locals
+            0, 0, 0, 1,   // code length
+            177, // code
+            0, 0, // exception table length
+            0, 1, // attributes count
+            0, 7, 0, 0, 0, 9, 0, 0, // attribute
+        ];
+
+        let result = Code::from_bytes(&bytes);
+
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("The declared length of 9 of attribute 0 exceeds the 2 remaining bytes."));
+    }
+
+    #[test]
+    fn code_from_bytes_with_truncated_exception_table() {
+        #[rustfmt::skip]
+        let bytes = vec![
+            0, 1, // max stack
+            0, 1, // max locals
+            0, 0, 0, 1, // code length
+            177, // code
+            0, 1, // exception table length
+            0, 0, 0, 1, // partial exception table entry
+        ];
+
+        assert!(Code::from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn code_from_bytes_with_truncated_operand() {
+        let bytes = vec![
+            0, 1, // max stack
+            0, 1, // max locals
+            0, 0, 0, 1,  // code length
+            18, // ldc, missing its operand
+            0, 0, // exception table length
+            0, 0, // attributes count
+        ];
+
+        let result = Code::from_bytes(&bytes);
+
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("The instruction at offset 0 is missing operands."));
+    }
+}
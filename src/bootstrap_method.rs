@@ -0,0 +1,64 @@
+use std::io;
+
+use method_handle::MethodHandleInfo;
+use parsing;
+use util::Contextable;
+use ConstantPoolIndex;
+
+/// A bootstrap method used by `invokedynamic` instructions and dynamically
+/// computed constants, as given by the `BootstrapMethods` attribute.
+///
+/// See Chapter 4.7.23 of the JVM specification for details.
+///
+/// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-4.html#jvms-4.7.23
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct BootstrapMethod {
+    pub bootstrap_method_ref: ConstantPoolIndex,
+    pub bootstrap_arguments: Vec<ConstantPoolIndex>,
+}
+
+impl BootstrapMethod {
+    /// Parses the given `BootstrapMethods` attribute info bytes into the
+    /// bootstrap methods that they describe.
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Vec<BootstrapMethod>> {
+        let mut reader = bytes;
+
+        let num_bootstrap_methods = parsing::read_u16(&mut reader)
+            .context("Failed to read number of bootstrap methods.")?;
+
+        let mut bootstrap_methods = Vec::with_capacity(num_bootstrap_methods as usize);
+        for _ in 0..num_bootstrap_methods {
+            let bootstrap_method_ref = parsing::read_cp_index(&mut reader)
+                .context("Failed to read bootstrap method reference.")?;
+            let num_bootstrap_arguments = parsing::read_u16(&mut reader)
+                .context("Failed to read number of bootstrap arguments.")?;
+
+            let mut bootstrap_arguments = Vec::with_capacity(num_bootstrap_arguments as usize);
+            for _ in 0..num_bootstrap_arguments {
+                bootstrap_arguments.push(
+                    parsing::read_cp_index(&mut reader)
+                        .context("Failed to read bootstrap argument.")?,
+                );
+            }
+
+            bootstrap_methods.push(BootstrapMethod {
+                bootstrap_method_ref,
+                bootstrap_arguments,
+            });
+        }
+
+        Ok(bootstrap_methods)
+    }
+}
+
+/// A resolved Dynamic or InvokeDynamic constant, consisting of the bootstrap
+/// method that computes its value and the name and type that it is given.
+#[derive(Debug, Eq, PartialEq)]
+pub struct DynamicInfo<'a> {
+    /// The index of the bootstrap method in the `BootstrapMethods` attribute.
+    pub bootstrap_method_attr_index: u16,
+    pub bootstrap_method: MethodHandleInfo<'a>,
+    pub bootstrap_arguments: Vec<ConstantPoolIndex>,
+    pub name: &'a str,
+    pub descriptor: &'a str,
+}
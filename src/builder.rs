@@ -0,0 +1,169 @@
+use std::collections::HashSet;
+
+use attribute::AttributeSet;
+use class_access::ClassAccess;
+use class_file::ClassFile;
+use constant_pool::ConstantPoolEntry;
+
+const OBJECT_CLASS: &str = "java/lang/Object";
+
+/// A release of the Java platform, which determines the class file version
+/// used by classes that target it.
+///
+/// See Table 4.1-A of the JVM specification for details.
+///
+/// https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.1-200-B.2
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum JavaVersion {
+    Java1_1,
+    Java1_2,
+    Java1_3,
+    Java1_4,
+    Java5,
+    Java6,
+    Java7,
+    Java8,
+    Java9,
+    Java10,
+    Java11,
+    Java12,
+    Java13,
+    Java14,
+    Java15,
+    Java16,
+    Java17,
+}
+
+impl JavaVersion {
+    /// Returns the major version of the class files that target the release.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::JavaVersion;
+    /// #
+    /// assert_eq!(45, JavaVersion::Java1_1.major_version());
+    /// assert_eq!(52, JavaVersion::Java8.major_version());
+    /// assert_eq!(61, JavaVersion::Java17.major_version());
+    /// ```
+    pub fn major_version(self) -> u16 {
+        use JavaVersion::*;
+
+        match self {
+            Java1_1 => 45,
+            Java1_2 => 46,
+            Java1_3 => 47,
+            Java1_4 => 48,
+            Java5 => 49,
+            Java6 => 50,
+            Java7 => 51,
+            Java8 => 52,
+            Java9 => 53,
+            Java10 => 54,
+            Java11 => 55,
+            Java12 => 56,
+            Java13 => 57,
+            Java14 => 58,
+            Java15 => 59,
+            Java16 => 60,
+            Java17 => 61,
+        }
+    }
+}
+
+/// A builder for generating new classes from scratch.
+///
+/// By default the built class is a public class that extends
+/// `java.lang.Object` and targets Java 11.
+///
+/// ```
+/// # use jvm_class_file_parser::{ClassFileBuilder, JavaVersion};
+/// #
+/// let class_file = ClassFileBuilder::new("com/example/Generated")
+///     .java_version(JavaVersion::Java8)
+///     .build();
+///
+/// assert_eq!("com/example/Generated", class_file.get_class_name());
+/// assert_eq!(52, class_file.major_version);
+/// assert_eq!(0, class_file.minor_version);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ClassFileBuilder {
+    class_name: String,
+    super_class_name: String,
+    access_flags: HashSet<ClassAccess>,
+    major_version: u16,
+    minor_version: u16,
+}
+
+impl ClassFileBuilder {
+    /// Creates a builder for a class with the given internal name (ex.
+    /// `com/example/Generated`).
+    pub fn new(class_name: &str) -> ClassFileBuilder {
+        let mut access_flags = HashSet::new();
+        access_flags.insert(ClassAccess::Public);
+        access_flags.insert(ClassAccess::Super);
+
+        ClassFileBuilder {
+            class_name: class_name.to_string(),
+            super_class_name: OBJECT_CLASS.to_string(),
+            access_flags,
+            major_version: JavaVersion::Java11.major_version(),
+            minor_version: 0,
+        }
+    }
+
+    /// Sets the class file version of the class.
+    pub fn version(mut self, major: u16, minor: u16) -> ClassFileBuilder {
+        self.major_version = major;
+        self.minor_version = minor;
+
+        self
+    }
+
+    /// Sets the class file version of the class to the one used by the given
+    /// release of Java.
+    pub fn java_version(self, java_version: JavaVersion) -> ClassFileBuilder {
+        self.version(java_version.major_version(), 0)
+    }
+
+    /// Sets the internal name of the class that the class extends.
+    pub fn super_class(mut self, super_class_name: &str) -> ClassFileBuilder {
+        self.super_class_name = super_class_name.to_string();
+
+        self
+    }
+
+    /// Sets the access flags of the class.
+    pub fn access_flags(mut self, access_flags: HashSet<ClassAccess>) -> ClassFileBuilder {
+        self.access_flags = access_flags;
+
+        self
+    }
+
+    /// Builds the class, adding the constants that it needs to its constant
+    /// pool.
+    pub fn build(self) -> ClassFile {
+        let mut class_file = ClassFile {
+            minor_version: self.minor_version,
+            major_version: self.major_version,
+            constant_pool: vec![],
+            access_flags: self.access_flags,
+            unrecognized_access_flags: 0,
+            this_class: 0,
+            super_class: 0,
+            interfaces: vec![],
+            fields: vec![],
+            methods: vec![],
+            attributes: AttributeSet { attributes: vec![] },
+        };
+
+        let name_index = class_file.intern_utf8(&self.class_name);
+        class_file.this_class =
+            class_file.add_constant(ConstantPoolEntry::ConstantClass { name_index });
+
+        let name_index = class_file.intern_utf8(&self.super_class_name);
+        class_file.super_class =
+            class_file.add_constant(ConstantPoolEntry::ConstantClass { name_index });
+
+        class_file
+    }
+}
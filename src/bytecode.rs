@@ -1,15 +1,62 @@
+use std::collections::BTreeSet;
+use std::io;
+
+use class_file::ClassFile;
+use constant_pool::ConstantPoolIndex;
+use descriptor::ClassType;
+use util::io_err;
+
+const NOP: u8 = 0;
 const ACONST_NULL: u8 = 1;
 const ICONST_0: u8 = 3;
 const ICONST_1: u8 = 4;
+const ICONST_2: u8 = 5;
+const LCONST_0: u8 = 9;
+const LCONST_1: u8 = 10;
+const FCONST_0: u8 = 11;
+const FCONST_1: u8 = 12;
+const FCONST_2: u8 = 13;
+const DCONST_0: u8 = 14;
+const DCONST_1: u8 = 15;
+const BIPUSH: u8 = 16;
+const SIPUSH: u8 = 17;
 const LDC: u8 = 18;
+const LDC_W: u8 = 19;
+const LDC2_W: u8 = 20;
+const ILOAD: u8 = 21;
+const LLOAD: u8 = 22;
+const DLOAD: u8 = 24;
+const ALOAD: u8 = 25;
+const ILOAD_0: u8 = 26;
 const ILOAD_1: u8 = 27;
 const ALOAD_0: u8 = 42;
+const ALOAD_1: u8 = 43;
+const ALOAD_3: u8 = 45;
+const SALOAD: u8 = 53;
+const ISTORE: u8 = 54;
+const LSTORE: u8 = 55;
+const DSTORE: u8 = 57;
+const ASTORE: u8 = 58;
+const ISTORE_0: u8 = 59;
 const ASTORE_1: u8 = 76;
+const ASTORE_3: u8 = 78;
 const DUP: u8 = 89;
+const LXOR: u8 = 131;
+const IINC: u8 = 132;
+const I2L: u8 = 133;
+const DCMPG: u8 = 152;
 const IFEQ: u8 = 153;
 const IFNE: u8 = 154;
 const GOTO: u8 = 167;
+const JSR: u8 = 168;
+const RET: u8 = 169;
+const TABLESWITCH: u8 = 170;
+const LOOKUPSWITCH: u8 = 171;
 const IRETURN: u8 = 172;
+const LRETURN: u8 = 173;
+const FRETURN: u8 = 174;
+const DRETURN: u8 = 175;
+const ARETURN: u8 = 176;
 const RETURN: u8 = 177;
 const GETSTATIC: u8 = 178;
 const PUTSTATIC: u8 = 179;
@@ -17,9 +64,34 @@ const GETFIELD: u8 = 180;
 const PUTFIELD: u8 = 181;
 const INVOKEVIRTUAL: u8 = 182;
 const INVOKESPECIAL: u8 = 183;
+const INVOKESTATIC: u8 = 184;
+const INVOKEINTERFACE: u8 = 185;
+const INVOKEDYNAMIC: u8 = 186;
 const NEW: u8 = 187;
+const NEWARRAY: u8 = 188;
+const ANEWARRAY: u8 = 189;
+const ARRAYLENGTH: u8 = 190;
 const ATHROW: u8 = 191;
 const CHECKCAST: u8 = 192;
+const INSTANCEOF: u8 = 193;
+const MONITORENTER: u8 = 194;
+const MONITOREXIT: u8 = 195;
+const WIDE: u8 = 196;
+const MULTIANEWARRAY: u8 = 197;
+const IFNULL: u8 = 198;
+const IFNONNULL: u8 = 199;
+const GOTO_W: u8 = 200;
+const JSR_W: u8 = 201;
+
+/// The kind of method invocation performed by an invoke instruction.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum InvokeKind {
+    Virtual,
+    Special,
+    Static,
+    Interface,
+    Dynamic,
+}
 
 /// A JVM bytecode instruction.
 ///
@@ -29,20 +101,38 @@ const CHECKCAST: u8 = 192;
 ///
 /// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-6.html
 #[allow(non_camel_case_types)]
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum Bytecode {
+    Nop,
     Aconst_null,
     Iconst_0,
     Iconst_1,
+    Iconst_2,
+    Lconst_0,
+    Lconst_1,
+    Fconst_0,
+    Fconst_1,
+    Fconst_2,
+    Dconst_0,
+    Dconst_1,
     Ldc(u8),
+    Ldc_w(u16),
+    Ldc2_w(u16),
     Iload_1,
     Aload_0,
+    Aload_1,
     Astore_1,
     Dup,
     Ifeq(u16),
     Ifne(u16),
     Goto(u16),
+    Jsr(i16),
+    Ret(u8),
     Ireturn,
+    Lreturn,
+    Freturn,
+    Dreturn,
+    Areturn,
     Return,
     Getstatic(u16),
     Putstatic(u16),
@@ -50,9 +140,16 @@ pub enum Bytecode {
     Putfield(u16),
     Invokevirtual(u16),
     Invokespecial(u16),
+    Invokestatic(u16),
+    Invokeinterface(u16, u8),
+    Invokedynamic(u16),
     New(u16),
     Athrow,
     Checkcast(u16),
+    Goto_w(i32),
+    Jsr_w(i32),
+    /// An opcode that is not supported, as decoded by `from_bytes_lenient`.
+    Unknown(u8),
 }
 
 impl Bytecode {
@@ -75,9 +172,42 @@ impl Bytecode {
     ///     (4, Return),
     /// ];
     ///
-    /// assert_eq!(bytecodes, Bytecode::from_bytes(&bytes));
+    /// assert_eq!(bytecodes, Bytecode::from_bytes(&bytes).unwrap());
+    /// ```
+    ///
+    /// Returns an error if the bytes end partway through the operands of an
+    /// instruction.
+    ///
+    /// Panics if the bytes contain an opcode that is not supported. Use
+    /// `from_bytes_lenient` to decode such bytes without panicking.
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Vec<(usize, Bytecode)>> {
+        Bytecode::decode(bytes, false)
+    }
+
+    /// Converts the given slice of bytes into the bytecode instructions that
+    /// they represent, decoding any opcode that is not supported as an
+    /// `Unknown` instruction and continuing on from the byte after it.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::Bytecode;
+    /// # use jvm_class_file_parser::Bytecode::*;
+    /// #
+    /// let bytes = vec![
+    ///     42,
+    ///     254,
+    ///     177,
+    /// ];
+    ///
+    /// assert_eq!(
+    ///     vec![(0, Aload_0), (1, Unknown(254)), (2, Return)],
+    ///     Bytecode::from_bytes_lenient(&bytes).unwrap()
+    /// );
     /// ```
-    pub fn from_bytes(bytes: &[u8]) -> Vec<(usize, Bytecode)> {
+    pub fn from_bytes_lenient(bytes: &[u8]) -> io::Result<Vec<(usize, Bytecode)>> {
+        Bytecode::decode(bytes, true)
+    }
+
+    fn decode(bytes: &[u8], lenient: bool) -> io::Result<Vec<(usize, Bytecode)>> {
         use Bytecode::*;
 
         let mut bytecode = Vec::new();
@@ -87,6 +217,11 @@ impl Bytecode {
             let instruction = bytes[i];
 
             match instruction {
+                NOP => {
+                    bytecode.push((i, Nop));
+
+                    i += 1;
+                }
                 ACONST_NULL => {
                     bytecode.push((i, Aconst_null));
 
@@ -102,13 +237,67 @@ impl Bytecode {
 
                     i += 1;
                 }
+                ICONST_2 => {
+                    bytecode.push((i, Iconst_2));
+
+                    i += 1;
+                }
+                LCONST_0 => {
+                    bytecode.push((i, Lconst_0));
+
+                    i += 1;
+                }
+                LCONST_1 => {
+                    bytecode.push((i, Lconst_1));
+
+                    i += 1;
+                }
+                FCONST_0 => {
+                    bytecode.push((i, Fconst_0));
+
+                    i += 1;
+                }
+                FCONST_1 => {
+                    bytecode.push((i, Fconst_1));
+
+                    i += 1;
+                }
+                FCONST_2 => {
+                    bytecode.push((i, Fconst_2));
+
+                    i += 1;
+                }
+                DCONST_0 => {
+                    bytecode.push((i, Dconst_0));
+
+                    i += 1;
+                }
+                DCONST_1 => {
+                    bytecode.push((i, Dconst_1));
+
+                    i += 1;
+                }
                 LDC => {
-                    let constant_index = bytes[i + 1];
+                    let [constant_index] = read_operands(bytes, i)?;
 
                     bytecode.push((i, Ldc(constant_index)));
 
                     i += 2;
                 }
+                LDC_W => {
+                    let constant_index = u16::from_be_bytes(read_operands(bytes, i)?);
+
+                    bytecode.push((i, Ldc_w(constant_index)));
+
+                    i += 3;
+                }
+                LDC2_W => {
+                    let constant_index = u16::from_be_bytes(read_operands(bytes, i)?);
+
+                    bytecode.push((i, Ldc2_w(constant_index)));
+
+                    i += 3;
+                }
                 ILOAD_1 => {
                     bytecode.push((i, Iload_1));
 
@@ -119,6 +308,11 @@ impl Bytecode {
 
                     i += 1;
                 }
+                ALOAD_1 => {
+                    bytecode.push((i, Aload_1));
+
+                    i += 1;
+                }
                 ASTORE_1 => {
                     bytecode.push((i, Astore_1));
 
@@ -130,80 +324,139 @@ impl Bytecode {
                     i += 1;
                 }
                 IFEQ => {
-                    let jump_offset = u16::from_be_bytes([bytes[i + 1], bytes[i + 2]]);
+                    let jump_offset = u16::from_be_bytes(read_operands(bytes, i)?);
 
                     bytecode.push((i, Ifeq(jump_offset)));
 
                     i += 3;
                 }
                 IFNE => {
-                    let jump_offset = u16::from_be_bytes([bytes[i + 1], bytes[i + 2]]);
+                    let jump_offset = u16::from_be_bytes(read_operands(bytes, i)?);
 
                     bytecode.push((i, Ifne(jump_offset)));
 
                     i += 3;
                 }
                 GOTO => {
-                    let jump_offset = u16::from_be_bytes([bytes[i + 1], bytes[i + 2]]);
+                    let jump_offset = u16::from_be_bytes(read_operands(bytes, i)?);
 
                     bytecode.push((i, Goto(jump_offset)));
 
                     i += 3;
                 }
+                JSR => {
+                    let jump_offset = i16::from_be_bytes(read_operands(bytes, i)?);
+
+                    bytecode.push((i, Jsr(jump_offset)));
+
+                    i += 3;
+                }
+                RET => {
+                    let [local_index] = read_operands(bytes, i)?;
+
+                    bytecode.push((i, Ret(local_index)));
+
+                    i += 2;
+                }
                 IRETURN => {
                     bytecode.push((i, Ireturn));
 
                     i += 1;
                 }
+                LRETURN => {
+                    bytecode.push((i, Lreturn));
+
+                    i += 1;
+                }
+                FRETURN => {
+                    bytecode.push((i, Freturn));
+
+                    i += 1;
+                }
+                DRETURN => {
+                    bytecode.push((i, Dreturn));
+
+                    i += 1;
+                }
+                ARETURN => {
+                    bytecode.push((i, Areturn));
+
+                    i += 1;
+                }
                 RETURN => {
                     bytecode.push((i, Return));
 
                     i += 1;
                 }
                 GETSTATIC => {
-                    let field = u16::from_be_bytes([bytes[i + 1], bytes[i + 2]]);
+                    let field = u16::from_be_bytes(read_operands(bytes, i)?);
 
                     bytecode.push((i, Getstatic(field)));
 
                     i += 3;
                 }
                 PUTSTATIC => {
-                    let field = u16::from_be_bytes([bytes[i + 1], bytes[i + 2]]);
+                    let field = u16::from_be_bytes(read_operands(bytes, i)?);
 
                     bytecode.push((i, Putstatic(field)));
 
                     i += 3;
                 }
                 GETFIELD => {
-                    let field = u16::from_be_bytes([bytes[i + 1], bytes[i + 2]]);
+                    let field = u16::from_be_bytes(read_operands(bytes, i)?);
 
                     bytecode.push((i, Getfield(field)));
 
                     i += 3;
                 }
                 PUTFIELD => {
-                    let field = u16::from_be_bytes([bytes[i + 1], bytes[i + 2]]);
+                    let field = u16::from_be_bytes(read_operands(bytes, i)?);
 
                     bytecode.push((i, Putfield(field)));
 
                     i += 3;
                 }
                 INVOKEVIRTUAL => {
-                    let method = u16::from_be_bytes([bytes[i + 1], bytes[i + 2]]);
+                    let method = u16::from_be_bytes(read_operands(bytes, i)?);
 
                     bytecode.push((i, Invokevirtual(method)));
 
                     i += 3;
                 }
                 INVOKESPECIAL => {
-                    let method = u16::from_be_bytes([bytes[i + 1], bytes[i + 2]]);
+                    let method = u16::from_be_bytes(read_operands(bytes, i)?);
 
                     bytecode.push((i, Invokespecial(method)));
 
                     i += 3;
                 }
+                INVOKESTATIC => {
+                    let method = u16::from_be_bytes(read_operands(bytes, i)?);
+
+                    bytecode.push((i, Invokestatic(method)));
+
+                    i += 3;
+                }
+                INVOKEINTERFACE => {
+                    let [high, low, count, _] = read_operands(bytes, i)?;
+                    let method = u16::from_be_bytes([high, low]);
+
+                    bytecode.push((i, Invokeinterface(method, count)));
+
+                    // the argument count is followed by a zero byte
+                    i += 5;
+                }
+                INVOKEDYNAMIC => {
+                    let [high, low, _, _] = read_operands(bytes, i)?;
+                    let call_site = u16::from_be_bytes([high, low]);
+
+                    bytecode.push((i, Invokedynamic(call_site)));
+
+                    // the call site index is followed by two zero bytes
+                    i += 5;
+                }
                 NEW => {
-                    let class = u16::from_be_bytes([bytes[i + 1], bytes[i + 2]]);
+                    let class = u16::from_be_bytes(read_operands(bytes, i)?);
 
                     bytecode.push((i, New(class)));
 
@@ -215,17 +468,161 @@ impl Bytecode {
                     i += 1;
                 }
                 CHECKCAST => {
-                    let class = u16::from_be_bytes([bytes[i + 1], bytes[i + 2]]);
+                    let class = u16::from_be_bytes(read_operands(bytes, i)?);
 
                     bytecode.push((i, Checkcast(class)));
 
                     i += 3;
                 }
+                GOTO_W => {
+                    let jump_offset = i32::from_be_bytes(read_operands(bytes, i)?);
+
+                    bytecode.push((i, Goto_w(jump_offset)));
+
+                    i += 5;
+                }
+                JSR_W => {
+                    let jump_offset = i32::from_be_bytes(read_operands(bytes, i)?);
+
+                    bytecode.push((i, Jsr_w(jump_offset)));
+
+                    i += 5;
+                }
+                _ if lenient => {
+                    bytecode.push((i, Unknown(instruction)));
+
+                    i += 1;
+                }
                 _ => panic!("Unknown bytecode: {}", instruction),
             }
         }
 
-        bytecode
+        Ok(bytecode)
+    }
+
+    /// Converts the given bytecode instructions into the bytes that represent
+    /// them. This is the inverse of `from_bytes`.
+    ///
+    /// The offsets of the instructions are not checked, the instructions are
+    /// simply encoded one after another.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::Bytecode;
+    /// # use jvm_class_file_parser::Bytecode::*;
+    /// #
+    /// let bytecodes = vec![
+    ///     (0, Aload_0),
+    ///     (1, Invokespecial(1)),
+    ///     (4, Return),
+    /// ];
+    ///
+    /// let bytes = vec![
+    ///     42,
+    ///     183, 0, 1,
+    ///     177,
+    /// ];
+    ///
+    /// assert_eq!(bytes, Bytecode::to_bytes(&bytecodes));
+    /// ```
+    pub fn to_bytes(bytecode: &[(usize, Bytecode)]) -> Vec<u8> {
+        use Bytecode::*;
+
+        let mut bytes = Vec::new();
+
+        for (_, instruction) in bytecode.iter() {
+            let (opcode, operand) = match *instruction {
+                Nop => (NOP, None),
+                Aconst_null => (ACONST_NULL, None),
+                Iconst_0 => (ICONST_0, None),
+                Iconst_1 => (ICONST_1, None),
+                Iconst_2 => (ICONST_2, None),
+                Lconst_0 => (LCONST_0, None),
+                Lconst_1 => (LCONST_1, None),
+                Fconst_0 => (FCONST_0, None),
+                Fconst_1 => (FCONST_1, None),
+                Fconst_2 => (FCONST_2, None),
+                Dconst_0 => (DCONST_0, None),
+                Dconst_1 => (DCONST_1, None),
+                Ldc(constant_index) => {
+                    bytes.push(LDC);
+                    bytes.push(constant_index);
+
+                    continue;
+                }
+                Ldc_w(constant_index) => (LDC_W, Some(constant_index)),
+                Ldc2_w(constant_index) => (LDC2_W, Some(constant_index)),
+                Iload_1 => (ILOAD_1, None),
+                Aload_0 => (ALOAD_0, None),
+                Aload_1 => (ALOAD_1, None),
+                Astore_1 => (ASTORE_1, None),
+                Dup => (DUP, None),
+                Ifeq(jump_offset) => (IFEQ, Some(jump_offset)),
+                Ifne(jump_offset) => (IFNE, Some(jump_offset)),
+                Goto(jump_offset) => (GOTO, Some(jump_offset)),
+                Jsr(jump_offset) => {
+                    bytes.push(JSR);
+                    bytes.extend_from_slice(&jump_offset.to_be_bytes());
+
+                    continue;
+                }
+                Ret(local_index) => {
+                    bytes.push(RET);
+                    bytes.push(local_index);
+
+                    continue;
+                }
+                Ireturn => (IRETURN, None),
+                Lreturn => (LRETURN, None),
+                Freturn => (FRETURN, None),
+                Dreturn => (DRETURN, None),
+                Areturn => (ARETURN, None),
+                Return => (RETURN, None),
+                Getstatic(field) => (GETSTATIC, Some(field)),
+                Putstatic(field) => (PUTSTATIC, Some(field)),
+                Getfield(field) => (GETFIELD, Some(field)),
+                Putfield(field) => (PUTFIELD, Some(field)),
+                Invokevirtual(method) => (INVOKEVIRTUAL, Some(method)),
+                Invokespecial(method) => (INVOKESPECIAL, Some(method)),
+                Invokestatic(method) => (INVOKESTATIC, Some(method)),
+                Invokeinterface(method, count) => {
+                    bytes.push(INVOKEINTERFACE);
+                    bytes.extend_from_slice(&method.to_be_bytes());
+                    bytes.extend_from_slice(&[count, 0]);
+
+                    continue;
+                }
+                Invokedynamic(call_site) => {
+                    bytes.push(INVOKEDYNAMIC);
+                    bytes.extend_from_slice(&call_site.to_be_bytes());
+                    bytes.extend_from_slice(&[0, 0]);
+
+                    continue;
+                }
+                New(class) => (NEW, Some(class)),
+                Athrow => (ATHROW, None),
+                Checkcast(class) => (CHECKCAST, Some(class)),
+                Goto_w(jump_offset) => {
+                    bytes.push(GOTO_W);
+                    bytes.extend_from_slice(&jump_offset.to_be_bytes());
+
+                    continue;
+                }
+                Jsr_w(jump_offset) => {
+                    bytes.push(JSR_W);
+                    bytes.extend_from_slice(&jump_offset.to_be_bytes());
+
+                    continue;
+                }
+                Unknown(opcode) => (opcode, None),
+            };
+
+            bytes.push(opcode);
+            if let Some(operand) = operand {
+                bytes.extend_from_slice(&operand.to_be_bytes());
+            }
+        }
+
+        bytes
     }
 
     /// Converts the bytecode into a String representation.
@@ -243,18 +640,40 @@ impl Bytecode {
         use Bytecode::*;
 
         match self {
+            Nop => "nop".to_string(),
             Aconst_null => "aconst_null".to_string(),
             Iconst_0 => "iconst_0".to_string(),
             Iconst_1 => "iconst_1".to_string(),
+            Iconst_2 => "iconst_2".to_string(),
+            Lconst_0 => "lconst_0".to_string(),
+            Lconst_1 => "lconst_1".to_string(),
+            Fconst_0 => "fconst_0".to_string(),
+            Fconst_1 => "fconst_1".to_string(),
+            Fconst_2 => "fconst_2".to_string(),
+            Dconst_0 => "dconst_0".to_string(),
+            Dconst_1 => "dconst_1".to_string(),
             Ldc(constant_index) => format!("{:13} #{}", "ldc", constant_index),
+            Ldc_w(constant_index) => format!("{:13} #{}", "ldc_w", constant_index),
+            Ldc2_w(constant_index) => format!("{:13} #{}", "ldc2_w", constant_index),
             Iload_1 => "iload_1".to_string(),
             Aload_0 => "aload_0".to_string(),
+            Aload_1 => "aload_1".to_string(),
             Astore_1 => "astore_1".to_string(),
             Dup => "dup".to_string(),
             Ifeq(jump_offset) => format!("{:13} {}", "ifeq", jump_offset + index),
             Ifne(jump_offset) => format!("{:13} {}", "ifne", jump_offset + index),
             Goto(jump_offset) => format!("{:13} {}", "goto", jump_offset + index),
+            Jsr(jump_offset) => format!(
+                "{:13} {}",
+                "jsr",
+                i32::from(*jump_offset) + i32::from(index)
+            ),
+            Ret(local_index) => format!("{:13} {}", "ret", local_index),
             Ireturn => "ireturn".to_string(),
+            Lreturn => "lreturn".to_string(),
+            Freturn => "freturn".to_string(),
+            Dreturn => "dreturn".to_string(),
+            Areturn => "areturn".to_string(),
             Return => "return".to_string(),
             Getstatic(field) => format!("{:13} #{}", "getstatic", field),
             Putstatic(field) => format!("{:13} #{}", "putstatic", field),
@@ -262,9 +681,750 @@ impl Bytecode {
             Putfield(field) => format!("{:13} #{}", "putfield", field),
             Invokevirtual(method) => format!("{:13} #{}", "invokevirtual", method),
             Invokespecial(method) => format!("{:13} #{}", "invokespecial", method),
+            Invokestatic(method) => format!("{:13} #{}", "invokestatic", method),
+            Invokeinterface(method, count) => {
+                format!("{:13} #{},  {}", "invokeinterface", method, count)
+            }
+            Invokedynamic(call_site) => format!("{:13} #{}, 0", "invokedynamic", call_site),
             New(class) => format!("{:13} #{}", "new", class),
             Athrow => "athrow".to_string(),
-            Checkcast(class) => format!("{:13} #{}", "invokespecial", class),
+            Checkcast(class) => format!("{:13} #{}", "checkcast", class),
+            Goto_w(jump_offset) => format!(
+                "{:13} {}",
+                "goto_w",
+                i64::from(*jump_offset) + i64::from(index)
+            ),
+            Jsr_w(jump_offset) => format!(
+                "{:13} {}",
+                "jsr_w",
+                i64::from(*jump_offset) + i64::from(index)
+            ),
+            Unknown(opcode) => format!("{:13} {}", "<unknown>", opcode),
+        }
+    }
+
+    /// Converts the bytecode into a String representation, with any constant
+    /// pool index operand replaced by the contents of the constant that it
+    /// points to.
+    ///
+    /// Takes in the index of the instruction so that it can be used to display
+    /// bytecode instructions that contain an instruction offset.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// # use jvm_class_file_parser::Bytecode::*;
+    /// #
+    /// let mut file = File::open("classes/HelloWorld.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert_eq!(
+    ///     "invokevirtual java/io/PrintStream.println:(Ljava/lang/String;)V",
+    ///     Invokevirtual(4).to_string_resolved(5, &class_file)
+    /// );
+    /// assert_eq!("ldc           \"Hello, World!\"", Ldc(3).to_string_resolved(3, &class_file));
+    /// assert_eq!("return", Return.to_string_resolved(8, &class_file));
+    /// ```
+    pub fn to_string_resolved(&self, index: u16, class_file: &ClassFile) -> String {
+        let unresolved = self.to_string(index);
+
+        match self.constant_pool_index() {
+            Some(constant_index) => {
+                let mnemonic = unresolved.split_whitespace().next().unwrap_or("");
+
+                format!(
+                    "{:13} {}",
+                    mnemonic,
+                    resolve_constant(class_file, constant_index)
+                )
+            }
+            None => unresolved,
         }
     }
+
+    /// Returns the constant pool index that the instruction takes as an
+    /// operand, if it has one.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::Bytecode::*;
+    /// #
+    /// assert_eq!(Some(1), Invokespecial(1).constant_pool_index());
+    /// assert_eq!(None, Goto(4).constant_pool_index());
+    /// ```
+    pub fn constant_pool_index(&self) -> Option<usize> {
+        use Bytecode::*;
+
+        match *self {
+            Ldc(index) => Some(index as usize),
+            Ldc_w(index)
+            | Ldc2_w(index)
+            | Getstatic(index)
+            | Putstatic(index)
+            | Getfield(index)
+            | Putfield(index)
+            | Invokevirtual(index)
+            | Invokespecial(index)
+            | Invokestatic(index)
+            | Invokeinterface(index, _)
+            | Invokedynamic(index)
+            | New(index)
+            | Checkcast(index) => Some(index as usize),
+            _ => None,
+        }
+    }
+
+    /// Returns the index of the constant that the instruction pushes onto the
+    /// operand stack, if it is one of `ldc`, `ldc_w`, or `ldc2_w`.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::Bytecode::*;
+    /// #
+    /// assert_eq!(Some(3), Ldc(3).loaded_constant_index());
+    /// assert_eq!(Some(300), Ldc_w(300).loaded_constant_index());
+    /// assert_eq!(None, Getstatic(2).loaded_constant_index());
+    /// ```
+    pub fn loaded_constant_index(&self) -> Option<usize> {
+        use Bytecode::*;
+
+        match *self {
+            Ldc(index) => Some(usize::from(index)),
+            Ldc_w(index) | Ldc2_w(index) => Some(usize::from(index)),
+            _ => None,
+        }
+    }
+
+    /// Returns the index of the local variable that the instruction loads
+    /// from, stores to, or returns to, if it is a local variable instruction.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::Bytecode::*;
+    /// #
+    /// assert_eq!(Some(1), Iload_1.local_variable_index());
+    /// assert_eq!(Some(4), Ret(4).local_variable_index());
+    /// assert_eq!(None, Return.local_variable_index());
+    /// ```
+    pub fn local_variable_index(&self) -> Option<u16> {
+        use Bytecode::*;
+
+        match *self {
+            Aload_0 => Some(0),
+            Iload_1 | Aload_1 | Astore_1 => Some(1),
+            Ret(index) => Some(u16::from(index)),
+            _ => None,
+        }
+    }
+
+    /// Returns the kind of method invocation that the instruction performs, if
+    /// it is an invoke instruction.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::{Bytecode::*, InvokeKind};
+    /// #
+    /// assert_eq!(Some(InvokeKind::Virtual), Invokevirtual(4).invoke_kind());
+    /// assert_eq!(None, Return.invoke_kind());
+    /// ```
+    pub fn invoke_kind(&self) -> Option<InvokeKind> {
+        use Bytecode::*;
+
+        match *self {
+            Invokevirtual(_) => Some(InvokeKind::Virtual),
+            Invokespecial(_) => Some(InvokeKind::Special),
+            Invokestatic(_) => Some(InvokeKind::Static),
+            Invokeinterface(_, _) => Some(InvokeKind::Interface),
+            Invokedynamic(_) => Some(InvokeKind::Dynamic),
+            _ => None,
+        }
+    }
+
+    /// Returns whether execution can continue on to the next instruction after
+    /// this one.
+    ///
+    /// Unconditional branches, returns, and `athrow` never fall through. A
+    /// `jsr` is considered to fall through, as its subroutine returns to the
+    /// instruction after it.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::Bytecode::*;
+    /// #
+    /// assert!(Ifeq(4).falls_through());
+    /// assert!(!Goto(4).falls_through());
+    /// assert!(!Return.falls_through());
+    /// ```
+    pub fn falls_through(&self) -> bool {
+        use Bytecode::*;
+
+        !matches!(
+            *self,
+            Goto(_)
+                | Goto_w(_)
+                | Ret(_)
+                | Ireturn
+                | Lreturn
+                | Freturn
+                | Dreturn
+                | Areturn
+                | Return
+                | Athrow
+        )
+    }
+
+    /// Returns the offset of the instruction that the instruction at the given
+    /// offset branches to, if it is a branch instruction.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::Bytecode::*;
+    /// #
+    /// assert_eq!(Some(15), Ifeq(5).branch_target(10));
+    /// assert_eq!(Some(5), Jsr(-5).branch_target(10));
+    /// assert_eq!(None, Return.branch_target(10));
+    /// ```
+    pub fn branch_target(&self, pc: usize) -> Option<usize> {
+        use Bytecode::*;
+
+        let offset = match *self {
+            // the 2 byte offsets are signed, even though they are stored as u16
+            Ifeq(offset) | Ifne(offset) | Goto(offset) => i64::from(offset as i16),
+            Jsr(offset) => i64::from(offset),
+            Goto_w(offset) | Jsr_w(offset) => i64::from(offset),
+            _ => return None,
+        };
+
+        Some((pc as i64 + offset) as usize)
+    }
+
+    /// Returns a copy of the instruction with its constant pool index operand,
+    /// if it has one, replaced by the result of the given function.
+    ///
+    /// Panics if the new index does not fit in the operand of the
+    /// instruction.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::Bytecode::*;
+    /// #
+    /// assert_eq!(Invokespecial(3), Invokespecial(1).map_constant_pool_index(|i| i + 2));
+    /// assert_eq!(Goto(4), Goto(4).map_constant_pool_index(|i| i + 2));
+    /// ```
+    pub fn map_constant_pool_index<F: FnOnce(usize) -> usize>(&self, f: F) -> Bytecode {
+        use Bytecode::*;
+
+        let index = match self.constant_pool_index() {
+            Some(index) => f(index),
+            None => return self.clone(),
+        };
+
+        let wide_index = || {
+            if index > u16::MAX as usize {
+                panic!("Constant pool index {} does not fit in 2 bytes", index);
+            }
+
+            index as u16
+        };
+
+        match *self {
+            Ldc(_) => {
+                if index > u8::MAX as usize {
+                    panic!(
+                        "Constant pool index {} does not fit in the operand of ldc",
+                        index
+                    );
+                }
+
+                Ldc(index as u8)
+            }
+            Ldc_w(_) => Ldc_w(wide_index()),
+            Ldc2_w(_) => Ldc2_w(wide_index()),
+            Getstatic(_) => Getstatic(wide_index()),
+            Putstatic(_) => Putstatic(wide_index()),
+            Getfield(_) => Getfield(wide_index()),
+            Putfield(_) => Putfield(wide_index()),
+            Invokevirtual(_) => Invokevirtual(wide_index()),
+            Invokespecial(_) => Invokespecial(wide_index()),
+            Invokestatic(_) => Invokestatic(wide_index()),
+            Invokeinterface(_, count) => Invokeinterface(wide_index(), count),
+            Invokedynamic(_) => Invokedynamic(wide_index()),
+            New(_) => New(wide_index()),
+            Checkcast(_) => Checkcast(wide_index()),
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Returns a String representation of the contents of the specified constant,
+/// as used for an instruction operand.
+fn resolve_constant(class_file: &ClassFile, index: usize) -> String {
+    use ConstantPoolEntry::*;
+
+    if index == 0 || index > class_file.constant_pool.len() {
+        return format!("#{}", index);
+    }
+
+    match *class_file.get_constant(index) {
+        ConstantClass { name_index } => {
+            let class_name = class_file.get_constant_utf8(name_index);
+
+            // as with javap, array types are quoted since they are descriptors
+            if ClassType::parse(class_name).is_ok_and(|class_type| class_type.is_array()) {
+                format!("\"{}\"", class_name)
+            } else {
+                class_name.to_string()
+            }
+        }
+        ConstantString { string_index } => {
+            format!("\"{}\"", class_file.get_constant_utf8(string_index))
+        }
+        ConstantInteger { val } => val.to_string(),
+        ConstantLong { val } => format!("{}l", val),
+        ConstantFloat { ref val } => format!("{}f", f32::from(val)),
+        ConstantDouble { ref val } => format!("{}d", f64::from(val)),
+        ConstantFieldref {
+            class_index,
+            name_and_type_index,
+        }
+        | ConstantMethodref {
+            class_index,
+            name_and_type_index,
+        } => resolve_member(class_file, class_index, name_and_type_index),
+        ConstantInterfaceMethodref {
+            class_index,
+            name_and_type_index,
+        } => resolve_member(
+            class_file,
+            class_index as usize,
+            name_and_type_index as usize,
+        ),
+        _ => format!("#{}", index),
+    }
+}
+
+fn resolve_member(
+    class_file: &ClassFile,
+    class_index: usize,
+    name_and_type_index: usize,
+) -> String {
+    use ConstantPoolEntry::*;
+
+    let class_name = class_file.get_constant_class_str(class_index);
+
+    match *class_file.get_constant(name_and_type_index) {
+        ConstantNameAndType {
+            name_index,
+            descriptor_index,
+        } => format!(
+            "{}.{}:{}",
+            class_name,
+            class_file.get_constant_utf8(name_index),
+            class_file.get_constant_utf8(descriptor_index)
+        ),
+        _ => format!("{}.#{}", class_name, name_and_type_index),
+    }
+}
+
+/// An instruction of a method body, as it appears in the code bytes. Unlike
+/// `Bytecode`, this covers every opcode of the JVM specification, so it can
+/// be used on code that contains instructions which cannot be decoded.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct RawInstruction<'a> {
+    pub pc: usize,
+    /// The opcode of the instruction followed by its operands.
+    pub bytes: &'a [u8],
+}
+
+impl<'a> RawInstruction<'a> {
+    pub fn opcode(&self) -> u8 {
+        self.bytes[0]
+    }
+
+    /// Returns the constant pool index operand of the instruction, if it has
+    /// one.
+    pub fn constant_pool_index(&self) -> Option<ConstantPoolIndex> {
+        match self.opcode() {
+            LDC => Some(self.bytes[1] as ConstantPoolIndex),
+            LDC_W
+            | LDC2_W
+            | GETSTATIC..=NEW
+            | ANEWARRAY
+            | CHECKCAST
+            | INSTANCEOF
+            | MULTIANEWARRAY => {
+                Some(u16::from_be_bytes([self.bytes[1], self.bytes[2]]) as ConstantPoolIndex)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the first local variable slot that the instruction loads from,
+    /// stores to, increments, or returns to, along with the number of slots
+    /// that the variable takes up, if it is a local variable instruction.
+    ///
+    /// A `long` or `double` variable takes up two slots.
+    pub fn local_variable_slots(&self) -> Option<(u16, u16)> {
+        // the specialized forms (ex. istore_2) are grouped by type, with four
+        // of each, in the same order as the general forms (ex. istore)
+        let (opcode, index) = match self.opcode() {
+            WIDE => (
+                self.bytes[1],
+                u16::from_be_bytes([self.bytes[2], self.bytes[3]]),
+            ),
+            opcode @ (ILOAD..=ALOAD | ISTORE..=ASTORE | IINC | RET) => {
+                (opcode, u16::from(self.bytes[1]))
+            }
+            opcode @ ILOAD_0..=ALOAD_3 => (
+                ILOAD + (opcode - ILOAD_0) / 4,
+                u16::from((opcode - ILOAD_0) % 4),
+            ),
+            opcode @ ISTORE_0..=ASTORE_3 => (
+                ISTORE + (opcode - ISTORE_0) / 4,
+                u16::from((opcode - ISTORE_0) % 4),
+            ),
+            _ => return None,
+        };
+
+        match opcode {
+            LLOAD | DLOAD | LSTORE | DSTORE => Some((index, 2)),
+            ILOAD..=ALOAD | ISTORE..=ASTORE | IINC | RET => Some((index, 1)),
+            _ => None,
+        }
+    }
+
+    /// Returns a copy of the bytes of the instruction with its constant pool
+    /// index operand, if it has one, replaced by the given index.
+    ///
+    /// Fails if the index does not fit in the operand.
+    pub fn with_constant_pool_index(&self, index: ConstantPoolIndex) -> io::Result<Vec<u8>> {
+        let mut bytes = self.bytes.to_vec();
+
+        match self.opcode() {
+            LDC if index <= u8::MAX as usize => bytes[1] = index as u8,
+            LDC => {
+                return Err(io_err(format!(
+                    "Constant pool index {} does not fit in the operand of the ldc at offset {}",
+                    index, self.pc
+                )))
+            }
+            _ if self.constant_pool_index().is_some() => {
+                if index > u16::MAX as usize {
+                    return Err(io_err(format!(
+                        "Constant pool index {} does not fit in 2 bytes",
+                        index
+                    )));
+                }
+
+                bytes[1..3].copy_from_slice(&(index as u16).to_be_bytes());
+            }
+            _ => {}
+        }
+
+        Ok(bytes)
+    }
+}
+
+/// Splits the given code bytes into the instructions that they contain.
+///
+/// Fails if the bytes contain an opcode that is not defined by the JVM
+/// specification, or end partway through an instruction.
+pub(crate) fn raw_instructions(bytes: &[u8]) -> io::Result<Vec<RawInstruction<'_>>> {
+    let mut instructions = Vec::new();
+
+    let mut pc = 0;
+    while pc < bytes.len() {
+        let length = instruction_length(bytes, pc)?;
+
+        let instruction_bytes = bytes.get(pc..pc + length).ok_or_else(|| {
+            io_err(format!(
+                "The instruction at offset {} is missing operands. It needs {} operand bytes, but only {} remain in the code.",
+                pc,
+                length - 1,
+                bytes.len() - pc - 1
+            ))
+        })?;
+
+        instructions.push(RawInstruction {
+            pc,
+            bytes: instruction_bytes,
+        });
+
+        pc += length;
+    }
+
+    Ok(instructions)
+}
+
+/// Returns the local variable slots that are loaded from, stored to,
+/// incremented, or returned to by the instructions in the given code bytes.
+///
+/// Fails if the bytes cannot be split into instructions.
+pub(crate) fn used_local_slots(bytes: &[u8]) -> io::Result<BTreeSet<u16>> {
+    let mut slots = BTreeSet::new();
+
+    for instruction in raw_instructions(bytes)? {
+        if let Some((index, count)) = instruction.local_variable_slots() {
+            slots.extend((0..count).filter_map(|i| index.checked_add(i)));
+        }
+    }
+
+    Ok(slots)
+}
+
+/// Returns the length in bytes of the instruction at the given offset,
+/// including its opcode.
+///
+/// See Chapter 6.5 of the JVM specification for details.
+///
+/// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-6.html#jvms-6.5
+fn instruction_length(bytes: &[u8], pc: usize) -> io::Result<usize> {
+    // the operands of tableswitch and lookupswitch start at the next offset
+    // that is a multiple of 4
+    let padding = 3 - pc % 4;
+    let switch_operand = |i: usize| -> io::Result<i64> {
+        let offset = pc + 1 + padding + i * 4;
+
+        bytes
+            .get(offset..offset + 4)
+            .map(|operand| {
+                i64::from(i32::from_be_bytes([
+                    operand[0], operand[1], operand[2], operand[3],
+                ]))
+            })
+            .ok_or_else(|| {
+                io_err(format!(
+                    "The switch instruction at offset {} is missing operands.",
+                    pc
+                ))
+            })
+    };
+
+    let length = match bytes[pc] {
+        BIPUSH | LDC | ILOAD..=ALOAD | ISTORE..=ASTORE | RET | NEWARRAY => 2,
+        SIPUSH
+        | LDC_W
+        | LDC2_W
+        | IINC
+        | IFEQ..=JSR
+        | GETSTATIC..=INVOKESTATIC
+        | NEW
+        | ANEWARRAY
+        | CHECKCAST
+        | INSTANCEOF
+        | IFNULL
+        | IFNONNULL => 3,
+        MULTIANEWARRAY => 4,
+        INVOKEINTERFACE | INVOKEDYNAMIC | GOTO_W | JSR_W => 5,
+        WIDE => match bytes.get(pc + 1) {
+            Some(&IINC) => 6,
+            _ => 4,
+        },
+        TABLESWITCH => {
+            let low = switch_operand(1)?;
+            let high = switch_operand(2)?;
+
+            1 + padding + 12 + ((high - low + 1).max(0) as usize) * 4
+        }
+        LOOKUPSWITCH => {
+            let npairs = switch_operand(1)?;
+
+            1 + padding + 8 + (npairs.max(0) as usize) * 8
+        }
+        NOP..=DCONST_1
+        | ILOAD_0..=SALOAD
+        | ISTORE_0..=LXOR
+        | I2L..=DCMPG
+        | IRETURN..=RETURN
+        | ARRAYLENGTH
+        | ATHROW
+        | MONITORENTER
+        | MONITOREXIT => 1,
+        opcode => {
+            return Err(io_err(format!(
+                "Found the undefined opcode {} at offset {}.",
+                opcode, pc
+            )))
+        }
+    };
+
+    Ok(length)
+}
+
+/// Returns the operand bytes of the instruction at the given offset, or an
+/// error if the code ends before all of them.
+fn read_operands<const N: usize>(bytes: &[u8], pc: usize) -> io::Result<[u8; N]> {
+    let operands = bytes.get(pc + 1..pc + 1 + N).ok_or_else(|| {
+        io_err(format!(
+            "The instruction at offset {} is missing operands. It needs {} operand bytes, but only {} remain in the code.",
+            pc,
+            N,
+            bytes.len() - pc - 1
+        ))
+    })?;
+
+    let mut array = [0; N];
+    array.copy_from_slice(operands);
+
+    Ok(array)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Bytecode::*;
+    use super::{raw_instructions, used_local_slots, Bytecode, InvokeKind};
+
+    #[test]
+    fn decode_wide_and_subroutine_jumps() {
+        let bytes = vec![
+            200, 0, 1, 0, 0, // goto_w 65536
+            168, 0xff, 0xfb, // jsr -5
+            169, 1, // ret 1
+            201, 0xff, 0xff, 0xff, 0xf6, // jsr_w -10
+        ];
+
+        let bytecode = Bytecode::from_bytes(&bytes).unwrap();
+
+        assert_eq!(
+            vec![
+                (0, Goto_w(65536)),
+                (5, Jsr(-5)),
+                (8, Ret(1)),
+                (10, Jsr_w(-10)),
+            ],
+            bytecode
+        );
+        assert_eq!(bytes, Bytecode::to_bytes(&bytecode));
+
+        assert_eq!("goto_w        65536", Goto_w(65536).to_string(0));
+        assert_eq!("jsr           0", Jsr(-5).to_string(5));
+        assert_eq!("jsr_w         0", Jsr_w(-10).to_string(10));
+    }
+
+    #[test]
+    fn loaded_constant_index_of_ldc_instructions() {
+        assert_eq!(Some(255), Ldc(255).loaded_constant_index());
+        assert_eq!(Some(256), Ldc_w(256).loaded_constant_index());
+        assert_eq!(Some(65535), Ldc2_w(65535).loaded_constant_index());
+        assert_eq!(None, Checkcast(2).loaded_constant_index());
+        assert_eq!(None, Return.loaded_constant_index());
+    }
+
+    #[test]
+    fn invoke_kind_of_invoke_instructions() {
+        assert_eq!(Some(InvokeKind::Special), Invokespecial(1).invoke_kind());
+        assert_eq!(Some(InvokeKind::Static), Invokestatic(1).invoke_kind());
+        assert_eq!(None, Aload_0.invoke_kind());
+        assert_eq!(None, Ldc(1).invoke_kind());
+    }
+
+    #[test]
+    fn decode_and_encode_static_and_interface_invokes() {
+        let bytes = vec![
+            184, 0, 2, // invokestatic #2
+            185, 0, 3, 2, 0,   // invokeinterface #3,  2
+            177, // return
+        ];
+        let bytecode = vec![
+            (0, Invokestatic(2)),
+            (3, Invokeinterface(3, 2)),
+            (8, Return),
+        ];
+
+        assert_eq!(bytecode, Bytecode::from_bytes_lenient(&bytes).unwrap());
+        assert_eq!(bytes, Bytecode::to_bytes(&bytecode));
+        assert_eq!("invokeinterface #3,  2", Invokeinterface(3, 2).to_string(3));
+    }
+
+    #[test]
+    fn decode_lenient_resynchronizes_after_unknown_opcode() {
+        let bytes = vec![
+            42,  // aload_0
+            203, // unsupported
+            183, 0, 1,   // invokespecial #1
+            177, // return
+        ];
+
+        assert_eq!(
+            vec![
+                (0, Aload_0),
+                (1, Unknown(203)),
+                (2, Invokespecial(1)),
+                (5, Return),
+            ],
+            Bytecode::from_bytes_lenient(&bytes).unwrap()
+        );
+        assert_eq!(
+            bytes,
+            Bytecode::to_bytes(&Bytecode::from_bytes_lenient(&bytes).unwrap())
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn decode_strict_panics_on_unknown_opcode() {
+        Bytecode::from_bytes(&[42, 203, 177]).unwrap();
+    }
+
+    #[test]
+    fn decode_truncated_operands() {
+        let result = Bytecode::from_bytes(&[42, 183, 0]);
+
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("The instruction at offset 1 is missing operands."));
+        assert!(Bytecode::from_bytes_lenient(&[200, 0, 1]).is_err());
+    }
+
+    #[test]
+    fn raw_instructions_of_switches_and_wide() {
+        let mut bytes = vec![
+            0,    // nop
+            0xaa, // tableswitch
+            0, 0, // padding
+            0, 0, 0, 30, // default
+            0, 0, 0, 0, // low
+            0, 0, 0, 1, // high
+            0, 0, 0, 30, 0, 0, 0, 30,   // jump offsets
+            0xab, // lookupswitch
+            0, 0, 0, // padding
+            0, 0, 0, 20, // default
+            0, 0, 0, 1, // npairs
+            0, 0, 0, 5, 0, 0, 0, 20, // match-offset pairs
+            0xc4, 0x84, 0, 1, 0, 1, // wide iinc 1 1
+            0xbd, 0, 9, // anewarray #9
+        ];
+
+        let instructions = raw_instructions(&bytes).unwrap();
+
+        assert_eq!(
+            vec![0, 1, 24, 44, 50],
+            instructions
+                .iter()
+                .map(|instruction| instruction.pc)
+                .collect::<Vec<usize>>()
+        );
+        assert_eq!(Some(9), instructions[4].constant_pool_index());
+        assert_eq!(None, instructions[3].constant_pool_index());
+
+        bytes.pop();
+        assert!(raw_instructions(&bytes).is_err());
+        assert!(raw_instructions(&[0xfe]).is_err());
+    }
+
+    #[test]
+    fn used_local_slots_of_all_local_variable_instructions() {
+        let bytes = vec![
+            0x3d, // istore_2
+            0x37, 5, // lstore 5
+            0x84, 8, 1, // iinc 8 1
+            0xc4, 0x18, 1, 44, // wide dload 300
+            0xc4, 0x84, 1, 50, 0, 1,    // wide iinc 306 1
+            0x2d, // aload_3
+            0xa9, 10, // ret 10
+        ];
+
+        assert_eq!(
+            vec![2, 3, 5, 6, 8, 10, 300, 301, 306],
+            used_local_slots(&bytes)
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<u16>>()
+        );
+    }
 }
@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use util::flag_is_set;
+use util::{flag_is_set, join_source_keywords, sort_by_flag_bit};
 
 // Access flag masks are from Table 4.1-B of the JVM specification
 //
@@ -15,6 +15,16 @@ const ANNOTATION_FLAG: u16 = 0x2000;
 const ENUM_FLAG: u16 = 0x4000;
 const MODULE_FLAG: u16 = 0x8000;
 
+const RECOGNIZED_FLAGS: u16 = PUBLIC_FLAG
+    | FINAL_FLAG
+    | SUPER_FLAG
+    | INTERFACE_FLAG
+    | ABSTRACT_FLAG
+    | SYNTHETIC_FLAG
+    | ANNOTATION_FLAG
+    | ENUM_FLAG
+    | MODULE_FLAG;
+
 /// A flag that denotes an access level or property of a class.
 ///
 /// See the `access_flags` section of Chapter 4.1 of the JVM specification for
@@ -105,6 +115,20 @@ impl ClassAccess {
         Ok(access)
     }
 
+    /// Returns the bits of the given access flag value that do not correspond
+    /// to any class access flag (ex. reserved bits, or bits used by vendor or
+    /// future versions of the JVM).
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::ClassAccess;
+    /// #
+    /// assert_eq!(0x0000, ClassAccess::unrecognized_flags(0x0021));
+    /// assert_eq!(0x0100, ClassAccess::unrecognized_flags(0x0121));
+    /// ```
+    pub fn unrecognized_flags(access_flags: u16) -> u16 {
+        access_flags & !RECOGNIZED_FLAGS
+    }
+
     #[allow(clippy::wrong_self_convention)]
     pub fn to_access_flags(access_flags: &HashSet<ClassAccess>) -> u16 {
         use ClassAccess::*;
@@ -141,4 +165,71 @@ impl ClassAccess {
 
         flags
     }
+
+    /// Returns the given flags in the canonical order used by javap.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::ClassAccess;
+    /// #
+    /// let access_flags = ClassAccess::from_access_flags(0x0601).unwrap();
+    ///
+    /// assert_eq!(
+    ///     vec![ClassAccess::Public, ClassAccess::Interface, ClassAccess::Abstract],
+    ///     ClassAccess::canonical_order(&access_flags)
+    /// );
+    /// ```
+    pub fn canonical_order(access_flags: &HashSet<ClassAccess>) -> Vec<ClassAccess> {
+        sort_by_flag_bit(access_flags, ClassAccess::access_flag)
+    }
+
+    /// Returns the bit of the access flags that corresponds to the flag.
+    fn access_flag(&self) -> u16 {
+        use ClassAccess::*;
+
+        match self {
+            Public => PUBLIC_FLAG,
+            Final => FINAL_FLAG,
+            Super => SUPER_FLAG,
+            Interface => INTERFACE_FLAG,
+            Abstract => ABSTRACT_FLAG,
+            Synthetic => SYNTHETIC_FLAG,
+            Annotation => ANNOTATION_FLAG,
+            Enum => ENUM_FLAG,
+            Module => MODULE_FLAG,
+        }
+    }
+
+    /// Returns the Java source keyword that corresponds to the flag, or
+    /// `None` if the flag has no source modifier form.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::ClassAccess;
+    /// #
+    /// assert_eq!(Some("public"), ClassAccess::Public.source_keyword());
+    /// assert_eq!(None, ClassAccess::Super.source_keyword());
+    /// ```
+    pub fn source_keyword(&self) -> Option<&'static str> {
+        use ClassAccess::*;
+
+        match self {
+            Public => Some("public"),
+            Final => Some("final"),
+            Abstract => Some("abstract"),
+            Super | Interface | Synthetic | Annotation | Enum | Module => None,
+        }
+    }
+
+    /// Returns the source keywords of the given flags joined in the canonical
+    /// Java modifier order.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::ClassAccess;
+    /// #
+    /// let access_flags = ClassAccess::from_access_flags(0x0031).unwrap();
+    ///
+    /// assert_eq!("public final", ClassAccess::source_modifiers(&access_flags));
+    /// ```
+    pub fn source_modifiers(access_flags: &HashSet<ClassAccess>) -> String {
+        join_source_keywords(access_flags.iter().filter_map(ClassAccess::source_keyword))
+    }
 }
@@ -1,15 +1,60 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::io;
-use std::io::{Read, Write};
+use std::io::{BufRead, BufWriter, Read, Write};
+use std::iter;
+use std::mem;
 use std::ops::Deref;
 
 use attribute::*;
+use bootstrap_method::*;
+use bytecode::{Bytecode, InvokeKind};
 use class_access::*;
+use class_kind::ClassKind;
+use compaction;
 use constant_pool::*;
+use constant_references::{self, ConstantReferrer};
+use constant_validation;
+use crc::Crc32Reader;
+use descriptor::{ClassType, FieldType, MethodDescriptor};
+use diff::{self, Difference};
+use disassembly::{self, DisassemblyOptions};
 use field::*;
+use field_access::*;
+use hashing;
+use inner_class::InnerClass;
+use member_ref::*;
 use method::*;
-use parsing;
-use writing;
+use method_handle::*;
+use parsing::{self, ParseOptions};
+use record::*;
+use relocation;
+use signature::{self, TypeParameter};
+use structure_validation;
+use util::{flag_is_set, io_err, with_hex_context, PositionedReader};
+use verification_type::VerificationType;
+use writing::{self, WriteOptions};
+
+#[cfg(feature = "tokio")]
+use async_parsing;
+#[cfg(feature = "tokio")]
+use std::future::Future;
+#[cfg(feature = "tokio")]
+use tokio::io::AsyncRead;
+
+/// The names of the attributes that only contain debug information.
+const DEBUG_ATTRIBUTES: [&str; 5] = [
+    "LineNumberTable",
+    "LocalVariableTable",
+    "LocalVariableTypeTable",
+    "SourceFile",
+    "SourceDebugExtension",
+];
+
+const MAIN_METHOD_NAME: &str = "main";
+const MAIN_METHOD_DESCRIPTOR: &str = "([Ljava/lang/String;)V";
+
+/// The minor version of class files that depend on preview features.
+const PREVIEW_MINOR_VERSION: u16 = 0xFFFF;
 
 /// A representation of a JVM class file.
 ///
@@ -23,6 +68,10 @@ pub struct ClassFile {
     pub major_version: u16,
     pub constant_pool: Vec<ConstantPoolEntry>,
     pub access_flags: HashSet<ClassAccess>,
+    /// The bits of the access flags of the class that do not correspond to
+    /// any `ClassAccess` flag. These are kept so that they are written back
+    /// out unchanged.
+    pub unrecognized_access_flags: u16,
     pub this_class: ConstantPoolIndex,
     pub super_class: ConstantPoolIndex,
     pub interfaces: Vec<ConstantPoolIndex>,
@@ -35,6 +84,9 @@ impl ClassFile {
     /// Parses the given class file. Fails if the given file is not a valid
     /// class file.
     ///
+    /// The file is read in many small reads, so it should be buffered when
+    /// reading from a file on disk, see `from_buf_reader`.
+    ///
     /// ```
     /// # use std::fs::File;
     /// # use jvm_class_file_parser::ClassFile;
@@ -46,47 +98,150 @@ impl ClassFile {
         parsing::read_class_file(file)
     }
 
-    pub fn to_file<W: Write>(&self, file: &mut W) -> io::Result<()> {
-        writing::write_class_file(file, self)
+    /// Parses the class file from the given buffered reader, which greatly
+    /// reduces the number of reads made to the underlying file. Any bytes
+    /// after the end of the class file are left in the buffer of the reader.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use std::io::BufReader;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let file = File::open("classes/Dummy.class").unwrap();
+    /// let class_file = ClassFile::from_buf_reader(&mut BufReader::new(file)).unwrap();
+    ///
+    /// assert_eq!("Dummy", class_file.get_class_name());
+    /// ```
+    pub fn from_buf_reader<R: BufRead>(reader: &mut R) -> io::Result<ClassFile> {
+        parsing::read_class_file(reader)
     }
 
-    /// Returns the name of the class file.
+    /// Parses the class file from the given reader, also returning the CRC-32
+    /// of the bytes of the class file. The checksum is computed as the bytes
+    /// are parsed, so the file only needs to be read once.
     ///
     /// ```
     /// # use std::fs::File;
     /// # use jvm_class_file_parser::ClassFile;
     /// #
     /// let mut file = File::open("classes/Dummy.class").unwrap();
-    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    /// let (class_file, crc32) = ClassFile::from_file_with_crc32(&mut file).unwrap();
     ///
     /// assert_eq!("Dummy", class_file.get_class_name());
     /// ```
-    pub fn get_class_name(&self) -> &str {
-        use ConstantPoolEntry::*;
+    pub fn from_file_with_crc32<R: Read>(file: &mut R) -> io::Result<(ClassFile, u32)> {
+        let mut reader = Crc32Reader::new(file);
 
-        let class = self.get_constant(self.this_class as usize);
+        let class_file = parsing::read_class_file(&mut reader)?;
 
-        if let ConstantClass { name_index } = *class.deref() {
-            let class_name = self.get_constant(name_index as usize);
+        Ok((class_file, reader.crc32()))
+    }
 
-            if let ConstantUtf8 { string } = class_name {
-                string
-            } else {
-                panic!("The \"name_index\" pointed to by \"this_class\" did not point to a ConstantUtf8. Found: {:?}", class_name.deref())
-            }
-        } else {
-            panic!(
-                "The \"this_class\" did not point to a ConstantClass. Found: {:?}",
-                class.deref()
-            )
+    /// Parses the class file from the given bytes. If parsing fails, then the
+    /// error includes a hex dump of the bytes around the offset at which it
+    /// failed.
+    ///
+    /// ```
+    /// # use std::fs;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let bytes = fs::read("classes/Dummy.class").unwrap();
+    ///
+    /// let error = ClassFile::from_bytes_with_diagnostics(&bytes[..20]).unwrap_err();
+    ///
+    /// assert!(error.to_string().starts_with("Failed at offset 0x14."));
+    /// ```
+    pub fn from_bytes_with_diagnostics(bytes: &[u8]) -> io::Result<ClassFile> {
+        let mut reader = PositionedReader::new(bytes);
+
+        parsing::read_class_file(&mut reader)
+            .map_err(|e| with_hex_context(e, bytes, reader.position()))
+    }
+
+    /// Reads the name of the class from the given reader, without parsing the
+    /// rest of the class file.
+    ///
+    /// Only the header and constant pool are read, so this is much faster than
+    /// a full parse when scanning many class files for their names. The reader
+    /// is left positioned just after the `this_class` index.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    ///
+    /// assert_eq!("Dummy", ClassFile::read_class_name_only(&mut file).unwrap());
+    /// ```
+    pub fn read_class_name_only<R: Read>(file: &mut R) -> io::Result<String> {
+        parsing::read_class_name(file)
+    }
+
+    /// Parses the class file from the given reader, using the given options.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::{ClassFile, ParseOptions};
+    /// #
+    /// let options = ParseOptions {
+    ///     structured_attributes: true,
+    ///     ..ParseOptions::default()
+    /// };
+    ///
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let class_file = ClassFile::from_file_with_options(&mut file, &options).unwrap();
+    ///
+    /// assert!(class_file.methods[0].attributes.attributes[0].structured.is_some());
+    /// ```
+    pub fn from_file_with_options<R: Read>(
+        file: &mut R,
+        options: &ParseOptions,
+    ) -> io::Result<ClassFile> {
+        let mut class_file = parsing::read_class_file_with_options(file, options)?;
+
+        if options.structured_attributes {
+            class_file.decode_attributes()?;
         }
+
+        Ok(class_file)
     }
 
-    /// Returns the name of the source file that the class file was compiled
-    /// from.
+    /// Parses the class file from the given async reader. Fails if the read
+    /// fails or if the read bytes are not a valid class file.
     ///
-    /// If the class file does not have a `SourceFile` attribute, then a `None`
-    /// option is returned.
+    /// The reader is read to its end before the class file is parsed, so the
+    /// parsing itself never blocks on the reader.
+    ///
+    /// Requires the `tokio` feature.
+    #[cfg(feature = "tokio")]
+    pub fn from_async_read<'a, R: AsyncRead + Unpin>(
+        file: &'a mut R,
+    ) -> impl Future<Output = io::Result<ClassFile>> + 'a {
+        async_parsing::ReadClassFile::new(file)
+    }
+
+    /// Parses all of the class files in the given reader, which are expected
+    /// to be concatenated one after another. Parsing stops at the first EOF
+    /// that falls between two class files, and fails if a class file is
+    /// truncated.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let class_files = ClassFile::parse_all(&mut file).unwrap();
+    ///
+    /// assert_eq!(1, class_files.len());
+    /// ```
+    pub fn parse_all<R: Read>(file: &mut R) -> io::Result<Vec<ClassFile>> {
+        parsing::read_class_files(file)
+    }
+
+    /// Writes the class file to the given writer.
+    ///
+    /// The writes to the given writer are buffered, and the buffer is flushed
+    /// before returning.
     ///
     /// ```
     /// # use std::fs::File;
@@ -95,148 +250,1977 @@ impl ClassFile {
     /// let mut file = File::open("classes/Dummy.class").unwrap();
     /// let class_file = ClassFile::from_file(&mut file).unwrap();
     ///
-    /// assert_eq!(Some("Dummy.java"), class_file.get_source_file_name());
+    /// let mut bytes = vec![];
+    /// class_file.to_file(&mut bytes).unwrap();
+    ///
+    /// assert_eq!(class_file, ClassFile::from_file(&mut &bytes[..]).unwrap());
     /// ```
-    pub fn get_source_file_name(&self) -> Option<&str> {
-        use ConstantPoolEntry::*;
+    pub fn to_file<W: Write>(&self, file: &mut W) -> io::Result<()> {
+        let mut writer = BufWriter::new(file);
 
-        for attr in self.attributes.attributes.iter() {
-            let name_constant = self.get_constant(attr.attribute_name_index as usize);
+        writing::write_class_file(&mut writer, self)?;
 
-            if let ConstantUtf8 { ref string } = *name_constant.deref() {
-                if string == "SourceFile" {
-                    if attr.info.len() != 2 {
-                        panic!("Incorrectly formatted SourceFile attribute. Expected info length of 2, found: {}", attr.info.len());
-                    }
+        writer.flush()
+    }
+
+    /// Writes the class file to the given writer, using the given options.
+    ///
+    /// The class file itself is left unchanged, even if the options cause a
+    /// modified copy of it to be written.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::{ClassFile, WriteOptions};
+    /// # use jvm_class_file_parser::ConstantPoolEntry::*;
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let mut class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// class_file.add_constant(ConstantUtf8 {
+    ///     string: "Code".to_string(),
+    /// });
+    ///
+    /// let options = WriteOptions {
+    ///     dedup_constants: true,
+    ///     ..WriteOptions::default()
+    /// };
+    ///
+    /// let mut bytes = vec![];
+    /// class_file.to_file_with_options(&mut bytes, &options).unwrap();
+    ///
+    /// let written = ClassFile::from_file(&mut &bytes[..]).unwrap();
+    /// assert_eq!(12, written.constant_pool.len());
+    /// ```
+    pub fn to_file_with_options<W: Write>(
+        &self,
+        file: &mut W,
+        options: &WriteOptions,
+    ) -> io::Result<()> {
+        if options.validate_method_access {
+            writing::validate_method_access(&self.methods)?;
+        }
 
-                    let info = [attr.info[0], attr.info[1]];
-                    let source_file_index = u16::from_be_bytes(info);
-                    let source_constant = self.get_constant(source_file_index as usize);
+        if options.dedup_constants {
+            let mut deduplicated = self.clone();
+            deduplicated.dedup_constant_pool()?;
 
-                    if let ConstantUtf8 { string } = source_constant {
-                        return Some(string);
-                    } else {
-                        panic!("The \"info\" of the \"SourceFile\" annotation did not point to a ConstantUtf8. Found: {:?}", source_constant.deref());
-                    }
-                }
-            }
+            return deduplicated.to_file(file);
         }
 
-        None
+        self.to_file(file)
     }
 
-    /// Returns a string representation of the specified Utf8 constant.
+    /// Returns a hash of the semantic contents of the class file.
+    ///
+    /// The hash covers the name, super class, interfaces, fields, and the
+    /// signatures and code of the methods, with constant pool indexes
+    /// resolved to the constants they point to. Because of this, the hash
+    /// does not depend on the ordering of the constant pool, or on debug
+    /// attributes such as `LineNumberTable` or `SourceFile`.
+    ///
+    /// Fails if the Code attribute of one of the methods is malformed, or
+    /// contains an undefined opcode, or if the constants fail
+    /// `validate_constant_references` (ex. a circular constant).
     ///
     /// ```
     /// # use std::fs::File;
     /// # use jvm_class_file_parser::ClassFile;
     /// #
+    /// let mut file = File::open("classes/IntBox.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let mut file = File::open("classes/no_debug/IntBox.class").unwrap();
+    /// let class_file_no_debug = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert_eq!(
+    ///     class_file.semantic_hash().unwrap(),
+    ///     class_file_no_debug.semantic_hash().unwrap()
+    /// );
+    /// ```
+    pub fn semantic_hash(&self) -> io::Result<u64> {
+        hashing::semantic_hash(self)
+    }
+
+    /// Returns a javap style description of the class file, formatted
+    /// according to the given options.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::{ClassFile, DisassemblyOptions};
+    /// #
     /// let mut file = File::open("classes/Dummy.class").unwrap();
     /// let class_file = ClassFile::from_file(&mut file).unwrap();
     ///
-    /// assert_eq!("<init>", class_file.get_constant_utf8(4));
+    /// let output = class_file.disassemble(&DisassemblyOptions::default());
+    ///
+    /// assert!(output.contains("   #2 = Class               #11             // Dummy\n"));
     /// ```
-    pub fn get_constant_utf8(&self, index: usize) -> &str {
-        use ConstantPoolEntry::*;
+    pub fn disassemble(&self, options: &DisassemblyOptions) -> String {
+        disassembly::disassemble(self, options)
+    }
 
-        let constant_utf8 = self.get_constant(index);
+    /// Removes the debug information attributes (`LineNumberTable`,
+    /// `LocalVariableTable`, `LocalVariableTypeTable`, `SourceFile`, and
+    /// `SourceDebugExtension`) from the class, its fields and methods, and the
+    /// Code attributes of its methods.
+    ///
+    /// The constants that were only used by the removed attributes are then
+    /// removed from the constant pool, as with `gc_constant_pool`. If the
+    /// constant pool cannot be collected (ex. the class has a non-standard
+    /// attribute whose constant references are not known), it is left as is.
+    ///
+    /// Fails if the Code attribute of one of the methods cannot be parsed.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let mut class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// class_file.strip_debug_info().unwrap();
+    ///
+    /// assert_eq!(None, class_file.get_source_file_name());
+    /// ```
+    pub fn strip_debug_info(&mut self) -> io::Result<()> {
+        let debug_attribute_indexes = self
+            .constant_pool
+            .iter()
+            .enumerate()
+            .filter(|(_, constant)| match *constant {
+                ConstantPoolEntry::ConstantUtf8 { ref string } => {
+                    DEBUG_ATTRIBUTES.contains(&string.as_str())
+                }
+                _ => false,
+            })
+            // account for 1 indexing
+            .map(|(i, _)| i + 1)
+            .collect::<HashSet<ConstantPoolIndex>>();
 
-        if let ConstantUtf8 { string } = constant_utf8 {
-            string
-        } else {
-            panic!(
-                "Failed to get constant \"#{}\" as a ConstantUtf8. Found: {:?}",
-                index, constant_utf8
-            )
+        let is_not_debug =
+            |attr: &Attribute| !debug_attribute_indexes.contains(&attr.attribute_name_index);
+
+        // there may be several Utf8 constants holding the name "Code"
+        let code_indexes = self
+            .constant_pool
+            .iter()
+            .enumerate()
+            .filter(|(_, constant)| match *constant {
+                ConstantPoolEntry::ConstantUtf8 { ref string } => string == "Code",
+                _ => false,
+            })
+            // account for 1 indexing
+            .map(|(i, _)| i + 1)
+            .collect::<HashSet<ConstantPoolIndex>>();
+
+        self.attributes.attributes.retain(is_not_debug);
+
+        for field in self.fields.iter_mut() {
+            field.attributes.attributes.retain(is_not_debug);
+        }
+
+        for method in self.methods.iter_mut() {
+            method.attributes.attributes.retain(is_not_debug);
+
+            for attr in method.attributes.attributes.iter_mut() {
+                if code_indexes.contains(&attr.attribute_name_index) {
+                    attr.info = retain_code_attributes(&attr.info, is_not_debug)?;
+
+                    if let Some(StructuredAttribute::Code(ref mut code)) = attr.structured {
+                        code.attributes.attributes.retain(is_not_debug);
+                    }
+                }
+            }
         }
+
+        let mut compacted = self.clone();
+        if compacted.gc_constant_pool().is_ok() {
+            *self = compacted;
+        }
+
+        Ok(())
     }
 
-    /// Returns a string representation of the specified class constant.
+    /// Removes the constants that are no longer referenced by the class from
+    /// the constant pool, and updates all of the references to the remaining
+    /// constants, including those in attributes and bytecode.
+    ///
+    /// Fails if one of the attributes is malformed, or if it is of a kind
+    /// whose constant references are not known (ex. a non-standard
+    /// attribute), since the constants it references could not be kept. Also
+    /// fails if the code of one of the methods contains an undefined opcode.
     ///
     /// ```
     /// # use std::fs::File;
     /// # use jvm_class_file_parser::ClassFile;
+    /// # use jvm_class_file_parser::ConstantPoolEntry::*;
     /// #
     /// let mut file = File::open("classes/Dummy.class").unwrap();
-    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    /// let mut class_file = ClassFile::from_file(&mut file).unwrap();
     ///
-    /// assert_eq!("java/lang/Object", class_file.get_constant_class_str(3));
+    /// class_file.add_constant(ConstantUtf8 {
+    ///     string: "unused".to_string(),
+    /// });
+    ///
+    /// class_file.gc_constant_pool().unwrap();
+    ///
+    /// assert_eq!(12, class_file.constant_pool.len());
     /// ```
-    pub fn get_constant_class_str(&self, index: usize) -> &str {
-        use ConstantPoolEntry::*;
+    pub fn gc_constant_pool(&mut self) -> io::Result<()> {
+        let was_decoded = self.has_structured_attributes();
 
-        let constant_class = self.get_constant(index);
+        compaction::gc_constant_pool(self)?;
 
-        if let ConstantClass { name_index } = constant_class {
-            self.get_constant_utf8(*name_index as usize)
-        } else {
-            panic!(
-                "Failed to get constant \"#{}\" as a ConstantClass. Found: {:?}",
-                index, constant_class
-            )
+        if was_decoded {
+            self.decode_attributes()?;
         }
+
+        Ok(())
     }
 
-    /// Returns a string representation of the specified name and type
-    /// constant.
+    /// Merges the identical constants in the constant pool, keeping the first
+    /// of each, and updates all of the references to the removed constants,
+    /// including those in attributes and bytecode.
+    ///
+    /// Constants that only differ in which of a set of identical constants
+    /// they reference are merged as well.
+    ///
+    /// Fails in the same cases as `gc_constant_pool`, or if the class
+    /// references an invalid constant pool index.
     ///
     /// ```
     /// # use std::fs::File;
     /// # use jvm_class_file_parser::ClassFile;
+    /// # use jvm_class_file_parser::ConstantPoolEntry::*;
     /// #
     /// let mut file = File::open("classes/Dummy.class").unwrap();
-    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    /// let mut class_file = ClassFile::from_file(&mut file).unwrap();
     ///
-    /// assert_eq!(
-    ///     "\"<init>\":()V",
-    ///     class_file.get_constant_name_and_type_str(10)
-    /// );
+    /// let duplicate = class_file.add_constant(ConstantUtf8 {
+    ///     string: "<init>".to_string(),
+    /// });
+    /// class_file.methods[0].name_index = duplicate;
+    ///
+    /// class_file.dedup_constant_pool().unwrap();
+    ///
+    /// assert_eq!(12, class_file.constant_pool.len());
+    /// assert_eq!("<init>", class_file.get_constant_utf8(class_file.methods[0].name_index));
     /// ```
-    pub fn get_constant_name_and_type_str(&self, index: usize) -> String {
-        use ConstantPoolEntry::*;
+    pub fn dedup_constant_pool(&mut self) -> io::Result<()> {
+        let was_decoded = self.has_structured_attributes();
 
-        let constant_nat = self.get_constant(index);
+        compaction::dedup_constant_pool(self)?;
 
-        if let ConstantNameAndType {
-            name_index,
-            descriptor_index,
-        } = constant_nat
-        {
-            format!(
-                "\"{}\":{}",
-                self.get_constant_utf8(*name_index as usize),
-                self.get_constant_utf8(*descriptor_index as usize),
-            )
-        } else {
-            panic!(
-                "Failed to get constant \"#{}\" as a ConstantNameAndType. Found: {:?}",
-                index, constant_nat
-            )
+        if was_decoded {
+            self.decode_attributes()?;
         }
+
+        Ok(())
     }
 
-    /// Returns the specified constant from the constant pool.
+    fn has_structured_attributes(&self) -> bool {
+        self.attribute_sets().any(|attributes| {
+            attributes
+                .attributes
+                .iter()
+                .any(|attr| attr.structured.is_some())
+        })
+    }
+
+    /// Decodes each of the attributes of the class, its fields, and its
+    /// methods that is of a known kind (ex. `Code`, `SourceFile`) into its
+    /// structured form, which is stored alongside its info bytes.
     ///
-    /// This method exists in order to encapsulate the fact that the constant
-    /// pool indexes start at 1 rather than 0.
+    /// This trades the lazy decoding of attributes for a fully decoded model
+    /// of the class file. The structured form of each attribute is kept up to
+    /// date by the methods that modify the class file, but not by direct
+    /// changes to the info bytes of an attribute.
+    ///
+    /// Fails if one of the attributes of a known kind is malformed.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::{ClassFile, StructuredAttribute};
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let mut class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// class_file.decode_attributes().unwrap();
+    ///
+    /// match class_file.attributes.attributes[0].structured {
+    ///     Some(StructuredAttribute::SourceFile(index)) => {
+    ///         assert_eq!("Dummy.java", class_file.get_constant_utf8(index));
+    ///     }
+    ///     ref other => panic!("Expected a SourceFile attribute, got {:?}", other),
+    /// }
+    /// ```
+    pub fn decode_attributes(&mut self) -> io::Result<()> {
+        let mut fields = mem::take(&mut self.fields);
+        let mut methods = mem::take(&mut self.methods);
+        let mut attributes =
+            mem::replace(&mut self.attributes, AttributeSet { attributes: vec![] });
+
+        let result = iter::once(&mut attributes)
+            .chain(fields.iter_mut().map(|field| &mut field.attributes))
+            .chain(methods.iter_mut().map(|method| &mut method.attributes))
+            .try_for_each(|attributes| attributes.decode(self));
+
+        self.fields = fields;
+        self.methods = methods;
+        self.attributes = attributes;
+
+        result
+    }
+
+    /// Returns the attribute sets of the class, its fields, and its methods.
+    fn attribute_sets(&self) -> impl Iterator<Item = &AttributeSet> {
+        iter::once(&self.attributes)
+            .chain(self.fields.iter().map(|field| &field.attributes))
+            .chain(self.methods.iter().map(|method| &method.attributes))
+    }
+
+    /// Returns the access flags of the class as the raw value that is stored
+    /// in the class file, including any unrecognized bits.
     ///
     /// ```
     /// # use std::fs::File;
-    /// # use std::ops::Deref;
     /// # use jvm_class_file_parser::ClassFile;
-    /// # use jvm_class_file_parser::ConstantPoolEntry::*;
     /// #
     /// let mut file = File::open("classes/Dummy.class").unwrap();
     /// let class_file = ClassFile::from_file(&mut file).unwrap();
     ///
-    /// assert_eq!(
-    ///     ConstantClass {
-    ///         name_index: 11,
-    ///     },
-    ///     *class_file.get_constant(2).deref()
-    /// );
+    /// assert_eq!(0x0021, class_file.raw_access_flags());
     /// ```
-    pub fn get_constant(&self, index: usize) -> &ConstantPoolEntry {
-        &self.constant_pool[index - 1]
+    pub fn raw_access_flags(&self) -> u16 {
+        ClassAccess::to_access_flags(&self.access_flags) | self.unrecognized_access_flags
+    }
+
+    /// Returns whether the class file describes an interface, including
+    /// annotation interfaces.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Interface.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert!(class_file.is_interface());
+    /// ```
+    pub fn is_interface(&self) -> bool {
+        self.access_flags.contains(&ClassAccess::Interface)
+    }
+
+    /// Returns whether the class file describes an enum class.
+    pub fn is_enum(&self) -> bool {
+        self.access_flags.contains(&ClassAccess::Enum)
+    }
+
+    /// Returns whether the class file describes an annotation interface.
+    pub fn is_annotation(&self) -> bool {
+        self.access_flags.contains(&ClassAccess::Annotation)
+    }
+
+    /// Returns whether the class file describes an abstract class or an
+    /// interface.
+    pub fn is_abstract(&self) -> bool {
+        self.access_flags.contains(&ClassAccess::Abstract)
     }
+
+    /// Returns whether the class file depends on the preview features of its
+    /// Java SE release, which is marked by a minor version of `0xFFFF`.
+    ///
+    /// See Chapter 4.1 of the JVM specification for details.
+    ///
+    /// https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.1
+    pub fn is_preview(&self) -> bool {
+        self.minor_version == PREVIEW_MINOR_VERSION
+    }
+
+    /// Returns a summary of what kind of class the class file describes.
+    ///
+    /// The kind is determined from the access flags of the class, whether it
+    /// has a `Record` attribute, and whether it has a main method. Where more
+    /// than one kind applies, the more specific one is returned (ex. an
+    /// annotation interface is an `Annotation` rather than an `Interface`).
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::{ClassFile, ClassKind};
+    /// #
+    /// let mut file = File::open("classes/Color.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert_eq!(ClassKind::Enum, class_file.classify());
+    /// ```
+    pub fn classify(&self) -> ClassKind {
+        if self.access_flags.contains(&ClassAccess::Module) {
+            ClassKind::Module
+        } else if self.is_annotation() {
+            ClassKind::Annotation
+        } else if self.is_interface() {
+            ClassKind::Interface
+        } else if self.is_enum() {
+            ClassKind::Enum
+        } else if self.attributes.find_attribute(self, "Record").is_some() {
+            ClassKind::Record
+        } else if self.main_method().is_some() {
+            ClassKind::Application
+        } else {
+            ClassKind::Plain
+        }
+    }
+
+    /// Returns the names of the constants of the enum class, in the order in
+    /// which they are declared.
+    ///
+    /// The constants are the static final fields of the class whose type is
+    /// the class itself. If the class is not an enum class, then an empty list
+    /// is returned.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Color.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert_eq!(vec!["RED", "GREEN", "BLUE"], class_file.enum_constants());
+    /// ```
+    pub fn enum_constants(&self) -> Vec<&str> {
+        if !self.is_enum() {
+            return vec![];
+        }
+
+        let enum_descriptor = format!("L{};", self.get_class_name());
+
+        self.fields
+            .iter()
+            .filter(|field| {
+                field.access_flags.contains(&FieldAccess::Static)
+                    && field.access_flags.contains(&FieldAccess::Final)
+                    && self.get_constant_utf8(field.descriptor_index) == enum_descriptor
+            })
+            .map(|field| self.get_constant_utf8(field.name_index))
+            .collect()
+    }
+
+    /// Returns the name of the class file.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert_eq!("Dummy", class_file.get_class_name());
+    /// ```
+    pub fn get_class_name(&self) -> &str {
+        use ConstantPoolEntry::*;
+
+        let class = self.get_constant(self.this_class as usize);
+
+        if let ConstantClass { name_index } = *class.deref() {
+            let class_name = self.get_constant(name_index as usize);
+
+            if let ConstantUtf8 { string } = class_name {
+                string
+            } else {
+                panic!("The \"name_index\" pointed to by \"this_class\" did not point to a ConstantUtf8. Found: {:?}", class_name.deref())
+            }
+        } else {
+            panic!(
+                "The \"this_class\" did not point to a ConstantClass. Found: {:?}",
+                class.deref()
+            )
+        }
+    }
+
+    /// Returns the name of the source file that the class file was compiled
+    /// from.
+    ///
+    /// If the class file does not have a `SourceFile` attribute, then a `None`
+    /// option is returned.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert_eq!(Some("Dummy.java"), class_file.get_source_file_name());
+    /// ```
+    pub fn get_source_file_name(&self) -> Option<&str> {
+        use ConstantPoolEntry::*;
+
+        if let Some(attr) = self.attributes.find_attribute(self, "SourceFile") {
+            if attr.info.len() != 2 {
+                panic!("Incorrectly formatted SourceFile attribute. Expected info length of 2, found: {}", attr.info.len());
+            }
+
+            let info = [attr.info[0], attr.info[1]];
+            let source_file_index = u16::from_be_bytes(info);
+            let source_constant = self.get_constant(source_file_index as usize);
+
+            if let ConstantUtf8 { string } = source_constant {
+                return Some(string);
+            } else {
+                panic!("The \"info\" of the \"SourceFile\" annotation did not point to a ConstantUtf8. Found: {:?}", source_constant.deref());
+            }
+        }
+
+        None
+    }
+
+    /// Returns the name of the host class of the nest that the class is a
+    /// member of, as given by its `NestHost` attribute.
+    ///
+    /// If the class file does not have a `NestHost` attribute, then a `None`
+    /// option is returned.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Nest$Inner.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert_eq!(Some("Nest"), class_file.get_nest_host());
+    /// ```
+    pub fn get_nest_host(&self) -> Option<&str> {
+        self.attributes
+            .find_attribute(self, "NestHost")
+            .map(|attr| {
+                if attr.info.len() != 2 {
+                    panic!("Incorrectly formatted NestHost attribute. Expected info length of 2, found: {}", attr.info.len());
+                }
+
+                let host_class_index = u16::from_be_bytes([attr.info[0], attr.info[1]]);
+
+                self.get_constant_class_str(host_class_index as usize)
+            })
+    }
+
+    /// Returns the names of the members of the nest that the class is the host
+    /// of, as given by its `NestMembers` attribute.
+    ///
+    /// If the class file does not have a `NestMembers` attribute, then an
+    /// empty list is returned.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Nest.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert_eq!(
+    ///     vec!["Nest$Hidden", "Nest$Inner"],
+    ///     class_file.get_nest_members()
+    /// );
+    /// ```
+    pub fn get_nest_members(&self) -> Vec<&str> {
+        match self.attributes.find_attribute(self, "NestMembers") {
+            Some(attr) => read_class_table(self, attr, "NestMembers"),
+            None => vec![],
+        }
+    }
+
+    /// Returns the names of the packages of the module, as given by its
+    /// `ModulePackages` attribute.
+    ///
+    /// If the class file does not have a `ModulePackages` attribute, then an
+    /// empty list is returned.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/module-info.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert_eq!(vec!["com/example/app"], class_file.get_module_packages());
+    /// ```
+    pub fn get_module_packages(&self) -> Vec<&str> {
+        match self.attributes.find_attribute(self, "ModulePackages") {
+            Some(attr) => read_index_table(attr, "ModulePackages")
+                .into_iter()
+                .map(|index| self.get_constant_package_str(index as usize))
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// Returns the name of the main class of the module, as given by its
+    /// `ModuleMainClass` attribute.
+    ///
+    /// If the class file does not have a `ModuleMainClass` attribute, then a
+    /// `None` option is returned.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/module-info.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert_eq!(
+    ///     Some("com/example/app/Main"),
+    ///     class_file.get_module_main_class()
+    /// );
+    /// ```
+    pub fn get_module_main_class(&self) -> Option<&str> {
+        self.attributes
+            .find_attribute(self, "ModuleMainClass")
+            .map(|attr| {
+                if attr.info.len() != 2 {
+                    panic!("Incorrectly formatted ModuleMainClass attribute. Expected info length of 2, found: {}", attr.info.len());
+                }
+
+                let main_class_index = u16::from_be_bytes([attr.info[0], attr.info[1]]);
+
+                self.get_constant_class_str(main_class_index as usize)
+            })
+    }
+
+    /// Returns the names of the classes that are permitted to extend or
+    /// implement the class, as given by its `PermittedSubclasses` attribute.
+    ///
+    /// If the class file does not have a `PermittedSubclasses` attribute (ie.
+    /// the class is not sealed), then a `None` option is returned.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Shape.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert_eq!(
+    ///     Some(vec!["Circle", "Square"]),
+    ///     class_file.get_permitted_subclasses()
+    /// );
+    /// ```
+    pub fn get_permitted_subclasses(&self) -> Option<Vec<&str>> {
+        self.attributes
+            .find_attribute(self, "PermittedSubclasses")
+            .map(|attr| read_class_table(self, attr, "PermittedSubclasses"))
+    }
+
+    /// Returns the components of the record class, as given by its `Record`
+    /// attribute.
+    ///
+    /// If the class file does not have a `Record` attribute (ie. the class is
+    /// not a record), then a `None` option is returned.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Point.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let components = class_file.get_record_components().unwrap();
+    ///
+    /// assert_eq!("x", components[0].get_name(&class_file));
+    /// assert_eq!("I", components[0].get_descriptor(&class_file));
+    /// ```
+    pub fn get_record_components(&self) -> Option<Vec<RecordComponent>> {
+        self.attributes.find_attribute(self, "Record").map(
+            |attr| match RecordComponent::from_bytes(&attr.info) {
+                Ok(components) => components,
+                Err(e) => panic!("Incorrectly formatted Record attribute. {}", e),
+            },
+        )
+    }
+
+    /// Returns the semantic differences between this class and the given
+    /// one, treating this class as the old version and the given class as the
+    /// new version.
+    ///
+    /// Fields and methods are matched up by their names and descriptors, and
+    /// attributes by their names. The contents of attributes are compared by
+    /// the constants they reference rather than the indexes of those
+    /// constants, so rearranging the constant pool does not count as a change.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let mut compacted = class_file.clone();
+    /// compacted.gc_constant_pool().unwrap();
+    ///
+    /// assert!(class_file.diff(&compacted).is_empty());
+    /// ```
+    pub fn diff(&self, other: &ClassFile) -> Vec<Difference> {
+        diff::diff(self, other)
+    }
+
+    /// Returns the type parameters declared by the class, as given by its
+    /// `Signature` attribute. Returns an empty list if the class is not
+    /// generic.
+    ///
+    /// Panics if the signature of the class is malformed.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Generic.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let type_parameters = class_file.type_parameters();
+    ///
+    /// assert_eq!("T", type_parameters[0].name);
+    /// assert_eq!(
+    ///     "U extends java.lang.Number & java.lang.Comparable<U>",
+    ///     type_parameters[1].to_string()
+    /// );
+    /// ```
+    pub fn type_parameters(&self) -> Vec<TypeParameter> {
+        let signature = match self.attributes.get_signature(self) {
+            Some(signature) => signature,
+            None => return Vec::new(),
+        };
+
+        match signature::parse_type_parameters(&signature) {
+            Some(type_parameters) => type_parameters,
+            None => panic!("Malformed class signature: {}", signature),
+        }
+    }
+
+    /// Returns the fields and methods that are referenced by the constant pool
+    /// of the class, in the order of their Fieldref, Methodref, and
+    /// InterfaceMethodref constants.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::{ClassFile, MemberRefKind};
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let members = class_file.referenced_members();
+    ///
+    /// assert_eq!(1, members.len());
+    /// assert_eq!(MemberRefKind::Method, members[0].kind);
+    /// assert_eq!("java/lang/Object", members[0].class_name);
+    /// assert_eq!("<init>", members[0].name);
+    /// assert_eq!("()V", members[0].descriptor);
+    /// ```
+    pub fn referenced_members(&self) -> Vec<MemberRef<'_>> {
+        use ConstantPoolEntry::*;
+
+        self.constant_pool
+            .iter()
+            .filter_map(|entry| match *entry {
+                ConstantFieldref {
+                    class_index,
+                    name_and_type_index,
+                } => Some((MemberRefKind::Field, class_index, name_and_type_index)),
+                ConstantMethodref {
+                    class_index,
+                    name_and_type_index,
+                } => Some((MemberRefKind::Method, class_index, name_and_type_index)),
+                ConstantInterfaceMethodref {
+                    class_index,
+                    name_and_type_index,
+                } => Some((
+                    MemberRefKind::InterfaceMethod,
+                    class_index as usize,
+                    name_and_type_index as usize,
+                )),
+                _ => None,
+            })
+            .map(|(kind, class_index, name_and_type_index)| {
+                let (name_index, descriptor_index) = match *self.get_constant(name_and_type_index) {
+                    ConstantNameAndType {
+                        name_index,
+                        descriptor_index,
+                    } => (name_index, descriptor_index),
+                    ref constant_nat => panic!(
+                        "Failed to get constant \"#{}\" as a ConstantNameAndType. Found: {:?}",
+                        name_and_type_index, constant_nat
+                    ),
+                };
+
+                MemberRef {
+                    kind,
+                    class_name: self.get_constant_class_str(class_index),
+                    name: self.get_constant_utf8(name_index),
+                    descriptor: self.get_constant_utf8(descriptor_index),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the bootstrap methods of the class, as given by its
+    /// `BootstrapMethods` attribute.
+    ///
+    /// If the class file does not have a `BootstrapMethods` attribute, then an
+    /// empty list is returned.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/StringConcat.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let bootstrap_methods = class_file.get_bootstrap_methods();
+    ///
+    /// assert_eq!(31, bootstrap_methods[0].bootstrap_method_ref);
+    /// assert_eq!(vec![37], bootstrap_methods[0].bootstrap_arguments);
+    /// ```
+    pub fn get_bootstrap_methods(&self) -> Vec<BootstrapMethod> {
+        match self.attributes.find_attribute(self, "BootstrapMethods") {
+            Some(attr) => match BootstrapMethod::from_bytes(&attr.info) {
+                Ok(bootstrap_methods) => bootstrap_methods,
+                Err(e) => panic!("Incorrectly formatted BootstrapMethods attribute. {}", e),
+            },
+            None => vec![],
+        }
+    }
+
+    /// Returns the nested classes listed in the `InnerClasses` attribute of
+    /// the class, or an empty list if the class does not have one.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Nest.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let inner_classes = class_file.get_inner_classes();
+    ///
+    /// assert_eq!(2, inner_classes.len());
+    /// assert_eq!("Nest$Inner", inner_classes[1].get_class_name(&class_file));
+    /// assert_eq!(Some("Inner"), inner_classes[1].get_inner_name(&class_file));
+    /// ```
+    pub fn get_inner_classes(&self) -> Vec<InnerClass> {
+        match self.attributes.find_attribute(self, "InnerClasses") {
+            Some(attr) => match InnerClass::from_bytes(&attr.info) {
+                Ok(inner_classes) => inner_classes,
+                Err(e) => panic!("Incorrectly formatted InnerClasses attribute. {}", e),
+            },
+            None => vec![],
+        }
+    }
+
+    /// Returns the checked exceptions declared to be thrown by each of the
+    /// methods of the class, keyed by the name and descriptor of the method
+    /// (ex. `run()V`).
+    ///
+    /// Methods that do not declare any exceptions are not included.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/ExceptionThrows.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let throws = class_file.method_throws_map();
+    ///
+    /// assert_eq!(
+    ///     Some(&vec![
+    ///         "java/io/IOException".to_string(),
+    ///         "java/lang/InterruptedException".to_string(),
+    ///     ]),
+    ///     throws.get("declareExceptions()V")
+    /// );
+    /// ```
+    pub fn method_throws_map(&self) -> HashMap<String, Vec<String>> {
+        self.methods
+            .iter()
+            .map(|method| (method, method.get_thrown_exceptions(self)))
+            .filter(|(_, exceptions)| !exceptions.is_empty())
+            .map(|(method, exceptions)| {
+                let signature = format!(
+                    "{}{}",
+                    self.get_constant_utf8(method.name_index),
+                    self.get_constant_utf8(method.descriptor_index)
+                );
+                let exceptions = exceptions.iter().map(|e| e.to_string()).collect();
+
+                (signature, exceptions)
+            })
+            .collect()
+    }
+
+    /// Returns the method with the given name and descriptor, if the class has
+    /// one.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/IntBox.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert!(class_file.find_method("getValue", "()I").is_some());
+    /// assert!(class_file.find_method("getValue", "()J").is_none());
+    /// ```
+    pub fn find_method(&self, name: &str, descriptor: &str) -> Option<&Method> {
+        self.methods.iter().find(|method| {
+            self.get_constant_utf8(method.name_index) == name
+                && self.get_constant_utf8(method.descriptor_index) == descriptor
+        })
+    }
+
+    /// Returns an iterator over the methods of the class, each paired with
+    /// its decoded body. Abstract and native methods are paired with `None`.
+    ///
+    /// The code of each method is only decoded once the iterator reaches it,
+    /// and a method whose code fails to decode is paired with the error,
+    /// rather than ending the iteration. See `Method::get_code` for details.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Interface.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// for (_, code) in class_file.methods_with_code() {
+    ///     assert_eq!(None, code.unwrap());
+    /// }
+    /// ```
+    pub fn methods_with_code(&self) -> impl Iterator<Item = (&Method, io::Result<Option<Code>>)> {
+        self.methods
+            .iter()
+            .map(move |method| (method, method.get_code(self)))
+    }
+
+    /// Returns the fields and methods that share both their name and their
+    /// descriptor with an earlier field or method of the same kind, which the
+    /// JVM does not allow.
+    ///
+    /// Each duplicate is listed once, as `field name:descriptor` or
+    /// `method name:descriptor`, with the fields listed before the methods.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/IntBox.class").unwrap();
+    /// let mut class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert!(class_file.find_duplicate_members().is_empty());
+    ///
+    /// let get_value = class_file.methods[1].clone();
+    /// class_file.methods.push(get_value);
+    ///
+    /// assert_eq!(
+    ///     vec!["method getValue:()I"],
+    ///     class_file.find_duplicate_members()
+    /// );
+    /// ```
+    pub fn find_duplicate_members(&self) -> Vec<String> {
+        let fields = self
+            .fields
+            .iter()
+            .map(|field| ("field", field.name_index, field.descriptor_index));
+        let methods = self
+            .methods
+            .iter()
+            .map(|method| ("method", method.name_index, method.descriptor_index));
+
+        let mut seen = HashSet::new();
+        let mut duplicates = Vec::new();
+        for (kind, name_index, descriptor_index) in fields.chain(methods) {
+            let member = format!(
+                "{} {}:{}",
+                kind,
+                self.get_constant_utf8(name_index),
+                self.get_constant_utf8(descriptor_index)
+            );
+
+            if !seen.insert(member.clone()) && !duplicates.contains(&member) {
+                duplicates.push(member);
+            }
+        }
+
+        duplicates
+    }
+
+    /// Returns the `public static void main(String[])` method of the class, if
+    /// the class has one.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/HelloWorld.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert!(class_file.main_method().is_some());
+    /// ```
+    pub fn main_method(&self) -> Option<&Method> {
+        self.find_method(MAIN_METHOD_NAME, MAIN_METHOD_DESCRIPTOR)
+            .filter(|method| {
+                flag_is_set(METHOD_PUBLIC_FLAG, method.access_flags)
+                    && flag_is_set(METHOD_STATIC_FLAG, method.access_flags)
+            })
+    }
+
+    /// Returns the bridge methods of the class, which are generated by the
+    /// compiler (ex. for overrides of generic methods) and forward to the
+    /// method that they bridge to.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Bridge.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert_eq!(1, class_file.bridge_methods().len());
+    /// ```
+    pub fn bridge_methods(&self) -> Vec<&Method> {
+        self.methods
+            .iter()
+            .filter(|method| method.is_bridge())
+            .collect()
+    }
+
+    /// Returns the methods of the class that match the given predicate.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/IntBox.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let constructors = class_file
+    ///     .methods_matching(|method| class_file.get_constant_utf8(method.name_index) == "<init>");
+    ///
+    /// assert_eq!(1, constructors.len());
+    /// ```
+    pub fn methods_matching<F: Fn(&Method) -> bool>(&self, predicate: F) -> Vec<&Method> {
+        self.methods
+            .iter()
+            .filter(|method| predicate(method))
+            .collect()
+    }
+
+    /// Returns the fields of the class that match the given predicate.
+    pub fn fields_matching<F: Fn(&Field) -> bool>(&self, predicate: F) -> Vec<&Field> {
+        self.fields
+            .iter()
+            .filter(|field| predicate(field))
+            .collect()
+    }
+
+    /// Returns the public methods of the class.
+    pub fn public_methods(&self) -> Vec<&Method> {
+        self.methods_matching(|method| flag_is_set(METHOD_PUBLIC_FLAG, method.access_flags))
+    }
+
+    /// Returns the static methods of the class.
+    pub fn static_methods(&self) -> Vec<&Method> {
+        self.methods_matching(|method| flag_is_set(METHOD_STATIC_FLAG, method.access_flags))
+    }
+
+    /// Returns the public fields of the class.
+    pub fn public_fields(&self) -> Vec<&Field> {
+        self.fields_matching(|field| field.access_flags.contains(&FieldAccess::Public))
+    }
+
+    /// Returns the static fields of the class.
+    pub fn static_fields(&self) -> Vec<&Field> {
+        self.fields_matching(|field| field.access_flags.contains(&FieldAccess::Static))
+    }
+
+    /// Returns a string representation of the specified Utf8 constant.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert_eq!("<init>", class_file.get_constant_utf8(4));
+    /// ```
+    pub fn get_constant_utf8(&self, index: usize) -> &str {
+        use ConstantPoolEntry::*;
+
+        let constant_utf8 = self.get_constant(index);
+
+        if let ConstantUtf8 { string } = constant_utf8 {
+            string
+        } else {
+            panic!(
+                "Failed to get constant \"#{}\" as a ConstantUtf8. Found: {:?}",
+                index, constant_utf8
+            )
+        }
+    }
+
+    /// Returns a string representation of the specified class constant.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert_eq!("java/lang/Object", class_file.get_constant_class_str(3));
+    /// ```
+    pub fn get_constant_class_str(&self, index: usize) -> &str {
+        use ConstantPoolEntry::*;
+
+        let constant_class = self.get_constant(index);
+
+        if let ConstantClass { name_index } = constant_class {
+            self.get_constant_utf8(*name_index as usize)
+        } else {
+            panic!(
+                "Failed to get constant \"#{}\" as a ConstantClass. Found: {:?}",
+                index, constant_class
+            )
+        }
+    }
+
+    /// Returns the type that the specified class constant refers to, which may
+    /// be an array type.
+    ///
+    /// Panics if the constant is not a class constant, or if it names a
+    /// malformed array type.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::{ClassFile, ClassType};
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert_eq!(
+    ///     ClassType::Class("java/lang/Object".to_string()),
+    ///     class_file.get_constant_class_type(3)
+    /// );
+    /// ```
+    pub fn get_constant_class_type(&self, index: usize) -> ClassType {
+        let name = self.get_constant_class_str(index);
+
+        match ClassType::parse(name) {
+            Ok(class_type) => class_type,
+            Err(message) => panic!(
+                "Failed to parse the type of class constant \"#{}\". {}",
+                index, message
+            ),
+        }
+    }
+
+    /// Returns a string representation of the specified package constant.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/module-info.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert_eq!("com/example/app", class_file.get_constant_package_str(9));
+    /// ```
+    pub fn get_constant_package_str(&self, index: usize) -> &str {
+        use ConstantPoolEntry::*;
+
+        let constant_package = self.get_constant(index);
+
+        if let ConstantPackage { name_index } = constant_package {
+            self.get_constant_utf8(*name_index as usize)
+        } else {
+            panic!(
+                "Failed to get constant \"#{}\" as a ConstantPackage. Found: {:?}",
+                index, constant_package
+            )
+        }
+    }
+
+    /// Returns a string representation of the specified name and type
+    /// constant.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert_eq!(
+    ///     "\"<init>\":()V",
+    ///     class_file.get_constant_name_and_type_str(10)
+    /// );
+    /// ```
+    pub fn get_constant_name_and_type_str(&self, index: usize) -> String {
+        use ConstantPoolEntry::*;
+
+        let constant_nat = self.get_constant(index);
+
+        if let ConstantNameAndType {
+            name_index,
+            descriptor_index,
+        } = constant_nat
+        {
+            format!(
+                "\"{}\":{}",
+                self.get_constant_utf8(*name_index as usize),
+                self.get_constant_utf8(*descriptor_index as usize),
+            )
+        } else {
+            panic!(
+                "Failed to get constant \"#{}\" as a ConstantNameAndType. Found: {:?}",
+                index, constant_nat
+            )
+        }
+    }
+
+    /// Returns the name and descriptor of the specified name and type
+    /// constant.
+    ///
+    /// Returns `None` if the index is not that of a name and type constant
+    /// whose name and descriptor are both Utf8 constants.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert_eq!(Some(("<init>", "()V")), class_file.get_name_and_type(10));
+    /// assert_eq!(None, class_file.get_name_and_type(1));
+    /// ```
+    pub fn get_name_and_type(&self, index: usize) -> Option<(&str, &str)> {
+        use ConstantPoolEntry::*;
+
+        let utf8 = |index: usize| match self.constant_pool.get(index.checked_sub(1)?) {
+            Some(ConstantUtf8 { string }) => Some(string.as_str()),
+            _ => None,
+        };
+
+        match self.constant_pool.get(index.checked_sub(1)?)? {
+            ConstantNameAndType {
+                name_index,
+                descriptor_index,
+            } => Some((utf8(*name_index)?, utf8(*descriptor_index)?)),
+            _ => None,
+        }
+    }
+
+    /// Returns the type of the value that an `ldc`, `ldc_w`, or `ldc2_w` of
+    /// the specified constant pushes onto the operand stack.
+    ///
+    /// Returns `None` if the index is not that of a loadable constant, or if
+    /// it is a dynamically computed constant with a malformed descriptor.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::{ClassFile, VerificationType};
+    /// #
+    /// let mut file = File::open("classes/StaticConstantValues.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert_eq!(
+    ///     Some(VerificationType::Object("java/lang/String".to_string())),
+    ///     class_file.ldc_result_type(26)
+    /// );
+    /// ```
+    pub fn ldc_result_type(&self, index: usize) -> Option<VerificationType> {
+        use ConstantPoolEntry::*;
+
+        let object = |class_name: &str| Some(VerificationType::Object(class_name.to_string()));
+
+        match self.constant_pool.get(index.checked_sub(1)?)? {
+            ConstantInteger { .. } => Some(VerificationType::Integer),
+            ConstantFloat { .. } => Some(VerificationType::Float),
+            ConstantLong { .. } => Some(VerificationType::Long),
+            ConstantDouble { .. } => Some(VerificationType::Double),
+            ConstantString { .. } => object("java/lang/String"),
+            ConstantClass { .. } => object("java/lang/Class"),
+            ConstantMethodType { .. } => object("java/lang/invoke/MethodType"),
+            ConstantMethodHandle { .. } => object("java/lang/invoke/MethodHandle"),
+            ConstantDynamic {
+                name_and_type_index,
+                ..
+            } => {
+                let (_, descriptor) = self.get_name_and_type(*name_and_type_index as usize)?;
+
+                FieldType::parse(descriptor)
+                    .ok()
+                    .map(|field_type| VerificationType::from_field_type(&field_type))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the number of operand stack slots that the given invoke
+    /// instruction pops as the arguments of the method that it calls. This
+    /// includes the receiver of the call for all but `invokestatic` and
+    /// `invokedynamic`.
+    ///
+    /// Returns `None` if the instruction is not an invoke instruction, or if
+    /// the method that it calls can not be resolved.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// # use jvm_class_file_parser::Bytecode::*;
+    /// #
+    /// let mut file = File::open("classes/HelloWorld.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// // PrintStream.println(String)
+    /// assert_eq!(Some(2), class_file.invoke_arg_slots(&Invokevirtual(4)));
+    /// assert_eq!(None, class_file.invoke_arg_slots(&Return));
+    /// ```
+    pub fn invoke_arg_slots(&self, instruction: &Bytecode) -> Option<u16> {
+        use ConstantPoolEntry::*;
+
+        let kind = instruction.invoke_kind()?;
+        let index = instruction.constant_pool_index()?;
+
+        let name_and_type_index = match self.constant_pool.get(index.checked_sub(1)?)? {
+            ConstantMethodref {
+                name_and_type_index,
+                ..
+            } => *name_and_type_index,
+            ConstantInterfaceMethodref {
+                name_and_type_index,
+                ..
+            }
+            | ConstantInvokeDynamic {
+                name_and_type_index,
+                ..
+            } => *name_and_type_index as usize,
+            _ => return None,
+        };
+
+        let (_, descriptor) = self.get_name_and_type(name_and_type_index)?;
+        let descriptor = MethodDescriptor::parse(descriptor).ok()?;
+
+        let receiver_slots = match kind {
+            InvokeKind::Static | InvokeKind::Dynamic => 0,
+            InvokeKind::Virtual | InvokeKind::Special | InvokeKind::Interface => 1,
+        };
+
+        Some(descriptor.parameter_slots() + receiver_slots)
+    }
+
+    /// Resolves the specified MethodHandle constant into its reference kind
+    /// and the field or method that it references.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::{ClassFile, ReferenceKind};
+    /// #
+    /// let mut file = File::open("classes/Lambda.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let method_handle = class_file.resolve_method_handle(43);
+    ///
+    /// assert_eq!(ReferenceKind::InvokeStatic, method_handle.reference_kind);
+    /// assert_eq!("java/lang/invoke/LambdaMetafactory", method_handle.class_name);
+    /// assert_eq!("metafactory", method_handle.name);
+    /// ```
+    pub fn resolve_method_handle(&self, index: usize) -> MethodHandleInfo<'_> {
+        use ConstantPoolEntry::*;
+
+        let constant_method_handle = self.get_constant(index);
+
+        let (reference_kind, reference_index) = if let ConstantMethodHandle {
+            reference_kind,
+            reference_index,
+        } = *constant_method_handle
+        {
+            (reference_kind, reference_index as usize)
+        } else {
+            panic!(
+                "Failed to get constant \"#{}\" as a ConstantMethodHandle. Found: {:?}",
+                index, constant_method_handle
+            )
+        };
+
+        let reference_kind = ReferenceKind::from_u8(reference_kind).unwrap_or_else(|| {
+            panic!(
+                "The MethodHandle constant \"#{}\" has an invalid reference kind: {}",
+                index, reference_kind
+            )
+        });
+
+        let reference = self.get_constant(reference_index);
+        let (class_index, name_and_type_index) = match *reference {
+            ConstantFieldref {
+                class_index,
+                name_and_type_index,
+            }
+            | ConstantMethodref {
+                class_index,
+                name_and_type_index,
+            } => (class_index, name_and_type_index),
+            ConstantInterfaceMethodref {
+                class_index,
+                name_and_type_index,
+            } => (class_index as usize, name_and_type_index as usize),
+            _ => panic!(
+                "The MethodHandle constant \"#{}\" did not reference a field or method. Found: {:?}",
+                index, reference
+            ),
+        };
+
+        let (name_index, descriptor_index) = match *self.get_constant(name_and_type_index) {
+            ConstantNameAndType {
+                name_index,
+                descriptor_index,
+            } => (name_index, descriptor_index),
+            ref constant_nat => panic!(
+                "Failed to get constant \"#{}\" as a ConstantNameAndType. Found: {:?}",
+                name_and_type_index, constant_nat
+            ),
+        };
+
+        MethodHandleInfo {
+            reference_kind,
+            class_name: self.get_constant_class_str(class_index),
+            name: self.get_constant_utf8(name_index),
+            descriptor: self.get_constant_utf8(descriptor_index),
+        }
+    }
+
+    /// Resolves the specified Dynamic or InvokeDynamic constant into the
+    /// bootstrap method that computes its value, and the name and type that
+    /// it is given.
+    ///
+    /// Panics if the constant is not a Dynamic or InvokeDynamic constant, or
+    /// if the class has no bootstrap method at the index that it references.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/StringConcat.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let call_site = class_file.resolve_dynamic(13);
+    ///
+    /// assert_eq!("makeConcatWithConstants", call_site.name);
+    /// assert_eq!(
+    ///     "java/lang/invoke/StringConcatFactory",
+    ///     call_site.bootstrap_method.class_name
+    /// );
+    /// ```
+    pub fn resolve_dynamic(&self, index: usize) -> DynamicInfo<'_> {
+        use ConstantPoolEntry::*;
+
+        let constant_dynamic = self.get_constant(index);
+
+        let (bootstrap_method_attr_index, name_and_type_index) = match *constant_dynamic {
+            ConstantDynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            }
+            | ConstantInvokeDynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            } => (bootstrap_method_attr_index, name_and_type_index as usize),
+            _ => panic!(
+                "Failed to get constant \"#{}\" as a ConstantDynamic or ConstantInvokeDynamic. Found: {:?}",
+                index, constant_dynamic
+            ),
+        };
+
+        let bootstrap_method = self
+            .get_bootstrap_methods()
+            .into_iter()
+            .nth(bootstrap_method_attr_index as usize)
+            .unwrap_or_else(|| {
+                panic!(
+                    "The constant \"#{}\" references a missing bootstrap method: {}",
+                    index, bootstrap_method_attr_index
+                )
+            });
+
+        let (name_index, descriptor_index) = match *self.get_constant(name_and_type_index) {
+            ConstantNameAndType {
+                name_index,
+                descriptor_index,
+            } => (name_index, descriptor_index),
+            ref constant_nat => panic!(
+                "Failed to get constant \"#{}\" as a ConstantNameAndType. Found: {:?}",
+                name_and_type_index, constant_nat
+            ),
+        };
+
+        DynamicInfo {
+            bootstrap_method_attr_index,
+            bootstrap_method: self.resolve_method_handle(bootstrap_method.bootstrap_method_ref),
+            bootstrap_arguments: bootstrap_method.bootstrap_arguments,
+            name: self.get_constant_utf8(name_index),
+            descriptor: self.get_constant_utf8(descriptor_index),
+        }
+    }
+
+    /// Returns a map from the index of each constant that is referenced by the
+    /// class file to the parts of the class file that reference it, including
+    /// other constants.
+    ///
+    /// Fails if one of the attributes is malformed, or if it is of a kind
+    /// whose constant references are not known (ex. annotations).
+    ///
+    /// Panics if the code of one of the methods contains an instruction that
+    /// cannot be decoded.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::{ClassFile, ConstantReferrer};
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let references = class_file.constant_references().unwrap();
+    ///
+    /// // #3 of Dummy is the class java/lang/Object
+    /// assert_eq!(
+    ///     vec![ConstantReferrer::Constant(1), ConstantReferrer::SuperClass],
+    ///     references[&3]
+    /// );
+    /// ```
+    pub fn constant_references(
+        &self,
+    ) -> io::Result<HashMap<ConstantPoolIndex, Vec<ConstantReferrer>>> {
+        constant_references::constant_references(self)
+    }
+
+    /// Checks that every constant pool index held by the constants of the
+    /// class file, and by its `this_class` and `super_class`, is in range and
+    /// references the kind of constant that it is required to.
+    ///
+    /// This rules out self-referential and circular constants, so the other
+    /// methods that resolve constants can then be safely used on a class file
+    /// from an untrusted source.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert!(class_file.validate_constant_references().is_ok());
+    /// ```
+    pub fn validate_constant_references(&self) -> io::Result<()> {
+        constant_validation::validate_constant_references(self)
+    }
+
+    /// Checks that the constant pool indexes held by the class file outside of
+    /// its constant pool reference the kinds of constants that they are
+    /// required to. This complements `validate_constant_references`, which
+    /// checks the indexes held by the constants themselves.
+    ///
+    /// This covers `this_class`, `super_class`, the interfaces, the names and
+    /// descriptors of the fields and methods, and the names of the attributes
+    /// of the class, its fields, and its methods.
+    ///
+    /// Returns a message describing each of the problems that were found.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let mut class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert!(class_file.validate_structure().is_ok());
+    ///
+    /// class_file.this_class = 1;
+    ///
+    /// assert_eq!(1, class_file.validate_structure().unwrap_err().len());
+    /// ```
+    pub fn validate_structure(&self) -> Result<(), Vec<String>> {
+        structure_validation::validate_structure(self)
+    }
+
+    /// Returns the specified constant from the constant pool.
+    ///
+    /// This method exists in order to encapsulate the fact that the constant
+    /// pool indexes start at 1 rather than 0.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use std::ops::Deref;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// # use jvm_class_file_parser::ConstantPoolEntry::*;
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert_eq!(
+    ///     ConstantClass {
+    ///         name_index: 11,
+    ///     },
+    ///     *class_file.get_constant(2).deref()
+    /// );
+    /// ```
+    pub fn get_constant(&self, index: usize) -> &ConstantPoolEntry {
+        &self.constant_pool[index - 1]
+    }
+
+    /// Adds the given constant to the end of the constant pool and returns its
+    /// index.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// # use jvm_class_file_parser::ConstantPoolEntry::*;
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let mut class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let index = class_file.add_constant(ConstantUtf8 {
+    ///     string: "Hello".to_string(),
+    /// });
+    ///
+    /// assert_eq!("Hello", class_file.get_constant_utf8(index));
+    /// ```
+    pub fn add_constant(&mut self, entry: ConstantPoolEntry) -> ConstantPoolIndex {
+        self.constant_pool.push(entry);
+
+        self.constant_pool.len()
+    }
+
+    /// Returns the index of a Utf8 constant holding the given string, adding
+    /// one to the constant pool if there is not already one.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let mut class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert_eq!(4, class_file.intern_utf8("<init>"));
+    /// assert_eq!(13, class_file.intern_utf8("noop"));
+    /// ```
+    pub fn intern_utf8(&mut self, string: &str) -> ConstantPoolIndex {
+        let existing = self
+            .constant_pool
+            .iter()
+            .position(|constant| match *constant {
+                ConstantPoolEntry::ConstantUtf8 {
+                    string: ref existing,
+                } => existing == string,
+                _ => false,
+            });
+
+        match existing {
+            // account for 1 indexing
+            Some(i) => i + 1,
+            None => self.add_constant(ConstantPoolEntry::ConstantUtf8 {
+                string: string.to_string(),
+            }),
+        }
+    }
+
+    /// Adds a method with the given access flags, name, descriptor, and code
+    /// to the class, adding any needed constants to the constant pool.
+    ///
+    /// Any constants referenced by the code must already be in the constant
+    /// pool.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::{AttributeSet, ClassFile, Code};
+    /// # use jvm_class_file_parser::Bytecode::*;
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let mut class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let code = Code {
+    ///     max_stack: 0,
+    ///     max_locals: 0,
+    ///     code: vec![(0, Return)],
+    ///     exception_table: vec![],
+    ///     attributes: AttributeSet { attributes: vec![] },
+    /// };
+    ///
+    /// class_file.add_method(0x0009, "noop", "()V", code).unwrap();
+    ///
+    /// assert!(class_file.find_method("noop", "()V").is_some());
+    /// ```
+    pub fn add_method(
+        &mut self,
+        access_flags: u16,
+        name: &str,
+        descriptor: &str,
+        code: Code,
+    ) -> io::Result<()> {
+        let name_index = self.intern_utf8(name);
+        let descriptor_index = self.intern_utf8(descriptor);
+        let code_name_index = self.intern_utf8("Code");
+
+        let code_attribute = Attribute {
+            attribute_name_index: code_name_index,
+            info: code.to_bytes()?,
+            structured: None,
+            file_offset: None,
+        };
+
+        self.methods.push(Method {
+            access_flags,
+            name_index,
+            descriptor_index,
+            attributes: AttributeSet {
+                attributes: vec![code_attribute],
+            },
+            file_offset: None,
+        });
+
+        Ok(())
+    }
+
+    /// Replaces the code of the method at the given index of the methods of
+    /// the class with the given code.
+    ///
+    /// The other attributes of the method are preserved. If the method does
+    /// not have a Code attribute, then one is added.
+    ///
+    /// Any constants referenced by the code must already be in the constant
+    /// pool. Fails if there is no method at the given index.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::{AttributeSet, ClassFile, Code};
+    /// # use jvm_class_file_parser::Bytecode::*;
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let mut class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let code = Code {
+    ///     max_stack: 1,
+    ///     max_locals: 1,
+    ///     code: vec![(0, Aload_0), (1, Invokespecial(1)), (4, Return)],
+    ///     exception_table: vec![],
+    ///     attributes: AttributeSet { attributes: vec![] },
+    /// };
+    ///
+    /// class_file.set_method_code(0, code).unwrap();
+    ///
+    /// assert!(class_file.set_method_code(5, Code {
+    ///     max_stack: 0,
+    ///     max_locals: 0,
+    ///     code: vec![(0, Return)],
+    ///     exception_table: vec![],
+    ///     attributes: AttributeSet { attributes: vec![] },
+    /// }).is_err());
+    /// ```
+    pub fn set_method_code(&mut self, method_index: usize, code: Code) -> io::Result<()> {
+        if method_index >= self.methods.len() {
+            return Err(io_err(format!(
+                "Cannot set the code of method {}, since the class only has {} methods.",
+                method_index,
+                self.methods.len()
+            )));
+        }
+
+        let info = code.to_bytes()?;
+
+        let existing = self.methods[method_index]
+            .attributes
+            .attributes
+            .iter()
+            .position(|attr| attr.name(self) == "Code");
+
+        match existing {
+            Some(i) => {
+                let attr = &mut self.methods[method_index].attributes.attributes[i];
+                attr.info = info;
+
+                if attr.structured.is_some() {
+                    attr.structured = Some(StructuredAttribute::Code(code));
+                }
+            }
+            None => {
+                let code_name_index = self.intern_utf8("Code");
+
+                self.methods[method_index]
+                    .attributes
+                    .attributes
+                    .push(Attribute {
+                        attribute_name_index: code_name_index,
+                        info,
+                        structured: None,
+                        file_offset: None,
+                    });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Relocates the classes whose internal names start with the given prefix
+    /// (ex. `com/google/`) by replacing the prefix with the other given prefix
+    /// (ex. `shaded/com/google/`), as when shading a dependency into a jar.
+    ///
+    /// The class constants, the descriptors of the fields, methods, and
+    /// constants, and the `Signature` attributes of the class, its fields, and
+    /// its methods are updated, including any array types and type arguments
+    /// in them, as are the descriptors and signatures of the local variable
+    /// tables of the methods. String literals are left unchanged. The
+    /// descriptors in other attributes (ex. annotations) are not updated.
+    ///
+    /// The prefix is matched against the whole internal name, so it should end
+    /// with a `/` in order to only match the classes of a package and its
+    /// subpackages.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let mut class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// class_file.relocate("java/", "shaded/java/");
+    ///
+    /// assert_eq!("shaded/java/lang/Object", class_file.get_constant_class_str(3));
+    /// ```
+    pub fn relocate(&mut self, from_prefix: &str, to_prefix: &str) {
+        relocation::relocate(self, from_prefix, to_prefix)
+    }
+
+    /// Renames the class to the given internal name (ex. `com/example/Foo`).
+    ///
+    /// The Utf8 constant backing `this_class` is updated in place, unless it is
+    /// also used for something other than a class name (ex. a string literal
+    /// or a method name), in which case a new Utf8 constant is added and the
+    /// class constant is repointed to it.
+    ///
+    /// References to the class within descriptors are left unchanged, see
+    /// `rename_class_and_descriptors` for a variant that also updates those.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let mut class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// class_file.rename_class("Renamed");
+    ///
+    /// assert_eq!("Renamed", class_file.get_class_name());
+    /// ```
+    pub fn rename_class(&mut self, new_name: &str) {
+        use ConstantPoolEntry::*;
+
+        let name_index = match *self.get_constant(self.this_class) {
+            ConstantClass { name_index } => name_index,
+            ref class => panic!(
+                "The \"this_class\" did not point to a ConstantClass. Found: {:?}",
+                class
+            ),
+        };
+
+        let is_shared = relocation::is_used_as_non_class_name(self, name_index);
+
+        let new_name_constant = ConstantUtf8 {
+            string: new_name.to_string(),
+        };
+
+        if is_shared {
+            let new_name_index = self.add_constant(new_name_constant);
+
+            // every class constant naming this class refers to the renamed one
+            for entry in self.constant_pool.iter_mut() {
+                if let ConstantClass {
+                    name_index: ref mut class_name_index,
+                } = *entry
+                {
+                    if *class_name_index == name_index {
+                        *class_name_index = new_name_index;
+                    }
+                }
+            }
+        } else {
+            self.constant_pool[name_index - 1] = new_name_constant;
+        }
+    }
+
+    /// Renames the class to the given internal name, and also updates any
+    /// references to the class within class constants (ex. `[LDummy;`),
+    /// descriptors (ex. `(LDummy;)V`), and signatures, in the same way as
+    /// `relocate`.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/IntBox.class").unwrap();
+    /// let mut class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// class_file.rename_class_and_descriptors("Renamed");
+    ///
+    /// assert_eq!("Renamed", class_file.get_class_name());
+    /// ```
+    pub fn rename_class_and_descriptors(&mut self, new_name: &str) {
+        let old_name = self.get_class_name().to_string();
+
+        relocation::rename_classes(self, |name| {
+            if name == old_name {
+                new_name.to_string()
+            } else {
+                name.to_string()
+            }
+        });
+    }
+}
+
+/// Reads the table of class constant indexes held by the given attribute,
+/// which starts with the number of entries in the table, and returns the names
+/// of the classes.
+pub(crate) fn read_class_table<'a>(
+    class_file: &'a ClassFile,
+    attr: &Attribute,
+    attribute_name: &str,
+) -> Vec<&'a str> {
+    read_index_table(attr, attribute_name)
+        .into_iter()
+        .map(|class_index| class_file.get_constant_class_str(class_index as usize))
+        .collect()
+}
+
+/// Reads the table of constant pool indexes held by the given attribute, which
+/// starts with the number of entries in the table.
+fn read_index_table(attr: &Attribute, attribute_name: &str) -> Vec<u16> {
+    if attr.info.len() < 2 {
+        panic!(
+            "Incorrectly formatted {} attribute. Expected info length of at least 2, found: {}",
+            attribute_name,
+            attr.info.len()
+        );
+    }
+
+    let number_of_entries = u16::from_be_bytes([attr.info[0], attr.info[1]]) as usize;
+
+    if attr.info.len() != 2 + number_of_entries * 2 {
+        panic!(
+            "Incorrectly formatted {} attribute. Expected info length of {}, found: {}",
+            attribute_name,
+            2 + number_of_entries * 2,
+            attr.info.len()
+        );
+    }
+
+    attr.info[2..]
+        .chunks(2)
+        .map(|index| u16::from_be_bytes([index[0], index[1]]))
+        .collect()
 }
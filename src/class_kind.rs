@@ -0,0 +1,15 @@
+/// A summary of what kind of class a class file describes.
+///
+/// See `ClassFile::classify` for how the kind of a class is determined.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ClassKind {
+    /// A class with a `public static void main(String[])` method.
+    Application,
+    Interface,
+    Enum,
+    Annotation,
+    Module,
+    Record,
+    /// A class that is not of any of the other kinds.
+    Plain,
+}
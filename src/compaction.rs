@@ -0,0 +1,814 @@
+use std::collections::{HashMap, HashSet};
+use std::io;
+
+use annotation::*;
+use attribute::*;
+use bytecode::raw_instructions;
+use class_file::ClassFile;
+use constant_pool::*;
+use parsing;
+use record::*;
+use util::{io_err, Contextable};
+use writing;
+
+/// The attributes that do not reference any constants, other than by their
+/// name.
+const ATTRIBUTES_WITHOUT_REFERENCES: [&str; 4] = [
+    "Deprecated",
+    "LineNumberTable",
+    "SourceDebugExtension",
+    "Synthetic",
+];
+
+/// The attributes that consist of a single constant pool index.
+const SINGLE_INDEX_ATTRIBUTES: [&str; 5] = [
+    "ConstantValue",
+    "ModuleMainClass",
+    "NestHost",
+    "Signature",
+    "SourceFile",
+];
+
+/// The attributes that consist of a table of constant pool indexes, preceded by
+/// the number of entries in the table.
+const INDEX_TABLE_ATTRIBUTES: [&str; 4] = [
+    "Exceptions",
+    "ModulePackages",
+    "NestMembers",
+    "PermittedSubclasses",
+];
+
+/// Removes the constants that are not referenced by the given class file from
+/// its constant pool, and updates all of the references to the remaining
+/// constants.
+///
+/// The remaining constants keep their relative order, so constant pool indexes
+/// only ever decrease.
+pub fn gc_constant_pool(class_file: &mut ClassFile) -> io::Result<()> {
+    let pool_size = class_file.constant_pool.len();
+
+    // find the constants that are directly referenced by the class structure
+    let mut roots = Vec::new();
+    remap_class_file(class_file, &mut |index| {
+        roots.push(index);
+        index
+    })?;
+
+    if let Some(index) = roots.iter().find(|i| **i == 0 || **i > pool_size) {
+        return Err(io_err(format!(
+            "Found a reference to an invalid constant pool index: {}",
+            index
+        )));
+    }
+
+    // follow the references between the constants
+    let mut used = HashSet::new();
+    while let Some(index) = roots.pop() {
+        if !used.insert(index) {
+            continue;
+        }
+
+        roots.extend(class_file.get_constant(index).referenced_indices());
+
+        if let Some(reference) = roots.iter().find(|i| **i == 0 || **i > pool_size) {
+            return Err(io_err(format!(
+                "The constant at index {} references an invalid constant pool index: {}",
+                index, reference
+            )));
+        }
+    }
+
+    // the slot following a long or double is kept along with it
+    let mut new_indexes = vec![0; pool_size + 1];
+    let mut new_pool = Vec::new();
+    let mut keep_next = false;
+    for (i, entry) in class_file.constant_pool.iter().enumerate() {
+        // account for 1 indexing
+        let index = i + 1;
+
+        if keep_next || used.contains(&index) {
+            keep_next = matches!(
+                *entry,
+                ConstantPoolEntry::ConstantLong { .. } | ConstantPoolEntry::ConstantDouble { .. }
+            );
+
+            new_pool.push(entry.clone());
+            new_indexes[index] = new_pool.len();
+        }
+    }
+
+    replace_constant_pool(class_file, new_pool, &mut |index| new_indexes[index])
+}
+
+/// Merges the constants in the constant pool of the given class file that are
+/// identical, keeping the first of each, and updates all of the references to
+/// the removed constants.
+///
+/// Constants are compared with their own references resolved, so merging two
+/// duplicate Utf8 constants also merges any Class constants that name them.
+pub fn dedup_constant_pool(class_file: &mut ClassFile) -> io::Result<()> {
+    let pool_size = class_file.constant_pool.len();
+
+    let mut invalid_reference = None;
+    remap_class_file(class_file, &mut |index| {
+        if index == 0 || index > pool_size {
+            invalid_reference = Some(index);
+        }
+        index
+    })?;
+    if let Some(index) = invalid_reference {
+        return Err(io_err(format!(
+            "Found a reference to an invalid constant pool index: {}",
+            index
+        )));
+    }
+
+    // map each constant to the first constant identical to it, repeating
+    // until merging constants no longer makes any others identical
+    let mut canonical: Vec<ConstantPoolIndex> = (0..=pool_size).collect();
+    loop {
+        // the constants do not implement Hash, so their Debug forms are used
+        let mut first_indexes = HashMap::new();
+        let mut new_canonical = canonical.clone();
+
+        for (i, entry) in class_file.constant_pool.iter().enumerate() {
+            // account for 1 indexing
+            let index = i + 1;
+
+            if matches!(*entry, ConstantPoolEntry::ConstantEmptySlot {}) {
+                continue;
+            }
+
+            let resolved = map_constant_references(entry, &mut |reference| {
+                canonical.get(reference).cloned().unwrap_or(reference)
+            });
+            new_canonical[index] = *first_indexes
+                .entry(format!("{:?}", resolved))
+                .or_insert(index);
+        }
+
+        if new_canonical == canonical {
+            break;
+        }
+        canonical = new_canonical;
+    }
+
+    // the slot following a long or double is removed along with it
+    let mut new_indexes = vec![0; pool_size + 1];
+    let mut new_pool = Vec::new();
+    let mut keep_next = false;
+    for (i, entry) in class_file.constant_pool.iter().enumerate() {
+        let index = i + 1;
+
+        let is_empty_slot = matches!(*entry, ConstantPoolEntry::ConstantEmptySlot {});
+        if (is_empty_slot && keep_next) || (!is_empty_slot && canonical[index] == index) {
+            new_pool.push(entry.clone());
+            new_indexes[index] = new_pool.len();
+        } else if !is_empty_slot {
+            new_indexes[index] = new_indexes[canonical[index]];
+        }
+
+        keep_next = !is_empty_slot && canonical[index] == index;
+    }
+
+    replace_constant_pool(class_file, new_pool, &mut |index| {
+        new_indexes.get(index).cloned().unwrap_or(index)
+    })
+}
+
+/// Replaces the constant pool of the given class file with the given one,
+/// remapping the references in both the class file and the new constants with
+/// the given function.
+fn replace_constant_pool<F: FnMut(ConstantPoolIndex) -> ConstantPoolIndex>(
+    class_file: &mut ClassFile,
+    new_pool: Vec<ConstantPoolEntry>,
+    remap: &mut F,
+) -> io::Result<()> {
+    let new_class_file = remap_class_file(class_file, remap)?;
+
+    class_file.constant_pool = new_pool
+        .iter()
+        .map(|entry| map_constant_references(entry, remap))
+        .collect();
+    class_file.this_class = new_class_file.this_class;
+    class_file.super_class = new_class_file.super_class;
+    class_file.interfaces = new_class_file.interfaces;
+    class_file.fields = new_class_file.fields;
+    class_file.methods = new_class_file.methods;
+    class_file.attributes = new_class_file.attributes;
+
+    Ok(())
+}
+
+/// Returns a copy of the given class file with each of the constant pool
+/// indexes in its class structure, fields, methods, and attributes replaced by
+/// the result of the given function.
+///
+/// The constant pool itself is left unchanged.
+fn remap_class_file<F: FnMut(ConstantPoolIndex) -> ConstantPoolIndex>(
+    class_file: &ClassFile,
+    f: &mut F,
+) -> io::Result<ClassFile> {
+    let mut new_class_file = class_file.clone();
+
+    new_class_file.this_class = f(class_file.this_class);
+
+    // java/lang/Object has no super class
+    if class_file.super_class != 0 {
+        new_class_file.super_class = f(class_file.super_class);
+    }
+
+    new_class_file.interfaces = class_file.interfaces.iter().map(|i| f(*i)).collect();
+
+    for field in new_class_file.fields.iter_mut() {
+        field.name_index = f(field.name_index);
+        field.descriptor_index = f(field.descriptor_index);
+        field.attributes = remap_attributes(class_file, &field.attributes, f)?;
+    }
+
+    for method in new_class_file.methods.iter_mut() {
+        method.name_index = f(method.name_index);
+        method.descriptor_index = f(method.descriptor_index);
+        method.attributes = remap_attributes(class_file, &method.attributes, f)?;
+    }
+
+    new_class_file.attributes = remap_attributes(class_file, &class_file.attributes, f)?;
+
+    Ok(new_class_file)
+}
+
+fn remap_attributes<F: FnMut(ConstantPoolIndex) -> ConstantPoolIndex>(
+    class_file: &ClassFile,
+    attributes: &AttributeSet,
+    f: &mut F,
+) -> io::Result<AttributeSet> {
+    let mut new_attributes = Vec::with_capacity(attributes.attributes.len());
+
+    for attr in attributes.attributes.iter() {
+        new_attributes.push(remap_attribute(class_file, attr, f)?);
+    }
+
+    Ok(AttributeSet {
+        attributes: new_attributes,
+    })
+}
+
+/// Returns a copy of the given attribute with each of the constant pool
+/// indexes in it replaced by the result of the given function.
+///
+/// Fails if the attribute is malformed, or if it is of a kind whose constant
+/// references are not known.
+fn remap_attribute<F: FnMut(ConstantPoolIndex) -> ConstantPoolIndex>(
+    class_file: &ClassFile,
+    attr: &Attribute,
+    f: &mut F,
+) -> io::Result<Attribute> {
+    let info = remap_attribute_contents(class_file, attr, f)?;
+
+    Ok(Attribute {
+        attribute_name_index: f(attr.attribute_name_index),
+        info,
+        structured: None,
+        file_offset: attr.file_offset,
+    })
+}
+
+/// Returns a copy of the info bytes of the given attribute with each of the
+/// constant pool indexes in it, including those in any nested attributes,
+/// replaced by the result of the given function. The name of the attribute
+/// itself is not passed to the function.
+///
+/// Fails if the attribute is malformed, or if it is of a kind whose constant
+/// references are not known.
+pub fn remap_attribute_contents<F: FnMut(ConstantPoolIndex) -> ConstantPoolIndex>(
+    class_file: &ClassFile,
+    attr: &Attribute,
+    f: &mut F,
+) -> io::Result<Vec<u8>> {
+    let name = attr.name(class_file);
+
+    if ATTRIBUTES_WITHOUT_REFERENCES.contains(&name) {
+        Ok(attr.info.clone())
+    } else if name == "Code" {
+        remap_code(class_file, &attr.info, f)
+    } else if name == "Record" {
+        remap_record(class_file, &attr.info, f)
+    } else {
+        remap_attribute_info(name, &attr.info, f)
+    }
+}
+
+/// Returns a copy of the info bytes of the given attribute, which does not
+/// contain any nested attributes, with each of the constant pool indexes in it
+/// replaced by the result of the given function.
+fn remap_attribute_info<F: FnMut(ConstantPoolIndex) -> ConstantPoolIndex>(
+    name: &str,
+    bytes: &[u8],
+    f: &mut F,
+) -> io::Result<Vec<u8>> {
+    let mut reader = bytes;
+    let mut info = Vec::with_capacity(bytes.len());
+
+    if SINGLE_INDEX_ATTRIBUTES.contains(&name) {
+        copy_index(&mut reader, &mut info, f)?;
+    } else if INDEX_TABLE_ATTRIBUTES.contains(&name) {
+        let count = copy_u16(&mut reader, &mut info)?;
+        for _ in 0..count {
+            copy_index(&mut reader, &mut info, f)?;
+        }
+    } else {
+        match name {
+            "InnerClasses" => {
+                let count = copy_u16(&mut reader, &mut info)?;
+                for _ in 0..count {
+                    copy_index(&mut reader, &mut info, f)?;
+                    copy_optional_index(&mut reader, &mut info, f)?;
+                    copy_optional_index(&mut reader, &mut info, f)?;
+                    copy_u16(&mut reader, &mut info)?;
+                }
+            }
+            "EnclosingMethod" => {
+                copy_index(&mut reader, &mut info, f)?;
+                copy_optional_index(&mut reader, &mut info, f)?;
+            }
+            "LocalVariableTable" | "LocalVariableTypeTable" => {
+                let count = copy_u16(&mut reader, &mut info)?;
+                for _ in 0..count {
+                    copy_u16(&mut reader, &mut info)?;
+                    copy_u16(&mut reader, &mut info)?;
+                    copy_index(&mut reader, &mut info, f)?;
+                    copy_index(&mut reader, &mut info, f)?;
+                    copy_u16(&mut reader, &mut info)?;
+                }
+            }
+            "MethodParameters" => {
+                let count = copy_u8(&mut reader, &mut info)?;
+                for _ in 0..count {
+                    copy_optional_index(&mut reader, &mut info, f)?;
+                    copy_u16(&mut reader, &mut info)?;
+                }
+            }
+            "BootstrapMethods" => {
+                let count = copy_u16(&mut reader, &mut info)?;
+                for _ in 0..count {
+                    copy_index(&mut reader, &mut info, f)?;
+
+                    let arguments_count = copy_u16(&mut reader, &mut info)?;
+                    for _ in 0..arguments_count {
+                        copy_index(&mut reader, &mut info, f)?;
+                    }
+                }
+            }
+            "RuntimeVisibleAnnotations" | "RuntimeInvisibleAnnotations" => {
+                let count = copy_u16(&mut reader, &mut info)?;
+                for _ in 0..count {
+                    copy_annotation(&mut reader, &mut info, f)?;
+                }
+            }
+            "RuntimeVisibleParameterAnnotations" | "RuntimeInvisibleParameterAnnotations" => {
+                let parameters_count = copy_u8(&mut reader, &mut info)?;
+                for _ in 0..parameters_count {
+                    let count = copy_u16(&mut reader, &mut info)?;
+                    for _ in 0..count {
+                        copy_annotation(&mut reader, &mut info, f)?;
+                    }
+                }
+            }
+            "RuntimeVisibleTypeAnnotations" | "RuntimeInvisibleTypeAnnotations" => {
+                let count = copy_u16(&mut reader, &mut info)?;
+                for _ in 0..count {
+                    let mut annotation = read_type_annotation(&mut reader)?;
+                    annotation.annotation = map_annotation_references(&annotation.annotation, f);
+
+                    writing::write_type_annotation(&mut info, &annotation)?;
+                }
+            }
+            "AnnotationDefault" => {
+                let value = read_element_value(&mut reader)?;
+
+                writing::write_element_value(&mut info, &map_element_value_references(&value, f))?;
+            }
+            "Module" => remap_module(&mut reader, &mut info, f)?,
+            "StackMapTable" => {
+                let count = copy_u16(&mut reader, &mut info)?;
+                for _ in 0..count {
+                    remap_stack_map_frame(&mut reader, &mut info, f)?;
+                }
+            }
+            _ => {
+                return Err(io_err(format!(
+                    "Unable to update the constant references of the {} attribute.",
+                    name
+                )))
+            }
+        }
+    }
+
+    if !reader.is_empty() {
+        return Err(io_err(format!(
+            "Found {} unexpected trailing bytes in the {} attribute.",
+            reader.len(),
+            name
+        )));
+    }
+
+    Ok(info)
+}
+
+fn remap_code<F: FnMut(ConstantPoolIndex) -> ConstantPoolIndex>(
+    class_file: &ClassFile,
+    bytes: &[u8],
+    f: &mut F,
+) -> io::Result<Vec<u8>> {
+    let mut reader = bytes;
+    let mut info = Vec::with_capacity(bytes.len());
+
+    // max stack and max locals
+    copy_u16(&mut reader, &mut info)?;
+    copy_u16(&mut reader, &mut info)?;
+
+    let code_length = parsing::read_u32(&mut reader).context("Failed to read code length.")?;
+    if code_length as usize > reader.len() {
+        return Err(io_err(format!(
+            "The declared code length of {} exceeds the {} remaining bytes of the attribute.",
+            code_length,
+            reader.len()
+        )));
+    }
+    info.extend_from_slice(&code_length.to_be_bytes());
+
+    // only the constant pool operands are changed, so the instructions do not
+    // need to be decoded
+    let (code, rest) = reader.split_at(code_length as usize);
+    reader = rest;
+    for instruction in raw_instructions(code)? {
+        match instruction.constant_pool_index() {
+            Some(index) => info.extend(instruction.with_constant_pool_index(f(index))?),
+            None => info.extend_from_slice(instruction.bytes),
+        }
+    }
+
+    let exception_table_length = copy_u16(&mut reader, &mut info)?;
+    for _ in 0..exception_table_length {
+        copy_u16(&mut reader, &mut info)?;
+        copy_u16(&mut reader, &mut info)?;
+        copy_u16(&mut reader, &mut info)?;
+
+        // a catch type of 0 catches all exceptions
+        copy_optional_index(&mut reader, &mut info, f)?;
+    }
+
+    let budget = reader.len();
+    let attributes = parsing::read_nested_attributes(&mut reader, budget)?;
+    let attributes = remap_attributes(class_file, &attributes, f)?;
+    writing::write_attributes(&mut info, &attributes.attributes)?;
+
+    Ok(info)
+}
+
+fn remap_record<F: FnMut(ConstantPoolIndex) -> ConstantPoolIndex>(
+    class_file: &ClassFile,
+    bytes: &[u8],
+    f: &mut F,
+) -> io::Result<Vec<u8>> {
+    let components = RecordComponent::from_bytes(bytes)?;
+
+    let mut info = Vec::new();
+    info.extend_from_slice(&(components.len() as u16).to_be_bytes());
+
+    for component in components.iter() {
+        info.extend_from_slice(&to_u16_index(f(component.name_index))?.to_be_bytes());
+        info.extend_from_slice(&to_u16_index(f(component.descriptor_index))?.to_be_bytes());
+
+        let attributes = remap_attributes(class_file, &component.attributes, f)?;
+        writing::write_attributes(&mut info, &attributes.attributes)?;
+    }
+
+    Ok(info)
+}
+
+/// Copies the contents of a Module attribute.
+///
+/// See Chapter 4.7.25 of the JVM specification for details.
+///
+/// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-4.html#jvms-4.7.25
+fn remap_module<F: FnMut(ConstantPoolIndex) -> ConstantPoolIndex>(
+    reader: &mut &[u8],
+    info: &mut Vec<u8>,
+    f: &mut F,
+) -> io::Result<()> {
+    // module name, flags, and version
+    copy_index(reader, info, f)?;
+    copy_u16(reader, info)?;
+    copy_optional_index(reader, info, f)?;
+
+    let requires_count = copy_u16(reader, info)?;
+    for _ in 0..requires_count {
+        copy_index(reader, info, f)?;
+        copy_u16(reader, info)?;
+        copy_optional_index(reader, info, f)?;
+    }
+
+    // exports and opens have the same structure
+    for _ in 0..2 {
+        let count = copy_u16(reader, info)?;
+        for _ in 0..count {
+            copy_index(reader, info, f)?;
+            copy_u16(reader, info)?;
+
+            let to_count = copy_u16(reader, info)?;
+            for _ in 0..to_count {
+                copy_index(reader, info, f)?;
+            }
+        }
+    }
+
+    let uses_count = copy_u16(reader, info)?;
+    for _ in 0..uses_count {
+        copy_index(reader, info, f)?;
+    }
+
+    let provides_count = copy_u16(reader, info)?;
+    for _ in 0..provides_count {
+        copy_index(reader, info, f)?;
+
+        let with_count = copy_u16(reader, info)?;
+        for _ in 0..with_count {
+            copy_index(reader, info, f)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Copies a single frame of a StackMapTable attribute, replacing the class
+/// constants of its Object verification types.
+///
+/// See Chapter 4.7.4 of the JVM specification for details.
+///
+/// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-4.html#jvms-4.7.4
+fn remap_stack_map_frame<F: FnMut(ConstantPoolIndex) -> ConstantPoolIndex>(
+    reader: &mut &[u8],
+    info: &mut Vec<u8>,
+    f: &mut F,
+) -> io::Result<()> {
+    let frame_type = copy_u8(reader, info).context("Failed to read stack map frame type.")?;
+
+    match frame_type {
+        // same_frame
+        0..=63 => {}
+        // same_locals_1_stack_item_frame
+        64..=127 => remap_verification_type(reader, info, f)?,
+        // same_locals_1_stack_item_frame_extended
+        247 => {
+            copy_u16(reader, info)?;
+            remap_verification_type(reader, info, f)?;
+        }
+        // chop_frame and same_frame_extended
+        248..=251 => {
+            copy_u16(reader, info)?;
+        }
+        // append_frame
+        252..=254 => {
+            copy_u16(reader, info)?;
+            for _ in 0..(frame_type - 251) {
+                remap_verification_type(reader, info, f)?;
+            }
+        }
+        // full_frame
+        255 => {
+            copy_u16(reader, info)?;
+
+            let locals_count = copy_u16(reader, info)?;
+            for _ in 0..locals_count {
+                remap_verification_type(reader, info, f)?;
+            }
+
+            let stack_count = copy_u16(reader, info)?;
+            for _ in 0..stack_count {
+                remap_verification_type(reader, info, f)?;
+            }
+        }
+        _ => {
+            return Err(io_err(format!(
+                "Found reserved stack map frame type: {}",
+                frame_type
+            )))
+        }
+    }
+
+    Ok(())
+}
+
+fn remap_verification_type<F: FnMut(ConstantPoolIndex) -> ConstantPoolIndex>(
+    reader: &mut &[u8],
+    info: &mut Vec<u8>,
+    f: &mut F,
+) -> io::Result<()> {
+    const OBJECT_VARIABLE_INFO_TAG: u8 = 7;
+    const UNINITIALIZED_VARIABLE_INFO_TAG: u8 = 8;
+
+    match copy_u8(reader, info).context("Failed to read verification type tag.")? {
+        OBJECT_VARIABLE_INFO_TAG => {
+            copy_index(reader, info, f)?;
+        }
+        UNINITIALIZED_VARIABLE_INFO_TAG => {
+            copy_u16(reader, info)?;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+fn copy_annotation<F: FnMut(ConstantPoolIndex) -> ConstantPoolIndex>(
+    reader: &mut &[u8],
+    info: &mut Vec<u8>,
+    f: &mut F,
+) -> io::Result<()> {
+    let annotation = read_annotation(reader)?;
+
+    writing::write_annotation(info, &map_annotation_references(&annotation, f))
+}
+
+fn map_annotation_references<F: FnMut(ConstantPoolIndex) -> ConstantPoolIndex>(
+    annotation: &Annotation,
+    f: &mut F,
+) -> Annotation {
+    Annotation {
+        type_index: f(annotation.type_index),
+        element_value_pairs: annotation
+            .element_value_pairs
+            .iter()
+            .map(|pair| ElementValuePair {
+                element_name_index: f(pair.element_name_index),
+                value: map_element_value_references(&pair.value, f),
+            })
+            .collect(),
+    }
+}
+
+fn map_element_value_references<F: FnMut(ConstantPoolIndex) -> ConstantPoolIndex>(
+    value: &ElementValue,
+    f: &mut F,
+) -> ElementValue {
+    match *value {
+        ElementValue::Const {
+            tag,
+            const_value_index,
+        } => ElementValue::Const {
+            tag,
+            const_value_index: f(const_value_index),
+        },
+        ElementValue::EnumConst {
+            type_name_index,
+            const_name_index,
+        } => ElementValue::EnumConst {
+            type_name_index: f(type_name_index),
+            const_name_index: f(const_name_index),
+        },
+        ElementValue::Class { class_info_index } => ElementValue::Class {
+            class_info_index: f(class_info_index),
+        },
+        ElementValue::Annotation(ref annotation) => {
+            ElementValue::Annotation(map_annotation_references(annotation, f))
+        }
+        ElementValue::Array(ref values) => ElementValue::Array(
+            values
+                .iter()
+                .map(|value| map_element_value_references(value, f))
+                .collect(),
+        ),
+    }
+}
+
+/// Returns a copy of the given constant with each of the constant pool indexes
+/// it references replaced by the result of the given function.
+pub fn map_constant_references<F: FnMut(ConstantPoolIndex) -> ConstantPoolIndex>(
+    entry: &ConstantPoolEntry,
+    f: &mut F,
+) -> ConstantPoolEntry {
+    use ConstantPoolEntry::*;
+
+    // the indexes that are stored as u16s always fit, since constant pool
+    // indexes only ever decrease
+    let mut f_u16 = |index: u16| f(index as usize) as u16;
+
+    match *entry {
+        ConstantClass { name_index } => ConstantClass {
+            name_index: f(name_index),
+        },
+        ConstantString { string_index } => ConstantString {
+            string_index: f(string_index),
+        },
+        ConstantFieldref {
+            class_index,
+            name_and_type_index,
+        } => ConstantFieldref {
+            class_index: f(class_index),
+            name_and_type_index: f(name_and_type_index),
+        },
+        ConstantMethodref {
+            class_index,
+            name_and_type_index,
+        } => ConstantMethodref {
+            class_index: f(class_index),
+            name_and_type_index: f(name_and_type_index),
+        },
+        ConstantInterfaceMethodref {
+            class_index,
+            name_and_type_index,
+        } => ConstantInterfaceMethodref {
+            class_index: f_u16(class_index),
+            name_and_type_index: f_u16(name_and_type_index),
+        },
+        ConstantNameAndType {
+            name_index,
+            descriptor_index,
+        } => ConstantNameAndType {
+            name_index: f(name_index),
+            descriptor_index: f(descriptor_index),
+        },
+        ConstantMethodHandle {
+            reference_kind,
+            reference_index,
+        } => ConstantMethodHandle {
+            reference_kind,
+            reference_index: f_u16(reference_index),
+        },
+        ConstantMethodType { descriptor_index } => ConstantMethodType {
+            descriptor_index: f_u16(descriptor_index),
+        },
+        ConstantDynamic {
+            bootstrap_method_attr_index,
+            name_and_type_index,
+        } => ConstantDynamic {
+            bootstrap_method_attr_index,
+            name_and_type_index: f_u16(name_and_type_index),
+        },
+        ConstantInvokeDynamic {
+            bootstrap_method_attr_index,
+            name_and_type_index,
+        } => ConstantInvokeDynamic {
+            bootstrap_method_attr_index,
+            name_and_type_index: f_u16(name_and_type_index),
+        },
+        ConstantModule { name_index } => ConstantModule {
+            name_index: f_u16(name_index),
+        },
+        ConstantPackage { name_index } => ConstantPackage {
+            name_index: f_u16(name_index),
+        },
+        ref other => other.clone(),
+    }
+}
+
+fn copy_u8(reader: &mut &[u8], info: &mut Vec<u8>) -> io::Result<u8> {
+    let value = parsing::read_u8(reader)?;
+    info.push(value);
+
+    Ok(value)
+}
+
+fn copy_u16(reader: &mut &[u8], info: &mut Vec<u8>) -> io::Result<u16> {
+    let value = parsing::read_u16(reader)?;
+    info.extend_from_slice(&value.to_be_bytes());
+
+    Ok(value)
+}
+
+fn copy_index<F: FnMut(ConstantPoolIndex) -> ConstantPoolIndex>(
+    reader: &mut &[u8],
+    info: &mut Vec<u8>,
+    f: &mut F,
+) -> io::Result<()> {
+    let index = parsing::read_cp_index(reader)?;
+    info.extend_from_slice(&to_u16_index(f(index))?.to_be_bytes());
+
+    Ok(())
+}
+
+/// Copies a constant pool index that may be 0 to indicate that there is no
+/// constant.
+fn copy_optional_index<F: FnMut(ConstantPoolIndex) -> ConstantPoolIndex>(
+    reader: &mut &[u8],
+    info: &mut Vec<u8>,
+    f: &mut F,
+) -> io::Result<()> {
+    let index = parsing::read_cp_index(reader)?;
+    let new_index = if index == 0 { 0 } else { f(index) };
+    info.extend_from_slice(&to_u16_index(new_index)?.to_be_bytes());
+
+    Ok(())
+}
+
+fn to_u16_index(index: ConstantPoolIndex) -> io::Result<u16> {
+    if index > u16::MAX as usize {
+        return Err(io_err(format!(
+            "Constant pool index {} does not fit in 2 bytes",
+            index
+        )));
+    }
+
+    Ok(index as u16)
+}
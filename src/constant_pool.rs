@@ -1,8 +1,117 @@
-use util::FloatBuffer;
+use std::io;
+use std::num::NonZeroU16;
+
+use class_file::ClassFile;
+use util::{io_err, FloatBuffer};
 
 /// Index into the constant pool "table"
 pub type ConstantPoolIndex = usize;
 
+/// An index into the constant pool that is known to be valid, in that it is
+/// not 0 and fits in the 2 bytes used to store constant pool indexes.
+///
+/// Unlike a raw `ConstantPoolIndex`, this can not hold the index 0, which
+/// never refers to a constant.
+///
+/// ```
+/// # use jvm_class_file_parser::CpIndex;
+/// #
+/// assert_eq!(3, CpIndex::new(3).unwrap().get());
+/// assert!(CpIndex::new(0).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct CpIndex(NonZeroU16);
+
+impl CpIndex {
+    /// Creates a constant pool index from the given raw index.
+    ///
+    /// Returns an error if the index is 0 or does not fit in 2 bytes.
+    pub fn new(index: ConstantPoolIndex) -> io::Result<CpIndex> {
+        if index > usize::from(u16::MAX) {
+            return Err(io_err(format!(
+                "Constant pool index {} does not fit in 2 bytes.",
+                index
+            )));
+        }
+
+        match NonZeroU16::new(index as u16) {
+            Some(index) => Ok(CpIndex(index)),
+            None => Err(io_err(
+                "Constant pool index 0 does not refer to a constant.",
+            )),
+        }
+    }
+
+    /// Creates a constant pool index from the given raw index, checking that
+    /// it refers to a constant in the constant pool of the given class file.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::{ClassFile, CpIndex};
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert!(CpIndex::checked(12, &class_file).is_ok());
+    /// assert!(CpIndex::checked(13, &class_file).is_err());
+    /// ```
+    pub fn checked(index: ConstantPoolIndex, class_file: &ClassFile) -> io::Result<CpIndex> {
+        let index = CpIndex::new(index)?;
+
+        index.resolve(class_file)?;
+
+        Ok(index)
+    }
+
+    /// Returns the raw index.
+    pub fn get(self) -> ConstantPoolIndex {
+        ConstantPoolIndex::from(self.0.get())
+    }
+
+    /// Returns the constant that the index refers to in the constant pool of
+    /// the given class file.
+    ///
+    /// Returns an error if the index is past the end of the constant pool, or
+    /// if it refers to the unusable slot after a long or double constant.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::{ClassFile, ConstantPoolEntry, CpIndex};
+    /// #
+    /// let mut file = File::open("classes/Dummy.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let index = CpIndex::new(2).unwrap();
+    ///
+    /// assert_eq!(
+    ///     &ConstantPoolEntry::ConstantClass { name_index: 11 },
+    ///     index.resolve(&class_file).unwrap()
+    /// );
+    /// ```
+    pub fn resolve(self, class_file: &ClassFile) -> io::Result<&ConstantPoolEntry> {
+        let index = self.get();
+
+        match class_file.constant_pool.get(index - 1) {
+            Some(ConstantPoolEntry::ConstantEmptySlot {}) => Err(io_err(format!(
+                "Constant pool index {} refers to the unusable slot after a long or double constant.",
+                index
+            ))),
+            Some(constant) => Ok(constant),
+            None => Err(io_err(format!(
+                "Constant pool index {} is out of range for a constant pool of {} entries.",
+                index,
+                class_file.constant_pool.len()
+            ))),
+        }
+    }
+}
+
+impl From<CpIndex> for ConstantPoolIndex {
+    fn from(index: CpIndex) -> ConstantPoolIndex {
+        index.get()
+    }
+}
+
 /// Constant pool structures,
 /// as defined in https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-4.html#jvms-4.4
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -68,3 +177,226 @@ pub enum ConstantPoolEntry {
     // represents an empty slot in the constant pool table
     ConstantEmptySlot {},
 }
+
+impl ConstantPoolEntry {
+    /// Returns the name of the kind of the constant, as shown in the constant
+    /// pool section of the disassembly.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::ConstantPoolEntry;
+    /// #
+    /// let constant = ConstantPoolEntry::ConstantClass { name_index: 1 };
+    ///
+    /// assert_eq!("Class", constant.kind_name());
+    /// ```
+    pub fn kind_name(&self) -> &'static str {
+        use self::ConstantPoolEntry::*;
+
+        match *self {
+            ConstantUtf8 { .. } => "Utf8",
+            ConstantInteger { .. } => "Integer",
+            ConstantFloat { .. } => "Float",
+            ConstantLong { .. } => "Long",
+            ConstantDouble { .. } => "Double",
+            ConstantClass { .. } => "Class",
+            ConstantString { .. } => "String",
+            ConstantFieldref { .. } => "Fieldref",
+            ConstantMethodref { .. } => "Methodref",
+            ConstantInterfaceMethodref { .. } => "InterfaceMethodref",
+            ConstantNameAndType { .. } => "NameAndType",
+            ConstantMethodHandle { .. } => "MethodHandle",
+            ConstantMethodType { .. } => "MethodType",
+            ConstantDynamic { .. } => "Dynamic",
+            ConstantInvokeDynamic { .. } => "InvokeDynamic",
+            ConstantModule { .. } => "Module",
+            ConstantPackage { .. } => "Package",
+            ConstantEmptySlot {} => "<empty slot>",
+        }
+    }
+
+    /// Returns the indexes of the constants that the constant directly
+    /// references, in the order in which they are stored.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::ConstantPoolEntry;
+    /// #
+    /// let constant = ConstantPoolEntry::ConstantMethodref {
+    ///     class_index: 2,
+    ///     name_and_type_index: 3,
+    /// };
+    ///
+    /// assert_eq!(vec![2, 3], constant.referenced_indices());
+    /// ```
+    pub fn referenced_indices(&self) -> Vec<ConstantPoolIndex> {
+        use self::ConstantPoolEntry::*;
+
+        match *self {
+            ConstantClass { name_index } => vec![name_index],
+            ConstantString { string_index } => vec![string_index],
+            ConstantFieldref {
+                class_index,
+                name_and_type_index,
+            }
+            | ConstantMethodref {
+                class_index,
+                name_and_type_index,
+            } => vec![class_index, name_and_type_index],
+            ConstantInterfaceMethodref {
+                class_index,
+                name_and_type_index,
+            } => vec![
+                ConstantPoolIndex::from(class_index),
+                ConstantPoolIndex::from(name_and_type_index),
+            ],
+            ConstantNameAndType {
+                name_index,
+                descriptor_index,
+            } => vec![name_index, descriptor_index],
+            ConstantMethodHandle {
+                reference_index, ..
+            } => vec![ConstantPoolIndex::from(reference_index)],
+            ConstantMethodType { descriptor_index } => {
+                vec![ConstantPoolIndex::from(descriptor_index)]
+            }
+            ConstantDynamic {
+                name_and_type_index,
+                ..
+            }
+            | ConstantInvokeDynamic {
+                name_and_type_index,
+                ..
+            } => vec![ConstantPoolIndex::from(name_and_type_index)],
+            ConstantModule { name_index } | ConstantPackage { name_index } => {
+                vec![ConstantPoolIndex::from(name_index)]
+            }
+            ConstantUtf8 { .. }
+            | ConstantInteger { .. }
+            | ConstantFloat { .. }
+            | ConstantLong { .. }
+            | ConstantDouble { .. }
+            | ConstantEmptySlot {} => vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConstantPoolEntry::*;
+    use super::CpIndex;
+    use util::FloatBuffer;
+
+    #[test]
+    fn cp_index_rejects_zero() {
+        assert!(CpIndex::new(0).is_err());
+    }
+
+    #[test]
+    fn cp_index_rejects_indexes_wider_than_2_bytes() {
+        assert_eq!(65535, CpIndex::new(65535).unwrap().get());
+        assert!(CpIndex::new(65536).is_err());
+    }
+
+    #[test]
+    fn kind_name_of_each_constant() {
+        let constants = vec![
+            (
+                ConstantUtf8 {
+                    string: "a".to_string(),
+                },
+                "Utf8",
+            ),
+            (ConstantInteger { val: 1 }, "Integer"),
+            (
+                ConstantFloat {
+                    val: FloatBuffer { buf: [0; 4] },
+                },
+                "Float",
+            ),
+            (ConstantLong { val: 1 }, "Long"),
+            (
+                ConstantDouble {
+                    val: FloatBuffer { buf: [0; 8] },
+                },
+                "Double",
+            ),
+            (ConstantClass { name_index: 1 }, "Class"),
+            (ConstantString { string_index: 1 }, "String"),
+            (
+                ConstantFieldref {
+                    class_index: 1,
+                    name_and_type_index: 2,
+                },
+                "Fieldref",
+            ),
+            (
+                ConstantMethodref {
+                    class_index: 1,
+                    name_and_type_index: 2,
+                },
+                "Methodref",
+            ),
+            (
+                ConstantInterfaceMethodref {
+                    class_index: 1,
+                    name_and_type_index: 2,
+                },
+                "InterfaceMethodref",
+            ),
+            (
+                ConstantNameAndType {
+                    name_index: 1,
+                    descriptor_index: 2,
+                },
+                "NameAndType",
+            ),
+            (
+                ConstantMethodHandle {
+                    reference_kind: 1,
+                    reference_index: 2,
+                },
+                "MethodHandle",
+            ),
+            (
+                ConstantMethodType {
+                    descriptor_index: 1,
+                },
+                "MethodType",
+            ),
+            (
+                ConstantDynamic {
+                    bootstrap_method_attr_index: 0,
+                    name_and_type_index: 1,
+                },
+                "Dynamic",
+            ),
+            (
+                ConstantInvokeDynamic {
+                    bootstrap_method_attr_index: 0,
+                    name_and_type_index: 1,
+                },
+                "InvokeDynamic",
+            ),
+            (ConstantModule { name_index: 1 }, "Module"),
+            (ConstantPackage { name_index: 1 }, "Package"),
+            (ConstantEmptySlot {}, "<empty slot>"),
+        ];
+
+        for (constant, kind_name) in constants {
+            assert_eq!(kind_name, constant.kind_name());
+        }
+    }
+
+    #[test]
+    fn referenced_indices_of_name_and_type_and_utf8() {
+        let name_and_type = ConstantNameAndType {
+            name_index: 5,
+            descriptor_index: 6,
+        };
+        let utf8 = ConstantUtf8 {
+            string: "value".to_string(),
+        };
+
+        assert_eq!(vec![5, 6], name_and_type.referenced_indices());
+        assert!(utf8.referenced_indices().is_empty());
+    }
+}
@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+
+use attribute::AttributeSet;
+use class_file::ClassFile;
+use compaction;
+use constant_pool::ConstantPoolIndex;
+
+/// A part of a class file that references a constant.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ConstantReferrer {
+    /// Another constant, at the given index of the constant pool.
+    Constant(ConstantPoolIndex),
+    ThisClass,
+    SuperClass,
+    Interface(usize),
+    FieldName(usize),
+    FieldDescriptor(usize),
+    MethodName(usize),
+    MethodDescriptor(usize),
+    /// The name of an attribute, along with what the attribute belongs to.
+    AttributeName(AttributeOwner),
+    /// The contents of an attribute, including any nested attributes (ex. the
+    /// bytecode of a Code attribute), along with what the attribute belongs
+    /// to.
+    AttributeInfo(AttributeOwner),
+}
+
+/// The part of a class file that an attribute belongs to, where fields and
+/// methods are given by their index.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum AttributeOwner {
+    Class,
+    Field(usize),
+    Method(usize),
+}
+
+impl fmt::Display for ConstantReferrer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::ConstantReferrer::*;
+
+        match *self {
+            Constant(index) => write!(f, "#{}", index),
+            ThisClass => write!(f, "this_class"),
+            SuperClass => write!(f, "super_class"),
+            Interface(i) => write!(f, "interface {}", i),
+            FieldName(i) => write!(f, "name of field {}", i),
+            FieldDescriptor(i) => write!(f, "descriptor of field {}", i),
+            MethodName(i) => write!(f, "name of method {}", i),
+            MethodDescriptor(i) => write!(f, "descriptor of method {}", i),
+            AttributeName(owner) => write!(f, "name of attribute of {}", owner),
+            AttributeInfo(owner) => write!(f, "attribute of {}", owner),
+        }
+    }
+}
+
+impl fmt::Display for AttributeOwner {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AttributeOwner::Class => write!(f, "class"),
+            AttributeOwner::Field(i) => write!(f, "field {}", i),
+            AttributeOwner::Method(i) => write!(f, "method {}", i),
+        }
+    }
+}
+
+/// Returns a map from the index of each constant that is referenced by the
+/// given class file to the parts of the class file that reference it.
+///
+/// The referrers of each constant are in the order that they appear in the
+/// class file, starting with the other constants that reference it.
+///
+/// Fails if one of the attributes is malformed, or if it is of a kind whose
+/// constant references are not known (ex. annotations).
+pub fn constant_references(
+    class_file: &ClassFile,
+) -> io::Result<HashMap<ConstantPoolIndex, Vec<ConstantReferrer>>> {
+    use self::ConstantReferrer::*;
+
+    let mut references: HashMap<ConstantPoolIndex, Vec<ConstantReferrer>> = HashMap::new();
+    let mut add = |index, referrer| references.entry(index).or_default().push(referrer);
+
+    for (i, entry) in class_file.constant_pool.iter().enumerate() {
+        // Account for 1 indexing
+        for reference in entry.referenced_indices() {
+            add(reference, Constant(i + 1));
+        }
+    }
+
+    add(class_file.this_class, ThisClass);
+
+    // the super class index is 0 for java/lang/Object and module-info classes
+    if class_file.super_class != 0 {
+        add(class_file.super_class, SuperClass);
+    }
+
+    for (i, interface) in class_file.interfaces.iter().enumerate() {
+        add(*interface, Interface(i));
+    }
+
+    for (i, field) in class_file.fields.iter().enumerate() {
+        add(field.name_index, FieldName(i));
+        add(field.descriptor_index, FieldDescriptor(i));
+        add_attribute_references(
+            class_file,
+            &field.attributes,
+            AttributeOwner::Field(i),
+            &mut add,
+        )?;
+    }
+
+    for (i, method) in class_file.methods.iter().enumerate() {
+        add(method.name_index, MethodName(i));
+        add(method.descriptor_index, MethodDescriptor(i));
+        add_attribute_references(
+            class_file,
+            &method.attributes,
+            AttributeOwner::Method(i),
+            &mut add,
+        )?;
+    }
+
+    add_attribute_references(
+        class_file,
+        &class_file.attributes,
+        AttributeOwner::Class,
+        &mut add,
+    )?;
+
+    Ok(references)
+}
+
+fn add_attribute_references<F: FnMut(ConstantPoolIndex, ConstantReferrer)>(
+    class_file: &ClassFile,
+    attributes: &AttributeSet,
+    owner: AttributeOwner,
+    add: &mut F,
+) -> io::Result<()> {
+    for attr in attributes.attributes.iter() {
+        add(
+            attr.attribute_name_index,
+            ConstantReferrer::AttributeName(owner),
+        );
+
+        compaction::remap_attribute_contents(class_file, attr, &mut |reference| {
+            add(reference, ConstantReferrer::AttributeInfo(owner));
+            reference
+        })?;
+    }
+
+    Ok(())
+}
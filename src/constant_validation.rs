@@ -0,0 +1,134 @@
+use std::io;
+
+use class_file::ClassFile;
+use constant_pool::*;
+use util::io_err;
+
+/// The kinds of constants that a constant pool index may be required to
+/// reference.
+#[derive(Clone, Copy, Debug)]
+pub enum ExpectedKind {
+    Utf8,
+    Class,
+    NameAndType,
+    MemberRef,
+}
+
+/// Checks that every constant pool index held by the constants of the given
+/// class file, and by its `this_class` and `super_class`, is in range and
+/// references the kind of constant that it is required to.
+///
+/// Each kind of constant may only reference kinds of constants that reference
+/// fewer levels of constants than itself (ex. a Fieldref references a
+/// NameAndType, which references Utf8s, which reference nothing). So once the
+/// kinds are checked, the references can not form a cycle, and any resolver
+/// that follows them is guaranteed to terminate.
+pub fn validate_constant_references(class_file: &ClassFile) -> io::Result<()> {
+    use self::ExpectedKind::*;
+    use ConstantPoolEntry::*;
+
+    for (i, entry) in class_file.constant_pool.iter().enumerate() {
+        let index = i + 1;
+
+        let references: Vec<(usize, ExpectedKind)> = match *entry {
+            ConstantClass { name_index } => vec![(name_index, Utf8)],
+            ConstantString { string_index } => vec![(string_index, Utf8)],
+            ConstantFieldref {
+                class_index,
+                name_and_type_index,
+            }
+            | ConstantMethodref {
+                class_index,
+                name_and_type_index,
+            } => vec![(class_index, Class), (name_and_type_index, NameAndType)],
+            ConstantInterfaceMethodref {
+                class_index,
+                name_and_type_index,
+            } => vec![
+                (class_index as usize, Class),
+                (name_and_type_index as usize, NameAndType),
+            ],
+            ConstantNameAndType {
+                name_index,
+                descriptor_index,
+            } => vec![(name_index, Utf8), (descriptor_index, Utf8)],
+            ConstantMethodHandle {
+                reference_index, ..
+            } => vec![(reference_index as usize, MemberRef)],
+            ConstantMethodType { descriptor_index } => vec![(descriptor_index as usize, Utf8)],
+            ConstantDynamic {
+                name_and_type_index,
+                ..
+            }
+            | ConstantInvokeDynamic {
+                name_and_type_index,
+                ..
+            } => vec![(name_and_type_index as usize, NameAndType)],
+            ConstantModule { name_index } | ConstantPackage { name_index } => {
+                vec![(name_index as usize, Utf8)]
+            }
+            ConstantUtf8 { .. }
+            | ConstantInteger { .. }
+            | ConstantFloat { .. }
+            | ConstantLong { .. }
+            | ConstantDouble { .. }
+            | ConstantEmptySlot {} => vec![],
+        };
+
+        for (reference, expected_kind) in references {
+            check_reference(class_file, reference, expected_kind)
+                .map_err(|e| io_err(format!("Invalid reference in constant #{}. {}", index, e)))?;
+        }
+    }
+
+    check_reference(class_file, class_file.this_class, Class)
+        .map_err(|e| io_err(format!("Invalid this_class. {}", e)))?;
+
+    // the super class index is 0 for java/lang/Object and module-info classes
+    if class_file.super_class != 0 {
+        check_reference(class_file, class_file.super_class, Class)
+            .map_err(|e| io_err(format!("Invalid super_class. {}", e)))?;
+    }
+
+    Ok(())
+}
+
+/// Checks that the given constant pool index is in range and references the
+/// given kind of constant.
+pub fn check_reference(
+    class_file: &ClassFile,
+    index: ConstantPoolIndex,
+    expected_kind: ExpectedKind,
+) -> Result<(), String> {
+    use self::ExpectedKind::*;
+    use ConstantPoolEntry::*;
+
+    if index == 0 || index > class_file.constant_pool.len() {
+        return Err(format!(
+            "Constant pool index #{} is out of range for a constant pool of {} entries.",
+            index,
+            class_file.constant_pool.len()
+        ));
+    }
+
+    let constant = class_file.get_constant(index);
+
+    let is_expected_kind = matches!(
+        (expected_kind, constant),
+        (Utf8, ConstantUtf8 { .. })
+            | (Class, ConstantClass { .. })
+            | (NameAndType, ConstantNameAndType { .. })
+            | (MemberRef, ConstantFieldref { .. })
+            | (MemberRef, ConstantMethodref { .. })
+            | (MemberRef, ConstantInterfaceMethodref { .. })
+    );
+
+    if is_expected_kind {
+        Ok(())
+    } else {
+        Err(format!(
+            "Expected constant #{} to be a {:?} constant, but found: {:?}",
+            index, expected_kind, constant
+        ))
+    }
+}
@@ -0,0 +1,219 @@
+use std::collections::BTreeSet;
+use std::io;
+
+use attribute::Code;
+use bytecode::Bytecode;
+use util::io_err;
+
+/// A maximal run of instructions that is only entered at its first instruction
+/// and only left after its last instruction.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BasicBlock {
+    /// The offset of the first instruction of the block.
+    pub start_pc: usize,
+    /// The offset just past the last instruction of the block.
+    pub end_pc: usize,
+    /// The start offsets of the blocks that the block can branch or fall
+    /// through to.
+    pub successors: Vec<usize>,
+    /// The start offsets of the exception handlers that cover any of the
+    /// instructions of the block.
+    pub exception_successors: Vec<usize>,
+}
+
+/// The control flow graph of the body of a method, as its basic blocks in the
+/// order that they appear in the code.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ControlFlowGraph {
+    pub blocks: Vec<BasicBlock>,
+}
+
+/// Builds the control flow graph of the given code.
+///
+/// Code that uses subroutines (`jsr`, `jsr_w`, or `ret`) is rejected, since the
+/// target of a `ret` depends on the return address stored in its local
+/// variable, which can not be found without a data flow analysis. Subroutines
+/// have not been allowed since version 51 class files, so this only affects
+/// legacy code.
+pub fn build_control_flow_graph(code: &Code) -> io::Result<ControlFlowGraph> {
+    if let Some((pc, bytecode)) = code
+        .code
+        .iter()
+        .find(|(_, bytecode)| is_subroutine(bytecode))
+    {
+        return Err(io_err(format!(
+            "Found a {} at pc {}. Code that uses legacy subroutines (jsr and ret) is not supported by the control flow graph.",
+            bytecode.to_string(*pc as u16).split_whitespace().next().unwrap_or(""),
+            pc
+        )));
+    }
+
+    if code.code.is_empty() {
+        return Ok(ControlFlowGraph { blocks: vec![] });
+    }
+
+    let code_length = Bytecode::to_bytes(&code.code).len();
+
+    let instruction_starts: BTreeSet<usize> = code.code.iter().map(|(pc, _)| *pc).collect();
+
+    // the instructions that start a block
+    let mut leaders = BTreeSet::new();
+    leaders.insert(0);
+    for (pc, bytecode) in code.code.iter() {
+        if let Some(target) = bytecode.branch_target(*pc) {
+            leaders.insert(target);
+
+            // the instruction after a branch starts a block, even if the branch
+            // is conditional
+            if let Some(next) = instruction_starts.range(pc + 1..).next() {
+                leaders.insert(*next);
+            }
+        } else if !bytecode.falls_through() {
+            if let Some(next) = instruction_starts.range(pc + 1..).next() {
+                leaders.insert(*next);
+            }
+        }
+    }
+    for entry in code.exception_table.iter() {
+        leaders.insert(entry.start_pc as usize);
+        leaders.insert(entry.end_pc as usize);
+        leaders.insert(entry.handler_pc as usize);
+    }
+    leaders.remove(&code_length);
+
+    if let Some(leader) = leaders.iter().find(|pc| !instruction_starts.contains(pc)) {
+        return Err(io_err(format!(
+            "Found a jump to offset {}, which is not the start of an instruction.",
+            leader
+        )));
+    }
+
+    let leaders: Vec<usize> = leaders.into_iter().collect();
+
+    let mut blocks = Vec::with_capacity(leaders.len());
+    for (i, start_pc) in leaders.iter().enumerate() {
+        let end_pc = leaders.get(i + 1).cloned().unwrap_or(code_length);
+
+        let (last_pc, last) = code
+            .code
+            .iter()
+            .rev()
+            .find(|(pc, _)| *pc < end_pc)
+            .expect("Every block has at least one instruction.");
+
+        let mut successors = vec![];
+        if let Some(target) = last.branch_target(*last_pc) {
+            successors.push(target);
+        }
+        if last.falls_through() && end_pc < code_length && !successors.contains(&end_pc) {
+            successors.push(end_pc);
+        }
+
+        let mut exception_successors = vec![];
+        for entry in code.exception_table.iter() {
+            let handler_pc = entry.handler_pc as usize;
+            let is_covered =
+                (entry.start_pc as usize) < end_pc && *start_pc < entry.end_pc as usize;
+
+            if is_covered && !exception_successors.contains(&handler_pc) {
+                exception_successors.push(handler_pc);
+            }
+        }
+
+        blocks.push(BasicBlock {
+            start_pc: *start_pc,
+            end_pc,
+            successors,
+            exception_successors,
+        });
+    }
+
+    Ok(ControlFlowGraph { blocks })
+}
+
+fn is_subroutine(bytecode: &Bytecode) -> bool {
+    matches!(
+        *bytecode,
+        Bytecode::Jsr(_) | Bytecode::Jsr_w(_) | Bytecode::Ret(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use attribute::{AttributeSet, Code, ExceptionTableEntry};
+    use bytecode::Bytecode::*;
+    use control_flow::{build_control_flow_graph, BasicBlock};
+
+    fn code_of(code: Vec<(usize, ::bytecode::Bytecode)>) -> Code {
+        Code {
+            max_stack: 1,
+            max_locals: 2,
+            code,
+            exception_table: vec![],
+            attributes: AttributeSet { attributes: vec![] },
+        }
+    }
+
+    #[test]
+    fn control_flow_graph_of_branches_and_handlers() {
+        // if (x) { return } else { throw }, with a catch all handler
+        let mut code = code_of(vec![
+            (0, Iload_1),
+            (1, Ifeq(4)),
+            (4, Return),
+            (5, Aload_0),
+            (6, Athrow),
+            (7, Astore_1),
+            (8, Return),
+        ]);
+        code.exception_table.push(ExceptionTableEntry {
+            start_pc: 0,
+            end_pc: 5,
+            handler_pc: 7,
+            catch_type: 0,
+        });
+
+        let graph = build_control_flow_graph(&code).unwrap();
+
+        assert_eq!(
+            vec![
+                BasicBlock {
+                    start_pc: 0,
+                    end_pc: 4,
+                    successors: vec![5, 4],
+                    exception_successors: vec![7],
+                },
+                BasicBlock {
+                    start_pc: 4,
+                    end_pc: 5,
+                    successors: vec![],
+                    exception_successors: vec![7],
+                },
+                BasicBlock {
+                    start_pc: 5,
+                    end_pc: 7,
+                    successors: vec![],
+                    exception_successors: vec![],
+                },
+                BasicBlock {
+                    start_pc: 7,
+                    end_pc: 9,
+                    successors: vec![],
+                    exception_successors: vec![],
+                },
+            ],
+            graph.blocks
+        );
+    }
+
+    #[test]
+    fn control_flow_graph_rejects_subroutines() {
+        let code = code_of(vec![(0, Jsr(4)), (3, Return), (4, Astore_1), (5, Ret(1))]);
+
+        let error = build_control_flow_graph(&code).unwrap_err();
+
+        assert!(error.to_string().contains(
+            "Found a jsr at pc 0. Code that uses legacy subroutines (jsr and ret) is not supported"
+        ));
+    }
+}
@@ -0,0 +1,76 @@
+use std::io;
+use std::io::Read;
+
+/// The reversed form of the polynomial used by the CRC-32 of zlib, gzip, and
+/// zip files.
+const POLYNOMIAL: u32 = 0xEDB8_8320;
+
+const TABLE: [u32; 256] = build_table();
+
+const fn build_table() -> [u32; 256] {
+    let mut table = [0; 256];
+
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ POLYNOMIAL
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+
+        table[i] = crc;
+        i += 1;
+    }
+
+    table
+}
+
+/// A reader that computes the CRC-32 of the bytes that are read through it.
+pub struct Crc32Reader<R: Read> {
+    inner: R,
+    crc: u32,
+}
+
+impl<R: Read> Crc32Reader<R> {
+    pub fn new(inner: R) -> Crc32Reader<R> {
+        Crc32Reader { inner, crc: !0 }
+    }
+
+    /// Returns the CRC-32 of the bytes that have been read so far.
+    pub fn crc32(&self) -> u32 {
+        !self.crc
+    }
+}
+
+impl<R: Read> Read for Crc32Reader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let bytes_read = self.inner.read(buf)?;
+
+        for byte in buf[..bytes_read].iter() {
+            self.crc = TABLE[((self.crc ^ u32::from(*byte)) & 0xFF) as usize] ^ (self.crc >> 8);
+        }
+
+        Ok(bytes_read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use crc::Crc32Reader;
+
+    #[test]
+    fn crc32_of_check_string() {
+        let mut reader = Crc32Reader::new(&b"123456789"[..]);
+        reader.read_to_end(&mut vec![]).unwrap();
+
+        assert_eq!(0xCBF4_3926, reader.crc32());
+    }
+}
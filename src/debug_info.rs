@@ -0,0 +1,130 @@
+use std::io;
+
+use parsing;
+use util::Contextable;
+use writing;
+use ConstantPoolIndex;
+
+/// An entry of a `LineNumberTable` attribute, which maps the instructions
+/// starting at `start_pc` to a line of the source file.
+///
+/// See Chapter 4.7.12 of the JVM specification for details.
+///
+/// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-4.html#jvms-4.7.12
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct LineNumberTableEntry {
+    pub start_pc: u16,
+    pub line_number: u16,
+}
+
+impl LineNumberTableEntry {
+    /// Parses the given `LineNumberTable` attribute info bytes into the
+    /// entries that they describe.
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Vec<LineNumberTableEntry>> {
+        let mut reader = bytes;
+
+        let table_length =
+            parsing::read_u16(&mut reader).context("Failed to read line number table length.")?;
+
+        let mut entries = Vec::with_capacity(table_length as usize);
+        for _ in 0..table_length {
+            let start_pc =
+                parsing::read_u16(&mut reader).context("Failed to read line number start pc.")?;
+            let line_number =
+                parsing::read_u16(&mut reader).context("Failed to read line number.")?;
+
+            entries.push(LineNumberTableEntry {
+                start_pc,
+                line_number,
+            });
+        }
+
+        Ok(entries)
+    }
+
+    /// Converts the given entries into the `LineNumberTable` attribute info
+    /// bytes that represent them. This is the inverse of `from_bytes`.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::LineNumberTableEntry;
+    /// #
+    /// let entries = vec![LineNumberTableEntry {
+    ///     start_pc: 0,
+    ///     line_number: 1,
+    /// }];
+    ///
+    /// let bytes = LineNumberTableEntry::to_bytes(&entries).unwrap();
+    ///
+    /// assert_eq!(vec![0, 1, 0, 0, 0, 1], bytes);
+    /// ```
+    pub fn to_bytes(entries: &[LineNumberTableEntry]) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+
+        writing::write_line_number_table(&mut bytes, entries)?;
+
+        Ok(bytes)
+    }
+}
+
+/// An entry of a `LocalVariableTable` attribute, which gives the name and
+/// descriptor of the local variable in slot `index` for the instructions in
+/// the range from `start_pc` to `start_pc + length`.
+///
+/// See Chapter 4.7.13 of the JVM specification for details.
+///
+/// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-4.html#jvms-4.7.13
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct LocalVariableTableEntry {
+    pub start_pc: u16,
+    pub length: u16,
+    pub name_index: ConstantPoolIndex,
+    pub descriptor_index: ConstantPoolIndex,
+    pub index: u16,
+}
+
+impl LocalVariableTableEntry {
+    /// Parses the given `LocalVariableTable` attribute info bytes into the
+    /// entries that they describe.
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Vec<LocalVariableTableEntry>> {
+        let mut reader = bytes;
+
+        let table_length = parsing::read_u16(&mut reader)
+            .context("Failed to read local variable table length.")?;
+
+        let mut entries = Vec::with_capacity(table_length as usize);
+        for _ in 0..table_length {
+            let start_pc = parsing::read_u16(&mut reader)
+                .context("Failed to read local variable start pc.")?;
+            let length =
+                parsing::read_u16(&mut reader).context("Failed to read local variable length.")?;
+            let name_index = parsing::read_cp_index(&mut reader)
+                .context("Failed to read local variable name.")?;
+            let descriptor_index = parsing::read_cp_index(&mut reader)
+                .context("Failed to read local variable descriptor.")?;
+            let index =
+                parsing::read_u16(&mut reader).context("Failed to read local variable index.")?;
+
+            entries.push(LocalVariableTableEntry {
+                start_pc,
+                length,
+                name_index,
+                descriptor_index,
+                index,
+            });
+        }
+
+        Ok(entries)
+    }
+
+    /// Converts the given entries into the `LocalVariableTable` attribute info
+    /// bytes that represent them. This is the inverse of `from_bytes`.
+    ///
+    /// Fails if one of the entries has an invalid constant pool index.
+    pub fn to_bytes(entries: &[LocalVariableTableEntry]) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+
+        writing::write_local_variable_table(&mut bytes, entries)?;
+
+        Ok(bytes)
+    }
+}
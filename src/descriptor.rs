@@ -0,0 +1,691 @@
+use std::fmt;
+use std::str::Chars;
+
+/// The type of a field, parameter, or local variable, as encoded in a
+/// descriptor.
+///
+/// See Chapter 4.3.2 of the JVM specification for details.
+///
+/// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-4.html#jvms-4.3.2
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum FieldType {
+    Byte,
+    Char,
+    Double,
+    Float,
+    Int,
+    Long,
+    Short,
+    Boolean,
+    Object(String),
+    Array(Box<FieldType>),
+}
+
+/// The return type of a method, as encoded in a method descriptor.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ReturnType {
+    Void,
+    Field(FieldType),
+}
+
+/// The parameter and return types of a method.
+///
+/// See Chapter 4.3.3 of the JVM specification for details.
+///
+/// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-4.html#jvms-4.3.3
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct MethodDescriptor {
+    pub parameters: Vec<FieldType>,
+    pub return_type: ReturnType,
+}
+
+impl FieldType {
+    /// Parses the given field descriptor.
+    ///
+    /// Returns an error message if the descriptor is not a valid field
+    /// descriptor.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::FieldType;
+    /// #
+    /// assert_eq!(Ok(FieldType::Int), FieldType::parse("I"));
+    /// assert_eq!(
+    ///     Ok(FieldType::Array(Box::new(FieldType::Object(
+    ///         "java/lang/String".to_string()
+    ///     )))),
+    ///     FieldType::parse("[Ljava/lang/String;")
+    /// );
+    /// assert!(FieldType::parse("II").is_err());
+    /// ```
+    pub fn parse(descriptor: &str) -> Result<FieldType, String> {
+        let mut chars = descriptor.chars();
+
+        let field_type = read_field_type(&mut chars, descriptor)?;
+
+        if chars.next().is_some() {
+            return Err(format!(
+                "Found unexpected trailing characters in field descriptor: {}",
+                descriptor
+            ));
+        }
+
+        Ok(field_type)
+    }
+
+    /// Returns the number of local variable or operand stack slots that a
+    /// value of the type takes up, where longs and doubles take up two slots
+    /// and all other types take up one.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::FieldType;
+    /// #
+    /// assert_eq!(2, FieldType::Long.slot_size());
+    /// assert_eq!(1, FieldType::Int.slot_size());
+    /// ```
+    pub fn slot_size(&self) -> u16 {
+        match *self {
+            FieldType::Long | FieldType::Double => 2,
+            _ => 1,
+        }
+    }
+
+    /// Returns the field descriptor that encodes the type. This is the
+    /// inverse of `parse`.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::FieldType;
+    /// #
+    /// let field_type = FieldType::Array(Box::new(FieldType::Object(
+    ///     "java/lang/String".to_string(),
+    /// )));
+    ///
+    /// assert_eq!("[Ljava/lang/String;", field_type.to_descriptor());
+    /// ```
+    pub fn to_descriptor(&self) -> String {
+        let mut descriptor = String::new();
+        write_field_type(&mut descriptor, self);
+
+        descriptor
+    }
+}
+
+/// Formats the type as it would be written in Java source code, with class
+/// names in their binary form.
+///
+/// ```
+/// # use jvm_class_file_parser::FieldType;
+/// #
+/// let field_type = FieldType::parse("[Ljava/lang/String;").unwrap();
+///
+/// assert_eq!("java.lang.String[]", field_type.to_string());
+/// assert_eq!("int", FieldType::Int.to_string());
+/// ```
+impl fmt::Display for FieldType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::FieldType::*;
+
+        match *self {
+            Byte => write!(f, "byte"),
+            Char => write!(f, "char"),
+            Double => write!(f, "double"),
+            Float => write!(f, "float"),
+            Int => write!(f, "int"),
+            Long => write!(f, "long"),
+            Short => write!(f, "short"),
+            Boolean => write!(f, "boolean"),
+            Object(ref class_name) => write!(f, "{}", class_name.replace('/', ".")),
+            Array(ref component_type) => write!(f, "{}[]", component_type),
+        }
+    }
+}
+
+/// Converts the given class name from the internal form used in the constant
+/// pool into its binary form, as shown to users.
+///
+/// Array classes, whose internal names are field descriptors, are converted
+/// into the Java source form of the array type.
+///
+/// ```
+/// # use jvm_class_file_parser::internal_to_binary_name;
+/// #
+/// assert_eq!("java.lang.String", internal_to_binary_name("java/lang/String"));
+/// assert_eq!("int[][]", internal_to_binary_name("[[I"));
+/// ```
+pub fn internal_to_binary_name(internal_name: &str) -> String {
+    if internal_name.starts_with('[') {
+        if let Ok(array_type) = FieldType::parse(internal_name) {
+            return array_type.to_string();
+        }
+    }
+
+    internal_name.replace('/', ".")
+}
+
+/// The type that a Class constant refers to.
+///
+/// Class constants usually hold the internal name of a class or interface,
+/// but those used by instructions such as `anewarray`, `checkcast`, and
+/// `multianewarray` may instead hold the descriptor of an array type.
+///
+/// See Chapter 4.4.1 of the JVM specification for details.
+///
+/// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-4.html#jvms-4.4.1
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ClassType {
+    Class(String),
+    Array {
+        dimensions: usize,
+        element_type: FieldType,
+    },
+}
+
+impl ClassType {
+    /// Parses the given name of a Class constant.
+    ///
+    /// Returns an error message if the name is that of an array type, but is
+    /// not a valid array descriptor.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::{ClassType, FieldType};
+    /// #
+    /// assert_eq!(
+    ///     Ok(ClassType::Class("java/lang/String".to_string())),
+    ///     ClassType::parse("java/lang/String")
+    /// );
+    /// assert_eq!(
+    ///     Ok(ClassType::Array {
+    ///         dimensions: 2,
+    ///         element_type: FieldType::Int,
+    ///     }),
+    ///     ClassType::parse("[[I")
+    /// );
+    /// assert!(ClassType::parse("[").is_err());
+    /// ```
+    pub fn parse(name: &str) -> Result<ClassType, String> {
+        if !name.starts_with('[') {
+            return Ok(ClassType::Class(name.to_string()));
+        }
+
+        let mut element_type = FieldType::parse(name)?;
+        let mut dimensions = 0;
+        while let FieldType::Array(component_type) = element_type {
+            element_type = *component_type;
+            dimensions += 1;
+        }
+
+        Ok(ClassType::Array {
+            dimensions,
+            element_type,
+        })
+    }
+
+    /// Returns whether the type is an array type.
+    pub fn is_array(&self) -> bool {
+        matches!(*self, ClassType::Array { .. })
+    }
+}
+
+impl fmt::Display for ClassType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ClassType::Class(ref class_name) => write!(f, "{}", class_name.replace('/', ".")),
+            ClassType::Array {
+                dimensions,
+                ref element_type,
+            } => write!(f, "{}{}", element_type, "[]".repeat(dimensions)),
+        }
+    }
+}
+
+impl MethodDescriptor {
+    /// Parses the given method descriptor.
+    ///
+    /// Returns an error message if the descriptor is not a valid method
+    /// descriptor.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::{FieldType, MethodDescriptor, ReturnType};
+    /// #
+    /// assert_eq!(
+    ///     Ok(MethodDescriptor {
+    ///         parameters: vec![FieldType::Int, FieldType::Long],
+    ///         return_type: ReturnType::Void,
+    ///     }),
+    ///     MethodDescriptor::parse("(IJ)V")
+    /// );
+    /// ```
+    pub fn parse(descriptor: &str) -> Result<MethodDescriptor, String> {
+        let mut chars = descriptor.chars();
+
+        if chars.next() != Some('(') {
+            return Err(format!(
+                "Method descriptor does not start with a '(': {}",
+                descriptor
+            ));
+        }
+
+        let mut parameters = Vec::new();
+        loop {
+            if chars.as_str().starts_with(')') {
+                chars.next();
+                break;
+            }
+
+            parameters.push(read_field_type(&mut chars, descriptor)?);
+        }
+
+        let return_type = if chars.as_str() == "V" {
+            chars.next();
+            ReturnType::Void
+        } else {
+            ReturnType::Field(read_field_type(&mut chars, descriptor)?)
+        };
+
+        if chars.next().is_some() {
+            return Err(format!(
+                "Found unexpected trailing characters in method descriptor: {}",
+                descriptor
+            ));
+        }
+
+        Ok(MethodDescriptor {
+            parameters,
+            return_type,
+        })
+    }
+
+    /// Returns the number of local variable slots taken up by the parameters
+    /// of the method, where longs and doubles take up two slots.
+    ///
+    /// This does not include the slot used by `this` for instance methods.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::MethodDescriptor;
+    /// #
+    /// let descriptor = MethodDescriptor::parse("(IJLjava/lang/String;D)V").unwrap();
+    ///
+    /// assert_eq!(6, descriptor.parameter_slots());
+    /// ```
+    pub fn parameter_slots(&self) -> u16 {
+        self.parameters.iter().map(FieldType::slot_size).sum()
+    }
+}
+
+/// Returns the method descriptor that encodes the given parameter and return
+/// types. This is the inverse of `MethodDescriptor::parse`.
+///
+/// ```
+/// # use jvm_class_file_parser::{build_method_descriptor, FieldType, ReturnType};
+/// #
+/// let descriptor = build_method_descriptor(
+///     &[FieldType::Int, FieldType::Long],
+///     &ReturnType::Void,
+/// );
+///
+/// assert_eq!("(IJ)V", descriptor);
+/// ```
+pub fn build_method_descriptor(parameters: &[FieldType], return_type: &ReturnType) -> String {
+    let mut descriptor = String::from("(");
+
+    for parameter in parameters {
+        write_field_type(&mut descriptor, parameter);
+    }
+
+    descriptor.push(')');
+
+    match *return_type {
+        ReturnType::Void => descriptor.push('V'),
+        ReturnType::Field(ref field_type) => write_field_type(&mut descriptor, field_type),
+    }
+
+    descriptor
+}
+
+/// Returns a copy of the given descriptor or signature with each of the class
+/// names in it replaced by the result of the given function. Type variables
+/// and the names of inner classes of parameterized types (ex. `Inner` in
+/// `LOuter<TT;>.Inner;`) are left unchanged.
+///
+/// Returns `None` if the given string is not a valid field descriptor, method
+/// descriptor, or signature.
+pub(crate) fn map_class_names<F: FnMut(&str) -> String>(
+    signature: &str,
+    mut f: F,
+) -> Option<String> {
+    let mut mapper = ClassNameMapper {
+        input: signature,
+        position: 0,
+        output: String::with_capacity(signature.len()),
+        f: &mut f,
+    };
+
+    if mapper.peek() == Some('<') {
+        mapper.map_formal_type_parameters()?;
+    }
+
+    while let Some(c) = mapper.peek() {
+        match c {
+            '(' | ')' | '^' => mapper.copy_char(),
+            _ => mapper.map_type()?,
+        }
+    }
+
+    Some(mapper.output)
+}
+
+/// A parser of descriptors and signatures that copies its input into its
+/// output, replacing each of the class names it reads.
+struct ClassNameMapper<'a, F: FnMut(&str) -> String> {
+    input: &'a str,
+    position: usize,
+    output: String,
+    f: &'a mut F,
+}
+
+impl<'a, F: FnMut(&str) -> String> ClassNameMapper<'a, F> {
+    fn peek(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+
+    fn copy_char(&mut self) {
+        if let Some(c) = self.peek() {
+            self.output.push(c);
+            self.position += c.len_utf8();
+        }
+    }
+
+    /// Copies the input up to, but not including, the first of the given
+    /// delimiters.
+    fn copy_until(&mut self, delimiters: &[char]) -> Option<&'a str> {
+        let rest = &self.input[self.position..];
+        let end = rest.find(|c| delimiters.contains(&c))?;
+
+        self.position += end;
+
+        Some(&rest[..end])
+    }
+
+    fn map_formal_type_parameters(&mut self) -> Option<()> {
+        self.copy_char();
+
+        loop {
+            if self.peek()? == '>' {
+                self.copy_char();
+                return Some(());
+            }
+
+            let identifier = self.copy_until(&[':'])?;
+            self.output.push_str(identifier);
+
+            // the class bound may be empty, but the interface bounds may not
+            self.copy_char();
+            if self.peek()? != ':' {
+                self.map_type()?;
+            }
+
+            while self.peek()? == ':' {
+                self.copy_char();
+                self.map_type()?;
+            }
+        }
+    }
+
+    fn map_type(&mut self) -> Option<()> {
+        match self.peek()? {
+            'L' => self.map_class_type(),
+            'T' => {
+                let type_variable = self.copy_until(&[';'])?;
+                self.output.push_str(type_variable);
+                self.copy_char();
+
+                Some(())
+            }
+            '[' => {
+                self.copy_char();
+                self.map_type()
+            }
+            'B' | 'C' | 'D' | 'F' | 'I' | 'J' | 'S' | 'Z' | 'V' => {
+                self.copy_char();
+
+                Some(())
+            }
+            _ => None,
+        }
+    }
+
+    fn map_class_type(&mut self) -> Option<()> {
+        self.copy_char();
+
+        let class_name = self.copy_until(&[';', '<', '.'])?;
+        let new_class_name = (self.f)(class_name);
+        self.output.push_str(&new_class_name);
+
+        loop {
+            match self.peek()? {
+                '<' => self.map_type_arguments()?,
+                '.' => {
+                    self.copy_char();
+
+                    let inner_name = self.copy_until(&[';', '<', '.'])?;
+                    self.output.push_str(inner_name);
+                }
+                ';' => {
+                    self.copy_char();
+                    return Some(());
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    fn map_type_arguments(&mut self) -> Option<()> {
+        self.copy_char();
+
+        loop {
+            match self.peek()? {
+                '>' => {
+                    self.copy_char();
+                    return Some(());
+                }
+                '*' => self.copy_char(),
+                '+' | '-' => {
+                    self.copy_char();
+                    self.map_type()?;
+                }
+                _ => self.map_type()?,
+            }
+        }
+    }
+}
+
+fn write_field_type(descriptor: &mut String, field_type: &FieldType) {
+    use self::FieldType::*;
+
+    match *field_type {
+        Byte => descriptor.push('B'),
+        Char => descriptor.push('C'),
+        Double => descriptor.push('D'),
+        Float => descriptor.push('F'),
+        Int => descriptor.push('I'),
+        Long => descriptor.push('J'),
+        Short => descriptor.push('S'),
+        Boolean => descriptor.push('Z'),
+        Object(ref class_name) => {
+            descriptor.push('L');
+            descriptor.push_str(class_name);
+            descriptor.push(';');
+        }
+        Array(ref component_type) => {
+            descriptor.push('[');
+            write_field_type(descriptor, component_type);
+        }
+    }
+}
+
+fn read_field_type(chars: &mut Chars, descriptor: &str) -> Result<FieldType, String> {
+    use self::FieldType::*;
+
+    match chars.next() {
+        Some('B') => Ok(Byte),
+        Some('C') => Ok(Char),
+        Some('D') => Ok(Double),
+        Some('F') => Ok(Float),
+        Some('I') => Ok(Int),
+        Some('J') => Ok(Long),
+        Some('S') => Ok(Short),
+        Some('Z') => Ok(Boolean),
+        Some('L') => {
+            let rest = chars.as_str();
+
+            match rest.find(';') {
+                Some(end) if end > 0 => {
+                    let class_name = rest[..end].to_string();
+                    *chars = rest[end + 1..].chars();
+
+                    Ok(Object(class_name))
+                }
+                _ => Err(format!(
+                    "Found an invalid object type in descriptor: {}",
+                    descriptor
+                )),
+            }
+        }
+        Some('[') => Ok(Array(Box::new(read_field_type(chars, descriptor)?))),
+        Some(c) => Err(format!(
+            "Found unexpected character '{}' in descriptor: {}",
+            c, descriptor
+        )),
+        None => Err(format!("Descriptor ended unexpectedly: {}", descriptor)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FieldType;
+    use super::FieldType::*;
+    use super::{
+        build_method_descriptor, internal_to_binary_name, map_class_names, MethodDescriptor,
+        ReturnType,
+    };
+
+    #[test]
+    fn slot_size_of_category_2_types() {
+        assert_eq!(2, Long.slot_size());
+        assert_eq!(2, Double.slot_size());
+
+        assert_eq!(2, FieldType::parse("J").unwrap().slot_size());
+        assert_eq!(2, FieldType::parse("D").unwrap().slot_size());
+    }
+
+    #[test]
+    fn slot_size_of_category_1_types() {
+        for descriptor in ["B", "C", "F", "I", "S", "Z", "Ljava/lang/Long;", "[J", "[D"].iter() {
+            assert_eq!(1, FieldType::parse(descriptor).unwrap().slot_size());
+        }
+    }
+
+    #[test]
+    fn internal_to_binary_name_plain_class() {
+        assert_eq!(
+            "java.lang.String",
+            internal_to_binary_name("java/lang/String")
+        );
+    }
+
+    #[test]
+    fn internal_to_binary_name_nested_class() {
+        assert_eq!(
+            "com.example.Outer$Inner",
+            internal_to_binary_name("com/example/Outer$Inner")
+        );
+    }
+
+    #[test]
+    fn internal_to_binary_name_object_array() {
+        assert_eq!(
+            "java.lang.String[]",
+            internal_to_binary_name("[Ljava/lang/String;")
+        );
+    }
+
+    fn assert_build_round_trips(descriptor: &str) {
+        let parsed = MethodDescriptor::parse(descriptor).unwrap();
+
+        assert_eq!(
+            descriptor,
+            build_method_descriptor(&parsed.parameters, &parsed.return_type)
+        );
+    }
+
+    #[test]
+    fn build_method_descriptor_no_parameters() {
+        assert_eq!("()V", build_method_descriptor(&[], &ReturnType::Void));
+        assert_build_round_trips("()V");
+    }
+
+    #[test]
+    fn build_method_descriptor_object_return() {
+        assert_eq!(
+            "(I)Ljava/lang/String;",
+            build_method_descriptor(
+                &[Int],
+                &ReturnType::Field(Object("java/lang/String".to_string()))
+            )
+        );
+        assert_build_round_trips("(I)Ljava/lang/String;");
+    }
+
+    #[test]
+    fn build_method_descriptor_array_parameters() {
+        assert_eq!(
+            "([[JLjava/lang/Object;)Z",
+            build_method_descriptor(
+                &[
+                    Array(Box::new(Array(Box::new(Long)))),
+                    Object("java/lang/Object".to_string()),
+                ],
+                &ReturnType::Field(Boolean)
+            )
+        );
+        assert_build_round_trips("([[JLjava/lang/Object;)Z");
+    }
+
+    fn relocate(signature: &str) -> Option<String> {
+        map_class_names(signature, |name| name.replace("java/lang/", "shaded/"))
+    }
+
+    #[test]
+    fn map_class_names_descriptors() {
+        assert_eq!(Some("I".to_string()), relocate("I"));
+        assert_eq!(
+            Some("[[Lshaded/String;".to_string()),
+            relocate("[[Ljava/lang/String;")
+        );
+        assert_eq!(
+            Some("(ILshaded/Object;[J)Lshaded/String;".to_string()),
+            relocate("(ILjava/lang/Object;[J)Ljava/lang/String;")
+        );
+    }
+
+    #[test]
+    fn map_class_names_signatures() {
+        assert_eq!(
+            Some("<T:Lshaded/Object;L:Ljava/util/List<TT;>;>Lshaded/Object;".to_string()),
+            relocate("<T:Ljava/lang/Object;L:Ljava/util/List<TT;>;>Ljava/lang/Object;")
+        );
+        assert_eq!(
+            Some("<E::Lshaded/Comparable<-TE;>;>(TE;)V^Lshaded/Exception;".to_string()),
+            relocate("<E::Ljava/lang/Comparable<-TE;>;>(TE;)V^Ljava/lang/Exception;")
+        );
+        assert_eq!(
+            Some("Lshaded/Outer<*>.Inner<+Lshaded/Number;>;".to_string()),
+            relocate("Ljava/lang/Outer<*>.Inner<+Ljava/lang/Number;>;")
+        );
+    }
+
+    #[test]
+    fn map_class_names_invalid() {
+        assert_eq!(None, relocate("Ljava/lang/String"));
+        assert_eq!(None, relocate("(Q)V"));
+    }
+}
@@ -0,0 +1,422 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use attribute::*;
+use class_file::ClassFile;
+use compaction;
+use constant_pool::*;
+use field_access::FieldAccess;
+
+/// The part of a class file that a difference was found in.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum DiffLocation {
+    Class,
+    /// A field, identified by its name and descriptor (ex. `count:I`).
+    Field(String),
+    /// A method, identified by its name and descriptor (ex. `getValue:()I`).
+    Method(String),
+}
+
+impl fmt::Display for DiffLocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DiffLocation::Class => write!(f, "class"),
+            DiffLocation::Field(ref field) => write!(f, "field {}", field),
+            DiffLocation::Method(ref method) => write!(f, "method {}", method),
+        }
+    }
+}
+
+/// A semantic difference between two class files, as found by
+/// `ClassFile::diff`.
+///
+/// Members are matched up by their names and descriptors, and attributes by
+/// their names, so constants being placed at different indexes in the two
+/// constant pools does not result in any differences.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Difference {
+    VersionChanged,
+    ClassNameChanged,
+    SuperClassChanged,
+    InterfacesChanged,
+    AccessFlagsChanged(DiffLocation),
+    FieldAdded(String),
+    FieldRemoved(String),
+    FieldDescriptorChanged {
+        name: String,
+        old: String,
+        new: String,
+    },
+    MethodAdded(String),
+    MethodRemoved(String),
+    MethodDescriptorChanged {
+        name: String,
+        old: String,
+        new: String,
+    },
+    AttributeAdded(DiffLocation, String),
+    AttributeRemoved(DiffLocation, String),
+    AttributeChanged(DiffLocation, String),
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::Difference::*;
+
+        match *self {
+            VersionChanged => write!(f, "class version changed"),
+            ClassNameChanged => write!(f, "class name changed"),
+            SuperClassChanged => write!(f, "super class changed"),
+            InterfacesChanged => write!(f, "interfaces changed"),
+            AccessFlagsChanged(ref location) => write!(f, "{} access flags changed", location),
+            FieldAdded(ref field) => write!(f, "field added: {}", field),
+            FieldRemoved(ref field) => write!(f, "field removed: {}", field),
+            FieldDescriptorChanged {
+                ref name,
+                ref old,
+                ref new,
+            } => write!(f, "field {} descriptor changed: {} -> {}", name, old, new),
+            MethodAdded(ref method) => write!(f, "method added: {}", method),
+            MethodRemoved(ref method) => write!(f, "method removed: {}", method),
+            MethodDescriptorChanged {
+                ref name,
+                ref old,
+                ref new,
+            } => write!(f, "method {} descriptor changed: {} -> {}", name, old, new),
+            AttributeAdded(ref location, ref name) => {
+                write!(f, "{} attribute added: {}", location, name)
+            }
+            AttributeRemoved(ref location, ref name) => {
+                write!(f, "{} attribute removed: {}", location, name)
+            }
+            AttributeChanged(ref location, ref name) => {
+                write!(f, "{} attribute changed: {}", location, name)
+            }
+        }
+    }
+}
+
+/// A field or method, reduced to the parts of it that are compared.
+struct Member<'a> {
+    name: &'a str,
+    descriptor: &'a str,
+    access_flags: u16,
+    attributes: &'a AttributeSet,
+}
+
+impl<'a> Member<'a> {
+    fn key(&self) -> String {
+        format!("{}:{}", self.name, self.descriptor)
+    }
+}
+
+pub fn diff(old: &ClassFile, new: &ClassFile) -> Vec<Difference> {
+    let mut differences = Vec::new();
+
+    let mut old_constants = ConstantKeys::new(old);
+    let mut new_constants = ConstantKeys::new(new);
+
+    if (old.major_version, old.minor_version) != (new.major_version, new.minor_version) {
+        differences.push(Difference::VersionChanged);
+    }
+    if old_constants.key(old.this_class) != new_constants.key(new.this_class) {
+        differences.push(Difference::ClassNameChanged);
+    }
+    if old_constants.key(old.super_class) != new_constants.key(new.super_class) {
+        differences.push(Difference::SuperClassChanged);
+    }
+
+    let old_interfaces: Vec<String> = old
+        .interfaces
+        .iter()
+        .map(|index| old_constants.key(*index))
+        .collect();
+    let new_interfaces: Vec<String> = new
+        .interfaces
+        .iter()
+        .map(|index| new_constants.key(*index))
+        .collect();
+    if old_interfaces != new_interfaces {
+        differences.push(Difference::InterfacesChanged);
+    }
+
+    if old.raw_access_flags() != new.raw_access_flags() {
+        differences.push(Difference::AccessFlagsChanged(DiffLocation::Class));
+    }
+
+    let old_fields = old.fields.iter().map(|field| Member {
+        name: old.get_constant_utf8(field.name_index),
+        descriptor: old.get_constant_utf8(field.descriptor_index),
+        access_flags: FieldAccess::to_access_flags(&field.access_flags),
+        attributes: &field.attributes,
+    });
+    let new_fields = new.fields.iter().map(|field| Member {
+        name: new.get_constant_utf8(field.name_index),
+        descriptor: new.get_constant_utf8(field.descriptor_index),
+        access_flags: FieldAccess::to_access_flags(&field.access_flags),
+        attributes: &field.attributes,
+    });
+    diff_members(
+        MemberKind::Field,
+        (old_fields.collect(), &mut old_constants),
+        (new_fields.collect(), &mut new_constants),
+        &mut differences,
+    );
+
+    let old_methods = old.methods.iter().map(|method| Member {
+        name: old.get_constant_utf8(method.name_index),
+        descriptor: old.get_constant_utf8(method.descriptor_index),
+        access_flags: method.access_flags,
+        attributes: &method.attributes,
+    });
+    let new_methods = new.methods.iter().map(|method| Member {
+        name: new.get_constant_utf8(method.name_index),
+        descriptor: new.get_constant_utf8(method.descriptor_index),
+        access_flags: method.access_flags,
+        attributes: &method.attributes,
+    });
+    diff_members(
+        MemberKind::Method,
+        (old_methods.collect(), &mut old_constants),
+        (new_methods.collect(), &mut new_constants),
+        &mut differences,
+    );
+
+    diff_attributes(
+        DiffLocation::Class,
+        (&old.attributes, &mut old_constants),
+        (&new.attributes, &mut new_constants),
+        &mut differences,
+    );
+
+    differences
+}
+
+#[derive(Clone, Copy)]
+enum MemberKind {
+    Field,
+    Method,
+}
+
+impl MemberKind {
+    fn location(self, member: &Member) -> DiffLocation {
+        match self {
+            MemberKind::Field => DiffLocation::Field(member.key()),
+            MemberKind::Method => DiffLocation::Method(member.key()),
+        }
+    }
+
+    fn added(self, member: &Member) -> Difference {
+        match self {
+            MemberKind::Field => Difference::FieldAdded(member.key()),
+            MemberKind::Method => Difference::MethodAdded(member.key()),
+        }
+    }
+
+    fn removed(self, member: &Member) -> Difference {
+        match self {
+            MemberKind::Field => Difference::FieldRemoved(member.key()),
+            MemberKind::Method => Difference::MethodRemoved(member.key()),
+        }
+    }
+
+    fn descriptor_changed(self, old: &Member, new: &Member) -> Difference {
+        let name = old.name.to_string();
+        let old = old.descriptor.to_string();
+        let new = new.descriptor.to_string();
+
+        match self {
+            MemberKind::Field => Difference::FieldDescriptorChanged { name, old, new },
+            MemberKind::Method => Difference::MethodDescriptorChanged { name, old, new },
+        }
+    }
+}
+
+fn diff_members(
+    kind: MemberKind,
+    (old_members, old_constants): (Vec<Member>, &mut ConstantKeys),
+    (new_members, new_constants): (Vec<Member>, &mut ConstantKeys),
+    differences: &mut Vec<Difference>,
+) {
+    let same_member = |a: &Member, b: &Member| a.name == b.name && a.descriptor == b.descriptor;
+
+    let mut removed = Vec::new();
+    for old_member in old_members.iter() {
+        match new_members.iter().find(|m| same_member(m, old_member)) {
+            Some(new_member) => diff_member(
+                kind,
+                (old_member, old_constants),
+                (new_member, new_constants),
+                differences,
+            ),
+            None => removed.push(old_member),
+        }
+    }
+
+    let mut added: Vec<&Member> = new_members
+        .iter()
+        .filter(|new_member| !old_members.iter().any(|m| same_member(m, new_member)))
+        .collect();
+
+    // a member that was removed and one that was added with the same name are
+    // treated as a change of descriptor, as long as the pairing is unambiguous
+    let count_named =
+        |members: &[&Member], name: &str| members.iter().filter(|m| m.name == name).count();
+    let mut changed = Vec::new();
+    for old_member in removed.clone() {
+        if count_named(&removed, old_member.name) != 1 || count_named(&added, old_member.name) != 1
+        {
+            continue;
+        }
+
+        let position = added.iter().position(|m| m.name == old_member.name);
+        changed.push((old_member, added.remove(position.unwrap())));
+    }
+    removed.retain(|old_member| !changed.iter().any(|(m, _)| same_member(m, old_member)));
+
+    for old_member in removed {
+        differences.push(kind.removed(old_member));
+    }
+    for new_member in added {
+        differences.push(kind.added(new_member));
+    }
+    for (old_member, new_member) in changed {
+        differences.push(kind.descriptor_changed(old_member, new_member));
+        diff_member(
+            kind,
+            (old_member, old_constants),
+            (new_member, new_constants),
+            differences,
+        );
+    }
+}
+
+fn diff_member(
+    kind: MemberKind,
+    (old_member, old_constants): (&Member, &mut ConstantKeys),
+    (new_member, new_constants): (&Member, &mut ConstantKeys),
+    differences: &mut Vec<Difference>,
+) {
+    // changes are reported against the member as it is in the new class
+    let location = kind.location(new_member);
+
+    if old_member.access_flags != new_member.access_flags {
+        differences.push(Difference::AccessFlagsChanged(location.clone()));
+    }
+
+    diff_attributes(
+        location,
+        (old_member.attributes, old_constants),
+        (new_member.attributes, new_constants),
+        differences,
+    );
+}
+
+fn diff_attributes(
+    location: DiffLocation,
+    (old_attributes, old_constants): (&AttributeSet, &mut ConstantKeys),
+    (new_attributes, new_constants): (&AttributeSet, &mut ConstantKeys),
+    differences: &mut Vec<Difference>,
+) {
+    let old_class_file = old_constants.class_file;
+    let new_class_file = new_constants.class_file;
+
+    for old_attribute in old_attributes.attributes.iter() {
+        let name = old_attribute.name(old_class_file);
+
+        match new_attributes.find_attribute(new_class_file, name) {
+            Some(new_attribute) => {
+                if old_constants.attribute_key(old_attribute)
+                    != new_constants.attribute_key(new_attribute)
+                {
+                    differences.push(Difference::AttributeChanged(
+                        location.clone(),
+                        name.to_string(),
+                    ));
+                }
+            }
+            None => differences.push(Difference::AttributeRemoved(
+                location.clone(),
+                name.to_string(),
+            )),
+        }
+    }
+
+    for new_attribute in new_attributes.attributes.iter() {
+        let name = new_attribute.name(new_class_file);
+
+        if old_attributes
+            .find_attribute(old_class_file, name)
+            .is_none()
+        {
+            differences.push(Difference::AttributeAdded(
+                location.clone(),
+                name.to_string(),
+            ));
+        }
+    }
+}
+
+/// Builds representations of constants and attributes that do not depend on
+/// where in the constant pool the constants they reference are placed.
+struct ConstantKeys<'a> {
+    class_file: &'a ClassFile,
+    keys: HashMap<ConstantPoolIndex, String>,
+}
+
+impl<'a> ConstantKeys<'a> {
+    fn new(class_file: &'a ClassFile) -> ConstantKeys<'a> {
+        ConstantKeys {
+            class_file,
+            keys: HashMap::new(),
+        }
+    }
+
+    fn key(&mut self, index: ConstantPoolIndex) -> String {
+        if index == 0 || index > self.class_file.constant_pool.len() {
+            return format!("#{}", index);
+        }
+
+        if let Some(key) = self.keys.get(&index) {
+            return key.clone();
+        }
+
+        // guard against malformed constants that reference themselves
+        self.keys.insert(index, format!("#{}", index));
+
+        let mut referenced = Vec::new();
+        let entry = compaction::map_constant_references(
+            self.class_file.get_constant(index),
+            &mut |referenced_index| {
+                referenced.push(referenced_index);
+                0
+            },
+        );
+
+        let referenced_keys: Vec<String> = referenced.into_iter().map(|i| self.key(i)).collect();
+        let key = format!("{:?}{:?}", entry, referenced_keys);
+        self.keys.insert(index, key.clone());
+
+        key
+    }
+
+    fn attribute_key(&mut self, attribute: &Attribute) -> String {
+        let mut referenced = Vec::new();
+        let contents =
+            compaction::remap_attribute_contents(self.class_file, attribute, &mut |index| {
+                referenced.push(index);
+                0
+            });
+
+        match contents {
+            Ok(contents) => {
+                let referenced_keys: Vec<String> =
+                    referenced.into_iter().map(|i| self.key(i)).collect();
+
+                format!("{:?}{:?}", contents, referenced_keys)
+            }
+            // attributes that cannot be parsed are compared byte for byte
+            Err(_) => format!("{:?}", attribute.info),
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use std::fs::{self, File, FileType};
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
+
+use class_file::ClassFile;
+use error::ParseError;
+
+const CLASS_FILE_EXTENSION: &str = "class";
+
+/// Recursively finds the class files (`*.class`) in the given directory and
+/// parses each of them, pairing the path of each file with the result of
+/// parsing it.
+///
+/// A file that fails to parse does not stop the others from being parsed.
+/// Neither does a subdirectory that can not be read, which is instead paired
+/// with the error that reading it gave. Symbolic links to directories are not
+/// followed. The results are sorted by path.
+///
+/// ```
+/// # use jvm_class_file_parser::parse_directory;
+/// #
+/// let results = parse_directory("classes");
+///
+/// let (path, class_file) = results
+///     .iter()
+///     .find(|(path, _)| path.ends_with("Dummy.class"))
+///     .unwrap();
+///
+/// assert_eq!("Dummy", class_file.as_ref().unwrap().get_class_name());
+/// ```
+pub fn parse_directory<P: AsRef<Path>>(dir: P) -> Vec<(PathBuf, Result<ClassFile, ParseError>)> {
+    let mut results = vec![];
+    let mut directories = vec![dir.as_ref().to_path_buf()];
+
+    while let Some(directory) = directories.pop() {
+        let entries = match read_directory(&directory) {
+            Ok(entries) => entries,
+            Err(e) => {
+                results.push((
+                    directory,
+                    Err(to_parse_error(e, "Failed to read directory.")),
+                ));
+                continue;
+            }
+        };
+
+        for (path, file_type) in entries {
+            // symbolic links to directories are not followed, since they could
+            // lead back to a directory that is already being searched
+            if file_type.is_dir() {
+                directories.push(path);
+            } else if path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|ext| ext == CLASS_FILE_EXTENSION)
+            {
+                let result =
+                    parse_file(&path).map_err(|e| to_parse_error(e, "Failed to read file."));
+
+                results.push((path, result));
+            }
+        }
+    }
+
+    results.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    results
+}
+
+fn read_directory(directory: &Path) -> io::Result<Vec<(PathBuf, FileType)>> {
+    fs::read_dir(directory)?
+        .map(|entry| {
+            let entry = entry?;
+
+            Ok((entry.path(), entry.file_type()?))
+        })
+        .collect()
+}
+
+fn parse_file(path: &Path) -> io::Result<ClassFile> {
+    let file = File::open(path)?;
+
+    ClassFile::from_buf_reader(&mut BufReader::new(file))
+}
+
+/// Extracts the parse error from the given error, or wraps it in one if the
+/// error came from the file system rather than the contents of a class file.
+fn to_parse_error(error: io::Error, description: &str) -> ParseError {
+    if error.get_ref().is_some_and(|e| e.is::<ParseError>()) {
+        let inner = error.into_inner().expect("The error has an inner error.");
+
+        return *inner
+            .downcast::<ParseError>()
+            .expect("The inner error is a parse error.");
+    }
+
+    ParseError::Context {
+        description: description.to_string(),
+        source: error,
+    }
+}
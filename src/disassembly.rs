@@ -0,0 +1,704 @@
+use std::collections::HashSet;
+
+use attribute::*;
+use bytecode::*;
+use class_access::*;
+use class_file::ClassFile;
+use constant_pool::*;
+use descriptor::*;
+use field::*;
+use field_access::*;
+use method::*;
+use method_access::*;
+use util::hex_dump;
+
+const CONSTRUCTOR_NAME: &str = "<init>";
+const STATIC_INITIALIZER_NAME: &str = "<clinit>";
+
+/// Options that control the javap style output of `ClassFile::disassemble`.
+///
+/// ```
+/// # use jvm_class_file_parser::DisassemblyOptions;
+/// #
+/// let options = DisassemblyOptions {
+///     print_code: true,
+///     ..DisassemblyOptions::default()
+/// };
+///
+/// assert_eq!(20, options.constant_kind_width);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DisassemblyOptions {
+    /// Whether to show the bytecode of methods.
+    pub print_code: bool,
+
+    /// Whether to show comments with the resolved values of the constants
+    /// that constant pool entries reference.
+    pub resolve_comments: bool,
+
+    /// Whether to annotate each constant pool entry with the parts of the
+    /// class file that reference it (ex. other constants, or the name of an
+    /// attribute).
+    ///
+    /// Panics when disassembling a class file whose constant references can
+    /// not be found. See `ClassFile::constant_references` for details.
+    pub show_constant_references: bool,
+
+    /// The width of the column holding the index of a constant pool entry.
+    pub constant_index_width: usize,
+
+    /// The width of the column holding the kind of a constant pool entry (ex.
+    /// `Methodref`).
+    pub constant_kind_width: usize,
+
+    /// The width of the column holding the operands of a constant pool entry
+    /// (ex. `#3.#10`).
+    pub constant_operands_width: usize,
+
+    /// The width of the column holding a bytecode instruction and its
+    /// operands.
+    pub instruction_width: usize,
+}
+
+impl Default for DisassemblyOptions {
+    fn default() -> DisassemblyOptions {
+        DisassemblyOptions {
+            print_code: false,
+            resolve_comments: true,
+            show_constant_references: false,
+            constant_index_width: 5,
+            constant_kind_width: 20,
+            constant_operands_width: 16,
+            instruction_width: 35,
+        }
+    }
+}
+
+pub fn disassemble(class_file: &ClassFile, options: &DisassemblyOptions) -> String {
+    let mut output = String::new();
+
+    let source_file = class_file.get_source_file_name();
+    if let Some(source_file) = source_file {
+        output.push_str(&format!("  Compiled from: \"{}\"\n", source_file));
+    }
+
+    output.push_str(&format!("class {}", class_file.get_class_name()));
+
+    let type_parameters = class_file.type_parameters();
+    if !type_parameters.is_empty() {
+        let type_parameters: Vec<String> = type_parameters.iter().map(|p| p.to_string()).collect();
+        output.push_str(&format!("<{}>", type_parameters.join(", ")));
+    }
+    output.push('\n');
+
+    output.push_str(&format!("  minor version: {}\n", class_file.minor_version));
+    output.push_str(&format!("  major version: {}\n", class_file.major_version));
+
+    output.push_str(&print_access_flags(&class_file.access_flags));
+
+    output.push_str(&print_constant_pool(class_file, options));
+
+    output.push_str(&print_attributes(class_file, &class_file.attributes, ""));
+
+    output.push_str("{\n");
+
+    for field in class_file.fields.iter() {
+        output.push_str(&print_field(class_file, field));
+    }
+
+    for method in class_file.methods.iter() {
+        output.push_str(&print_method(class_file, method, options));
+    }
+
+    output.push_str("}\n");
+
+    if let Some(source_file) = source_file {
+        output.push_str(&format!("SourceFile: \"{}\"\n", source_file));
+    }
+
+    output
+}
+
+fn print_access_flags(access_flags: &HashSet<ClassAccess>) -> String {
+    let flags_str = ClassAccess::canonical_order(access_flags)
+        .iter()
+        .map(access_flag_to_name)
+        .collect::<Vec<&str>>()
+        .join(", ");
+
+    format!("  flags: {}\n", flags_str)
+}
+
+fn print_attributes(
+    class_file: &ClassFile,
+    attributes: &AttributeSet,
+    prefix: &'static str,
+) -> String {
+    // as with javap, the section is left out entirely if there is nothing in it
+    if attributes.attributes.is_empty() {
+        return String::new();
+    }
+
+    let mut output = format!("{}Attributes:\n", prefix);
+
+    attributes.attributes.iter().for_each(|attr| {
+        output.push_str(&format!(
+            "{}  {}\n",
+            prefix,
+            format_attribute(class_file, attr)
+        ));
+    });
+
+    output
+}
+
+/// Format an attribute (into a single-line value to preserve outer formatting)
+fn format_attribute(class_file: &ClassFile, attr: &Attribute) -> String {
+    let attr_type = attr.name(class_file);
+    // https://docs.oracle.com/javase/specs/jvms/se7/html/jvms-4.html#jvms-4.7
+    match attr_type {
+        "ConstantValue" => {
+            let index = u16::from_be_bytes([attr.info[0], attr.info[1]]) as usize;
+            format!(
+                "{}: {}",
+                attr_type,
+                format_constant_value(class_file, index)
+            )
+        }
+        // "Code" => {},
+        // "StackMapTable" => {},
+        // "Exceptions" => {},
+        // "InnerClasses" => {},
+        // "EnclosingMethod" => {},
+        // "Synthetic" => {},
+        // "Signature" => {},
+        "SourceFile" | "Signature" => {
+            // clean this up with u16::from() on a vec slice
+            let index = ((attr.info[0] as usize) << 8) + attr.info[1] as usize;
+            format!("{} = {:?}", attr_type, class_file.get_constant_utf8(index))
+        }
+        // "SourceDebugExtension" => {},
+        // "LineNumberTable" => {},
+        // "LocalVariableTable" => {},
+        // "LocalVariableTypeTable" => {},
+        // "Deprecated" => {},
+        // "RuntimeVisibleAnnotations" => {},
+        // "RuntimeInvisibleAnnotations" => {},
+        // "RuntimeVisibleParameterAnnotations" => {},
+        // "RuntimeInvisibleParameterAnnotations" => {},
+        // "AnnotationDefault" => {},
+        // "BootstrapMethods" => {},
+        _ => format!(
+            "{}: length = {:#x}, bytes = {}",
+            attr_type,
+            attr.info.len(),
+            hex_dump(&attr.info)
+        ),
+    }
+}
+
+/// Formats the value of the given constant, as shown for a ConstantValue
+/// attribute.
+fn format_constant_value(class_file: &ClassFile, index: usize) -> String {
+    use ConstantPoolEntry::*;
+
+    match *class_file.get_constant(index) {
+        ConstantInteger { val } => format!("int {}", val),
+        ConstantFloat { ref val } => format!("float {:?}f", f32::from(val)),
+        ConstantLong { val } => format!("long {}l", val),
+        ConstantDouble { ref val } => format!("double {:?}d", f64::from(val)),
+        ConstantString { string_index } => {
+            format!("String {}", class_file.get_constant_utf8(string_index))
+        }
+        ref constant => format!("<invalid constant value {:?}>", constant),
+    }
+}
+
+fn access_flag_to_name(flag: &ClassAccess) -> &'static str {
+    use ClassAccess::*;
+
+    match flag {
+        Public => "ACC_PUBLIC",
+        Final => "ACC_FINAL",
+        Super => "ACC_SUPER",
+        Interface => "ACC_INTERFACE",
+        Abstract => "ACC_ABSTRACT",
+        Synthetic => "ACC_SYNTHETIC",
+        Annotation => "ACC_ANNOTATION",
+        Enum => "ACC_ENUM",
+        Module => "ACC_MODULE",
+    }
+}
+
+fn print_constant_pool(class_file: &ClassFile, options: &DisassemblyOptions) -> String {
+    let mut output = "Constant pool:\n".to_string();
+
+    let references = if options.show_constant_references {
+        match class_file.constant_references() {
+            Ok(references) => Some(references),
+            Err(e) => panic!("Failed to find the constant references. {}", e),
+        }
+    } else {
+        None
+    };
+
+    for (i, constant) in class_file.constant_pool.iter().enumerate() {
+        // Account for 1 indexing
+        let i = i + 1;
+
+        output.push_str(&format!(
+            "{:>width$} = {}\n",
+            format!("#{}", i),
+            format_constant_pool_entry(class_file, constant, options),
+            width = options.constant_index_width
+        ));
+
+        if let Some(referrers) = references.as_ref().and_then(|r| r.get(&i)) {
+            let referrers = referrers
+                .iter()
+                .map(|referrer| referrer.to_string())
+                .collect::<Vec<String>>()
+                .join(", ");
+
+            output.push_str(&format!(
+                "{:width$}   referenced by: {}\n",
+                "",
+                referrers,
+                width = options.constant_index_width
+            ));
+        }
+    }
+
+    output
+}
+
+fn format_constant_pool_entry(
+    class_file: &ClassFile,
+    constant: &ConstantPoolEntry,
+    options: &DisassemblyOptions,
+) -> String {
+    use ConstantPoolEntry::*;
+
+    let kind_width = options.constant_kind_width;
+    let operands_width = options.constant_operands_width;
+
+    // formats an entry, with a comment holding the resolved values of the
+    // constants that it references, if comments are enabled
+    let with_comment = |kind: &str, operands: String, comment: &dyn Fn() -> String| {
+        if options.resolve_comments {
+            format!(
+                "{:<kind_width$}{:<operands_width$}// {}",
+                kind,
+                operands,
+                comment(),
+                kind_width = kind_width,
+                operands_width = operands_width
+            )
+        } else {
+            format!("{:<kind_width$}{}", kind, operands, kind_width = kind_width)
+        }
+    };
+
+    // formats an entry that does not reference any other constants
+    let without_comment = |kind: &str, operands: String| {
+        format!(
+            "{:<kind_width$}{:<operands_width$}",
+            kind,
+            operands,
+            kind_width = kind_width,
+            operands_width = operands_width
+        )
+    };
+
+    let kind = constant.kind_name();
+
+    match *constant {
+        ConstantUtf8 { ref string } => {
+            format!("{:<kind_width$}{}", kind, string, kind_width = kind_width)
+        }
+        ConstantClass { name_index } => with_comment(kind, format!("#{}", name_index), &|| {
+            class_file.get_constant_utf8(name_index).to_string()
+        }),
+        ConstantString { string_index } => {
+            with_comment(kind, format!("#{}", string_index), &|| {
+                class_file.get_constant_utf8(string_index).to_string()
+            })
+        }
+        ConstantInteger { ref val } => without_comment(kind, format!("={}", val)),
+        ConstantFloat { ref val } => {
+            let as_f32: f32 = val.into();
+            without_comment(kind, format!("={}", as_f32))
+        }
+        ConstantLong { val } => without_comment(kind, format!("={}", val)),
+        ConstantDouble { ref val } => {
+            let as_f64: f64 = val.into();
+            without_comment(kind, format!("={}", as_f64))
+        }
+        ConstantFieldref {
+            class_index,
+            name_and_type_index,
+        } => with_comment(
+            kind,
+            format!("#{}.#{}", class_index, name_and_type_index),
+            &|| {
+                format!(
+                    "{}.{}",
+                    class_file.get_constant_class_str(class_index),
+                    class_file.get_constant_name_and_type_str(name_and_type_index),
+                )
+            },
+        ),
+        ConstantMethodref {
+            class_index,
+            name_and_type_index,
+        } => with_comment(
+            kind,
+            format!("#{}.#{}", class_index, name_and_type_index),
+            &|| {
+                format!(
+                    "{}.{}",
+                    class_file.get_constant_class_str(class_index),
+                    class_file.get_constant_name_and_type_str(name_and_type_index),
+                )
+            },
+        ),
+        ConstantInterfaceMethodref {
+            class_index,
+            name_and_type_index,
+        } => with_comment(
+            kind,
+            format!("#{}.#{}", class_index, name_and_type_index),
+            &|| {
+                format!(
+                    "{}.{}",
+                    class_file.get_constant_class_str(class_index as usize),
+                    class_file.get_constant_name_and_type_str(name_and_type_index as usize),
+                )
+            },
+        ),
+        ConstantNameAndType {
+            name_index,
+            descriptor_index,
+        } => with_comment(
+            kind,
+            format!("#{}:#{}", name_index, descriptor_index),
+            &|| {
+                format!(
+                    "\"{}\":{}",
+                    class_file.get_constant_utf8(name_index),
+                    class_file.get_constant_utf8(descriptor_index),
+                )
+            },
+        ),
+        ConstantMethodHandle {
+            reference_kind,
+            reference_index,
+        } => without_comment(kind, format!("#{}:#{}", reference_kind, reference_index)),
+        ConstantMethodType { descriptor_index } => {
+            with_comment(kind, descriptor_index.to_string(), &|| {
+                class_file
+                    .get_constant_utf8(descriptor_index as usize)
+                    .to_string()
+            })
+        }
+        ConstantDynamic {
+            bootstrap_method_attr_index,
+            name_and_type_index,
+        }
+        | ConstantInvokeDynamic {
+            bootstrap_method_attr_index,
+            name_and_type_index,
+        } => with_comment(
+            kind,
+            format!("#{}:#{}", bootstrap_method_attr_index, name_and_type_index),
+            &|| match *class_file.get_constant(name_and_type_index as usize) {
+                ConstantNameAndType {
+                    name_index,
+                    descriptor_index,
+                } => format!(
+                    "#{}:{}:{}",
+                    bootstrap_method_attr_index,
+                    class_file.get_constant_utf8(name_index),
+                    class_file.get_constant_utf8(descriptor_index),
+                ),
+                ref constant => panic!(
+                    "Failed to get constant \"#{}\" as a ConstantNameAndType. Found: {:?}",
+                    name_and_type_index, constant
+                ),
+            },
+        ),
+        // as with javap, module names are quoted but package names are not
+        ConstantModule { name_index } => with_comment(kind, format!("#{}", name_index), &|| {
+            format!("\"{}\"", class_file.get_constant_utf8(name_index as usize))
+        }),
+        ConstantPackage { name_index } => with_comment(kind, format!("#{}", name_index), &|| {
+            class_file
+                .get_constant_utf8(name_index as usize)
+                .to_string()
+        }),
+        ConstantEmptySlot {} => kind.to_string(),
+    }
+}
+
+fn print_field(class_file: &ClassFile, field: &Field) -> String {
+    const PREFIX: &str = "    ";
+
+    let mut output = String::new();
+
+    let descriptor = class_file.get_constant_utf8(field.descriptor_index);
+
+    output.push_str(&format!(
+        "  {};\n",
+        print_field_declaration(class_file, field, descriptor)
+    ));
+
+    output.push_str(&format!("{}descriptor: {}\n", PREFIX, descriptor));
+
+    output.push_str(&format!(
+        "{}flags: {}\n",
+        PREFIX,
+        print_field_flags(&field.access_flags)
+    ));
+
+    if let Some(attr) = field.attributes.find_attribute(class_file, "ConstantValue") {
+        output.push_str(&format!(
+            "{}{}\n",
+            PREFIX,
+            format_attribute(class_file, attr)
+        ));
+    }
+
+    if let Some(sig) = field.attributes.get_signature(class_file) {
+        output.push_str(&format!("{}signature: {}\n", PREFIX, sig));
+    }
+
+    output.push('\n');
+
+    output
+}
+
+/// Returns the declaration of the given field as it is shown in the field
+/// header, with its modifiers and type as they would be written in Java source
+/// code.
+fn print_field_declaration(class_file: &ClassFile, field: &Field, descriptor: &str) -> String {
+    let name = class_file.get_constant_utf8(field.name_index);
+
+    let field_type = match FieldType::parse(descriptor) {
+        Ok(field_type) => field_type.to_string(),
+        Err(_) => descriptor.to_string(),
+    };
+
+    let modifiers = FieldAccess::source_modifiers(&field.access_flags);
+
+    if modifiers.is_empty() {
+        format!("{} {}", field_type, name)
+    } else {
+        format!("{} {} {}", modifiers, field_type, name)
+    }
+}
+
+fn print_field_flags(access_flags: &HashSet<FieldAccess>) -> String {
+    FieldAccess::canonical_order(access_flags)
+        .iter()
+        .map(field_access_flag_to_name)
+        .collect::<Vec<&str>>()
+        .join(", ")
+}
+
+fn field_access_flag_to_name(flag: &FieldAccess) -> &'static str {
+    use FieldAccess::*;
+
+    match flag {
+        Public => "ACC_PUBLIC",
+        Private => "ACC_PRIVATE",
+        Protected => "ACC_PROTECTED",
+        Static => "ACC_STATIC",
+        Final => "ACC_FINAL",
+        Volatile => "ACC_VOLATILE",
+        Transient => "ACC_TRANSIENT",
+        Synthetic => "ACC_SYNTHETIC",
+        Enum => "ACC_ENUM",
+    }
+}
+
+fn print_method_flags(access_flags: &HashSet<MethodAccess>) -> String {
+    MethodAccess::canonical_order(access_flags)
+        .iter()
+        .map(method_access_flag_to_name)
+        .collect::<Vec<&str>>()
+        .join(", ")
+}
+
+fn method_access_flag_to_name(flag: &MethodAccess) -> &'static str {
+    use MethodAccess::*;
+
+    match flag {
+        Public => "ACC_PUBLIC",
+        Private => "ACC_PRIVATE",
+        Protected => "ACC_PROTECTED",
+        Static => "ACC_STATIC",
+        Final => "ACC_FINAL",
+        Synchronized => "ACC_SYNCHRONIZED",
+        Bridge => "ACC_BRIDGE",
+        Varargs => "ACC_VARARGS",
+        Native => "ACC_NATIVE",
+        Abstract => "ACC_ABSTRACT",
+        Strict => "ACC_STRICT",
+        Synthetic => "ACC_SYNTHETIC",
+    }
+}
+
+fn print_method(class_file: &ClassFile, method: &Method, options: &DisassemblyOptions) -> String {
+    const PREFIX: &str = "    ";
+
+    let mut output = String::new();
+
+    output.push_str(&format!("  {};\n", print_method_name(class_file, method)));
+
+    output.push_str(&format!(
+        "{}descriptor: {}\n",
+        PREFIX,
+        class_file.get_constant_utf8(method.descriptor_index)
+    ));
+
+    if let Some(sig) = method.attributes.get_signature(class_file) {
+        output.push_str(&format!("{}signature: {}\n", PREFIX, sig));
+    }
+
+    output.push_str(&format!(
+        "{}flags: {}\n",
+        PREFIX,
+        print_method_flags(&method.access())
+    ));
+
+    if options.print_code {
+        // the code is shown decoded below, rather than as a raw attribute
+        let attributes = AttributeSet {
+            attributes: method
+                .attributes
+                .attributes
+                .iter()
+                .filter(|attr| attr.name(class_file) != "Code")
+                .cloned()
+                .collect(),
+        };
+
+        output.push_str(&print_attributes(class_file, &attributes, PREFIX));
+    } else {
+        output.push_str(&print_attributes(class_file, &method.attributes, PREFIX));
+    }
+
+    if options.print_code {
+        // the code is decoded leniently, so that the rest of the method is
+        // still shown if it contains an unsupported opcode
+        let code_opt = method
+            .attributes
+            .find_attribute(class_file, "Code")
+            .map(|attr| Code::from_bytes_lenient(&attr.info));
+
+        match code_opt {
+            Some(Ok(code)) => {
+                // as with javap, the size of the arguments includes `this`
+                let args_size = match method.required_locals(class_file) {
+                    Ok(args_size) => args_size.to_string(),
+                    Err(_) => "?".to_string(),
+                };
+
+                output.push_str("    Code:\n");
+                output.push_str(&format!(
+                    "      stack={}, locals={}, args_size={}\n",
+                    code.max_stack, code.max_locals, args_size
+                ));
+
+                output.push_str(&print_bytecode(class_file, &code.code, options));
+
+                if !code.exception_table.is_empty() {
+                    output.push_str(&print_exception_table(class_file, &code.exception_table));
+                }
+            }
+            Some(Err(e)) => {
+                output.push_str(&format!("    Code: <malformed code: {}>\n", e));
+            }
+            None if !method.is_concrete() => {
+                output.push_str(&format!("{}// method has no body\n", PREFIX));
+            }
+            None => {}
+        }
+    }
+
+    output
+}
+
+/// Returns the name of the given method as it is shown in the method header.
+///
+/// Constructors are shown with the name of the class and their parameters, and
+/// static initializers are shown as `static {}`.
+fn print_method_name(class_file: &ClassFile, method: &Method) -> String {
+    let method_name = class_file.get_constant_utf8(method.name_index);
+
+    match method_name {
+        CONSTRUCTOR_NAME => {
+            let descriptor = class_file.get_constant_utf8(method.descriptor_index);
+            let parameters = match MethodDescriptor::parse(descriptor) {
+                Ok(descriptor) => descriptor
+                    .parameters
+                    .iter()
+                    .map(|parameter| parameter.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", "),
+                Err(_) => String::new(),
+            };
+
+            format!("{}({})", class_file.get_class_name(), parameters)
+        }
+        STATIC_INITIALIZER_NAME => "static {}".to_string(),
+        _ => format!("{}()", method_name),
+    }
+}
+
+fn print_bytecode(
+    _class_file: &ClassFile,
+    code: &[(usize, Bytecode)],
+    options: &DisassemblyOptions,
+) -> String {
+    let mut output = String::new();
+
+    for (i, bytecode) in code {
+        output.push_str(&format!(
+            "        {:>3}: {:width$}\n",
+            i,
+            bytecode.to_string(*i as u16),
+            width = options.instruction_width
+        ));
+
+        // TODO: show constants to the side
+    }
+
+    output
+}
+
+fn print_exception_table(
+    class_file: &ClassFile,
+    exception_table: &[ExceptionTableEntry],
+) -> String {
+    let mut output = "      Exception table:\n         from    to  target type\n".to_string();
+
+    for entry in exception_table.iter() {
+        // a catch type of 0 catches any exception, as is done for finally
+        // blocks
+        let catch_type = match entry.catch_type {
+            0 => "any".to_string(),
+            catch_type => format!(
+                "Class {}",
+                class_file.get_constant_class_str(catch_type as usize)
+            ),
+        };
+
+        output.push_str(&format!(
+            "         {:5} {:5} {:5}   {}\n",
+            entry.start_pc, entry.end_pc, entry.handler_pc, catch_type,
+        ));
+    }
+
+    output
+}
@@ -0,0 +1,145 @@
+use std::error;
+use std::fmt;
+use std::io;
+
+/// An error that occurred while parsing a class file.
+///
+/// Parsing functions return `io::Result`s, with a `ParseError` as the inner
+/// error of any errors that are caused by the contents of the class file
+/// rather than by the reader. It can be retrieved from an `io::Error` using
+/// `get_ref` and `downcast_ref`.
+///
+/// ```
+/// # use std::error::Error;
+/// # use jvm_class_file_parser::{ClassFile, ParseError};
+/// #
+/// let bytes = [0xCA, 0xFE, 0xBA, 0xBE];
+///
+/// let error = ClassFile::from_file(&mut &bytes[..]).unwrap_err();
+///
+/// let parse_error = error
+///     .get_ref()
+///     .and_then(|e| e.downcast_ref::<ParseError>())
+///     .unwrap();
+///
+/// assert_eq!(
+///     "Failed to read minor version. (at offset 0x4) failed to fill whole buffer",
+///     parse_error.to_string()
+/// );
+/// assert_eq!(
+///     "failed to fill whole buffer",
+///     parse_error.source().unwrap().to_string()
+/// );
+/// ```
+#[derive(Debug)]
+pub enum ParseError {
+    /// The file does not start with the magic bytes of a class file.
+    InvalidMagic(u32),
+
+    /// A part of the class file is not valid.
+    Invalid(String),
+
+    /// A part of the class file could not be read, due to the given error.
+    Context {
+        description: String,
+        source: io::Error,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::ParseError::*;
+
+        match *self {
+            InvalidMagic(magic) => write!(
+                f,
+                "The given file does not appear to be a valid JVM class file. JVM class files must start with the magic bytes \"CAFEBABE\", but this file started with \"{:x}\"",
+                magic
+            ),
+            Invalid(ref message) => write!(f, "{}", message),
+            Context {
+                ref description,
+                ref source,
+            } => write!(f, "{} {}", description, source),
+        }
+    }
+}
+
+impl error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            ParseError::Context { ref source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for io::Error {
+    fn from(error: ParseError) -> io::Error {
+        let kind = match error {
+            // keep the kind of the underlying error (ex. UnexpectedEof)
+            ParseError::Context { ref source, .. } => source.kind(),
+            ParseError::InvalidMagic(_) => io::ErrorKind::InvalidData,
+            ParseError::Invalid(_) => io::ErrorKind::Other,
+        };
+
+        io::Error::new(kind, error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::io;
+
+    use super::ParseError;
+
+    #[test]
+    fn display_parse_errors() {
+        assert_eq!(
+            "The given file does not appear to be a valid JVM class file. JVM class files must start with the magic bytes \"CAFEBABE\", but this file started with \"cafed00d\"",
+            ParseError::InvalidMagic(0xCAFE_D00D).to_string()
+        );
+        assert_eq!(
+            "Invalid access flags.",
+            ParseError::Invalid("Invalid access flags.".to_string()).to_string()
+        );
+        assert_eq!(
+            "Failed to read constant pool. unexpected end of file",
+            ParseError::Context {
+                description: "Failed to read constant pool.".to_string(),
+                source: io::Error::new(io::ErrorKind::UnexpectedEof, "unexpected end of file"),
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn chain_parse_error_sources() {
+        let inner: io::Error = ParseError::Invalid("Invalid tag.".to_string()).into();
+        let outer = ParseError::Context {
+            description: "Failed to read constant pool.".to_string(),
+            source: inner,
+        };
+
+        assert_eq!(
+            "Failed to read constant pool. Invalid tag.",
+            outer.to_string()
+        );
+
+        let source = outer.source().unwrap();
+        assert_eq!("Invalid tag.", source.to_string());
+        assert!(source.source().is_none());
+    }
+
+    #[test]
+    fn parse_error_keeps_kind_of_source() {
+        let error: io::Error = ParseError::Context {
+            description: "Failed to read code.".to_string(),
+            source: io::Error::new(io::ErrorKind::UnexpectedEof, "unexpected end of file"),
+        }
+        .into();
+
+        assert_eq!(io::ErrorKind::UnexpectedEof, error.kind());
+    }
+}
@@ -1,6 +1,8 @@
 use std::collections::HashSet;
 
 use attribute::*;
+use class_file::ClassFile;
+use constant_pool::CpIndex;
 use field_access::*;
 use ConstantPoolIndex;
 
@@ -10,4 +12,75 @@ pub struct Field {
     pub name_index: ConstantPoolIndex,
     pub descriptor_index: ConstantPoolIndex,
     pub attributes: AttributeSet,
+
+    /// The offset of the field from the start of the class file that it was
+    /// parsed from, if offsets were recorded while parsing. See
+    /// `ParseOptions::record_offsets` for details.
+    pub file_offset: Option<usize>,
+}
+
+impl Field {
+    /// Returns whether the field was generated by the compiler, as marked by
+    /// either the `ACC_SYNTHETIC` flag or a `Synthetic` attribute.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Nest$Inner.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// // the reference to the enclosing instance, `this$0`
+    /// assert!(class_file.fields[0].is_synthetic(&class_file));
+    /// ```
+    pub fn is_synthetic(&self, class_file: &ClassFile) -> bool {
+        self.access_flags.contains(&FieldAccess::Synthetic)
+            || self
+                .attributes
+                .find_attribute(class_file, "Synthetic")
+                .is_some()
+    }
+
+    /// Returns the index of the constant that holds the value of the field,
+    /// as given by its `ConstantValue` attribute.
+    ///
+    /// If the field does not have a `ConstantValue` attribute, then a `None`
+    /// option is returned.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/StaticConstantValues.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let index = class_file.fields[0]
+    ///     .get_constant_value_index(&class_file)
+    ///     .unwrap();
+    ///
+    /// assert!(index.resolve(&class_file).is_ok());
+    /// ```
+    pub fn get_constant_value_index(&self, class_file: &ClassFile) -> Option<CpIndex> {
+        self.attributes
+            .find_attribute(class_file, "ConstantValue")
+            .map(|attr| {
+                if attr.info.len() != 2 {
+                    panic!("Incorrectly formatted ConstantValue attribute. Expected info length of 2, found: {}", attr.info.len());
+                }
+
+                let index = u16::from_be_bytes([attr.info[0], attr.info[1]]);
+
+                match CpIndex::new(index as ConstantPoolIndex) {
+                    Ok(index) => index,
+                    Err(e) => panic!("Incorrectly formatted ConstantValue attribute. {}", e),
+                }
+            })
+    }
+
+    /// Returns the offset of the field from the start of the class file that it
+    /// was parsed from, or `None` if offsets were not recorded. See
+    /// `ParseOptions::record_offsets` for details.
+    pub fn file_offset(&self) -> Option<usize> {
+        self.file_offset
+    }
 }
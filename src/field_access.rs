@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use util::flag_is_set;
+use util::{flag_is_set, join_source_keywords, sort_by_flag_bit};
 
 // Access flag masks are from Table 4.5-A of the JVM specification
 //
@@ -104,4 +104,124 @@ impl FieldAccess {
 
         Ok(access)
     }
+
+    /// Converts the given set of field access flags into the access flag value
+    /// that represents them. This is the inverse of `from_access_flags`.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::FieldAccess;
+    /// #
+    /// let access_flags = 0b0000_0000_0100_0001;
+    ///
+    /// let access = FieldAccess::from_access_flags(access_flags).unwrap();
+    ///
+    /// assert_eq!(access_flags, FieldAccess::to_access_flags(&access));
+    /// ```
+    #[allow(clippy::wrong_self_convention)]
+    pub fn to_access_flags(access_flags: &HashSet<FieldAccess>) -> u16 {
+        use FieldAccess::*;
+
+        let mut flags = 0;
+
+        if access_flags.contains(&Public) {
+            flags ^= PUBLIC_FLAG;
+        }
+        if access_flags.contains(&Private) {
+            flags ^= PRIVATE_FLAG;
+        }
+        if access_flags.contains(&Protected) {
+            flags ^= PROTECTED_FLAG;
+        }
+        if access_flags.contains(&Static) {
+            flags ^= STATIC_FLAG;
+        }
+        if access_flags.contains(&Final) {
+            flags ^= FINAL_FLAG;
+        }
+        if access_flags.contains(&Volatile) {
+            flags ^= VOLATILE_FLAG;
+        }
+        if access_flags.contains(&Transient) {
+            flags ^= TRANSIENT_FLAG;
+        }
+        if access_flags.contains(&Synthetic) {
+            flags ^= SYNTHETIC_FLAG;
+        }
+        if access_flags.contains(&Enum) {
+            flags ^= ENUM_FLAG;
+        }
+
+        flags
+    }
+
+    /// Returns the given flags in the canonical order used by javap.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::FieldAccess;
+    /// #
+    /// let access_flags = FieldAccess::from_access_flags(0x0019).unwrap();
+    ///
+    /// assert_eq!(
+    ///     vec![FieldAccess::Public, FieldAccess::Static, FieldAccess::Final],
+    ///     FieldAccess::canonical_order(&access_flags)
+    /// );
+    /// ```
+    pub fn canonical_order(access_flags: &HashSet<FieldAccess>) -> Vec<FieldAccess> {
+        sort_by_flag_bit(access_flags, FieldAccess::access_flag)
+    }
+
+    /// Returns the bit of the access flags that corresponds to the flag.
+    fn access_flag(&self) -> u16 {
+        use FieldAccess::*;
+
+        match self {
+            Public => PUBLIC_FLAG,
+            Private => PRIVATE_FLAG,
+            Protected => PROTECTED_FLAG,
+            Static => STATIC_FLAG,
+            Final => FINAL_FLAG,
+            Volatile => VOLATILE_FLAG,
+            Transient => TRANSIENT_FLAG,
+            Synthetic => SYNTHETIC_FLAG,
+            Enum => ENUM_FLAG,
+        }
+    }
+
+    /// Returns the Java source keyword that corresponds to the flag, or
+    /// `None` if the flag has no source form.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::FieldAccess;
+    /// #
+    /// assert_eq!(Some("volatile"), FieldAccess::Volatile.source_keyword());
+    /// assert_eq!(None, FieldAccess::Synthetic.source_keyword());
+    /// ```
+    pub fn source_keyword(&self) -> Option<&'static str> {
+        use FieldAccess::*;
+
+        match self {
+            Public => Some("public"),
+            Private => Some("private"),
+            Protected => Some("protected"),
+            Static => Some("static"),
+            Final => Some("final"),
+            Volatile => Some("volatile"),
+            Transient => Some("transient"),
+            Synthetic | Enum => None,
+        }
+    }
+
+    /// Returns the source keywords of the given flags joined in the canonical
+    /// Java modifier order.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::FieldAccess;
+    /// #
+    /// let access_flags = FieldAccess::from_access_flags(0x0019).unwrap();
+    ///
+    /// assert_eq!("public static final", FieldAccess::source_modifiers(&access_flags));
+    /// ```
+    pub fn source_modifiers(access_flags: &HashSet<FieldAccess>) -> String {
+        join_source_keywords(access_flags.iter().filter_map(FieldAccess::source_keyword))
+    }
 }
@@ -0,0 +1,232 @@
+use std::hash::{Hash, Hasher};
+use std::io;
+
+use attribute::read_code_undecoded;
+use bytecode::{raw_instructions, RawInstruction};
+use class_access::*;
+use class_file::ClassFile;
+use constant_pool::*;
+use field_access::*;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// A 64 bit FNV-1a hasher.
+///
+/// This is used instead of the standard library's `DefaultHasher` since the
+/// algorithm of the latter is not guaranteed to be the same between releases
+/// of Rust, and the hashes are meant to be stable enough to be persisted.
+pub struct FnvHasher {
+    hash: u64,
+}
+
+impl Default for FnvHasher {
+    fn default() -> FnvHasher {
+        FnvHasher {
+            hash: FNV_OFFSET_BASIS,
+        }
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.hash ^= u64::from(*byte);
+            self.hash = self.hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    // integers are always hashed as little endian 64 bit values so that the
+    // hash does not depend on the platform
+    fn write_u16(&mut self, i: u16) {
+        self.write_u64(u64::from(i));
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write_u64(u64::from(i));
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+}
+
+pub fn semantic_hash(class_file: &ClassFile) -> io::Result<u64> {
+    // the constants are hashed by following their references, which would
+    // recurse without end on a circular constant
+    class_file.validate_constant_references()?;
+
+    let mut hasher = FnvHasher::default();
+
+    class_file.get_class_name().hash(&mut hasher);
+    ClassAccess::to_access_flags(&class_file.access_flags).hash(&mut hasher);
+    hash_constant(class_file, class_file.super_class, &mut hasher);
+
+    class_file.interfaces.len().hash(&mut hasher);
+    for interface in class_file.interfaces.iter() {
+        hash_constant(class_file, *interface, &mut hasher);
+    }
+
+    class_file.fields.len().hash(&mut hasher);
+    for field in class_file.fields.iter() {
+        class_file
+            .get_constant_utf8(field.name_index)
+            .hash(&mut hasher);
+        class_file
+            .get_constant_utf8(field.descriptor_index)
+            .hash(&mut hasher);
+
+        FieldAccess::to_access_flags(&field.access_flags).hash(&mut hasher);
+    }
+
+    class_file.methods.len().hash(&mut hasher);
+    for method in class_file.methods.iter() {
+        class_file
+            .get_constant_utf8(method.name_index)
+            .hash(&mut hasher);
+        class_file
+            .get_constant_utf8(method.descriptor_index)
+            .hash(&mut hasher);
+        method.access_flags.hash(&mut hasher);
+
+        // the instructions are hashed from their bytes, rather than decoded,
+        // so that code with instructions that are not supported can be hashed
+        match method.attributes.find_attribute(class_file, "Code") {
+            Some(attr) => {
+                true.hash(&mut hasher);
+
+                let (code, code_bytes) = read_code_undecoded(&attr.info)?;
+
+                code.max_stack.hash(&mut hasher);
+                code.max_locals.hash(&mut hasher);
+
+                let instructions = raw_instructions(code_bytes)?;
+                instructions.len().hash(&mut hasher);
+                for instruction in instructions.iter() {
+                    instruction.pc.hash(&mut hasher);
+                    hash_instruction(class_file, instruction, &mut hasher);
+                }
+
+                code.exception_table.len().hash(&mut hasher);
+                for entry in code.exception_table.iter() {
+                    entry.start_pc.hash(&mut hasher);
+                    entry.end_pc.hash(&mut hasher);
+                    entry.handler_pc.hash(&mut hasher);
+                    hash_constant(class_file, entry.catch_type as usize, &mut hasher);
+                }
+            }
+            None => false.hash(&mut hasher),
+        }
+    }
+
+    Ok(hasher.finish())
+}
+
+/// Hashes the given instruction by its opcode and operands, replacing any
+/// constant pool index operand with the contents of the constant that it
+/// points to.
+fn hash_instruction<H: Hasher>(
+    class_file: &ClassFile,
+    instruction: &RawInstruction,
+    hasher: &mut H,
+) {
+    instruction.opcode().hash(hasher);
+
+    match instruction.constant_pool_index() {
+        Some(index) => {
+            // operands may follow a 2 byte index (ex. the argument count of
+            // invokeinterface)
+            instruction.bytes.get(3..).unwrap_or_default().hash(hasher);
+            hash_constant(class_file, index, hasher);
+        }
+        None => instruction.bytes[1..].hash(hasher),
+    }
+}
+
+/// Hashes the contents of the specified constant, following any references it
+/// makes to other constants so that the hash does not depend on the ordering
+/// of the constant pool.
+///
+/// An index of 0 (ex. the super class of `java/lang/Object`) is hashed as a
+/// missing constant.
+fn hash_constant<H: Hasher>(class_file: &ClassFile, index: ConstantPoolIndex, hasher: &mut H) {
+    use ConstantPoolEntry::*;
+
+    if index == 0 {
+        0u8.hash(hasher);
+        return;
+    }
+
+    // the kind is hashed by name, rather than by its position in the enum, so
+    // that the hash does not change if kinds are added
+    let constant = class_file.get_constant(index);
+    constant.kind_name().hash(hasher);
+
+    match *constant {
+        ConstantUtf8 { ref string } => string.hash(hasher),
+        ConstantInteger { val } => val.hash(hasher),
+        ConstantFloat { ref val } => val.buf.hash(hasher),
+        ConstantLong { val } => val.hash(hasher),
+        ConstantDouble { ref val } => val.buf.hash(hasher),
+        ConstantClass { name_index } => hash_constant(class_file, name_index, hasher),
+        ConstantString { string_index } => hash_constant(class_file, string_index, hasher),
+        ConstantFieldref {
+            class_index,
+            name_and_type_index,
+        }
+        | ConstantMethodref {
+            class_index,
+            name_and_type_index,
+        } => {
+            hash_constant(class_file, class_index, hasher);
+            hash_constant(class_file, name_and_type_index, hasher);
+        }
+        ConstantInterfaceMethodref {
+            class_index,
+            name_and_type_index,
+        } => {
+            hash_constant(class_file, class_index as usize, hasher);
+            hash_constant(class_file, name_and_type_index as usize, hasher);
+        }
+        ConstantNameAndType {
+            name_index,
+            descriptor_index,
+        } => {
+            hash_constant(class_file, name_index, hasher);
+            hash_constant(class_file, descriptor_index, hasher);
+        }
+        ConstantMethodHandle {
+            reference_kind,
+            reference_index,
+        } => {
+            reference_kind.hash(hasher);
+            hash_constant(class_file, reference_index as usize, hasher);
+        }
+        ConstantMethodType { descriptor_index } => {
+            hash_constant(class_file, descriptor_index as usize, hasher)
+        }
+        ConstantDynamic {
+            bootstrap_method_attr_index,
+            name_and_type_index,
+        }
+        | ConstantInvokeDynamic {
+            bootstrap_method_attr_index,
+            name_and_type_index,
+        } => {
+            bootstrap_method_attr_index.hash(hasher);
+            hash_constant(class_file, name_and_type_index as usize, hasher);
+        }
+        ConstantModule { name_index } | ConstantPackage { name_index } => {
+            hash_constant(class_file, name_index as usize, hasher)
+        }
+        ConstantEmptySlot {} => {}
+    }
+}
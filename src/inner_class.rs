@@ -0,0 +1,80 @@
+use std::collections::HashSet;
+use std::io;
+
+use class_file::ClassFile;
+use inner_class_access::InnerClassAccess;
+use parsing;
+use util::Contextable;
+use ConstantPoolIndex;
+
+/// A nested class that is a member of, or is referenced by, a class, as given
+/// by an entry of the `InnerClasses` attribute.
+///
+/// See Chapter 4.7.6 of the JVM specification for details.
+///
+/// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-4.html#jvms-4.7.6
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct InnerClass {
+    pub inner_class_info_index: ConstantPoolIndex,
+    /// The class that the nested class is a member of, or 0 if it is not a
+    /// member (ex. a local or anonymous class).
+    pub outer_class_info_index: ConstantPoolIndex,
+    /// The simple name of the nested class, or 0 if it is anonymous.
+    pub inner_name_index: ConstantPoolIndex,
+    pub inner_class_access_flags: u16,
+}
+
+impl InnerClass {
+    /// Parses the given `InnerClasses` attribute info bytes into the nested
+    /// classes that they describe.
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Vec<InnerClass>> {
+        let mut reader = bytes;
+
+        let number_of_classes =
+            parsing::read_u16(&mut reader).context("Failed to read number of inner classes.")?;
+
+        let mut inner_classes = Vec::with_capacity(number_of_classes as usize);
+        for _ in 0..number_of_classes {
+            let inner_class_info_index = parsing::read_cp_index(&mut reader)
+                .context("Failed to read inner class info index.")?;
+            let outer_class_info_index = parsing::read_cp_index(&mut reader)
+                .context("Failed to read outer class info index.")?;
+            let inner_name_index =
+                parsing::read_cp_index(&mut reader).context("Failed to read inner name index.")?;
+            let inner_class_access_flags = parsing::read_u16(&mut reader)
+                .context("Failed to read inner class access flags.")?;
+
+            inner_classes.push(InnerClass {
+                inner_class_info_index,
+                outer_class_info_index,
+                inner_name_index,
+                inner_class_access_flags,
+            });
+        }
+
+        Ok(inner_classes)
+    }
+
+    /// Returns the name of the nested class (ex. `Nest$Inner`).
+    pub fn get_class_name<'a>(&self, class_file: &'a ClassFile) -> &'a str {
+        class_file.get_constant_class_str(self.inner_class_info_index)
+    }
+
+    /// Returns the simple name of the nested class (ex. `Inner`), or `None`
+    /// if the class is anonymous.
+    pub fn get_inner_name<'a>(&self, class_file: &'a ClassFile) -> Option<&'a str> {
+        if self.inner_name_index == 0 {
+            None
+        } else {
+            Some(class_file.get_constant_utf8(self.inner_name_index))
+        }
+    }
+
+    /// Returns the access flags that the nested class was declared with.
+    pub fn access(&self) -> HashSet<InnerClassAccess> {
+        match InnerClassAccess::from_access_flags(self.inner_class_access_flags) {
+            Ok(access) => access,
+            Err(message) => panic!("Failed to read the inner class access flags. {}", message),
+        }
+    }
+}
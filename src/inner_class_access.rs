@@ -0,0 +1,183 @@
+use std::collections::HashSet;
+
+use util::{flag_is_set, join_source_keywords, sort_by_flag_bit};
+
+// Access flag masks are from Table 4.7.6-A of the JVM specification
+//
+// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-4.html#jvms-4.7.6-300-D.1-D.1
+const PUBLIC_FLAG: u16 = 0x0001;
+const PRIVATE_FLAG: u16 = 0x0002;
+const PROTECTED_FLAG: u16 = 0x0004;
+const STATIC_FLAG: u16 = 0x0008;
+const FINAL_FLAG: u16 = 0x0010;
+const INTERFACE_FLAG: u16 = 0x0200;
+const ABSTRACT_FLAG: u16 = 0x0400;
+const SYNTHETIC_FLAG: u16 = 0x1000;
+const ANNOTATION_FLAG: u16 = 0x2000;
+const ENUM_FLAG: u16 = 0x4000;
+
+/// A flag that denotes an access level or property of a nested class, as
+/// given by an entry of the `InnerClasses` attribute.
+///
+/// Unlike the flags of a top level class, these include `private`,
+/// `protected`, and `static`, since they describe the class as it was
+/// declared in the source code of its enclosing class.
+///
+/// See Chapter 4.7.6 of the JVM specification for details.
+///
+/// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-4.html#jvms-4.7.6
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum InnerClassAccess {
+    Public,
+    Private,
+    Protected,
+    Static,
+    Final,
+    Interface,
+    Abstract,
+    Synthetic,
+    Annotation,
+    Enum,
+}
+
+const ALL_FLAGS: [InnerClassAccess; 10] = [
+    InnerClassAccess::Public,
+    InnerClassAccess::Private,
+    InnerClassAccess::Protected,
+    InnerClassAccess::Static,
+    InnerClassAccess::Final,
+    InnerClassAccess::Interface,
+    InnerClassAccess::Abstract,
+    InnerClassAccess::Synthetic,
+    InnerClassAccess::Annotation,
+    InnerClassAccess::Enum,
+];
+
+impl InnerClassAccess {
+    /// Extracts the list of nested class access flags that are embedded in
+    /// the given `inner_class_access_flags` value.
+    ///
+    /// Returns an error message if the extracted combination of access flags
+    /// are inconsistent. (This validation has not yet been implemented)
+    ///
+    /// ```
+    /// # use std::collections::HashSet;
+    /// # use jvm_class_file_parser::InnerClassAccess;
+    /// #
+    /// let access_flags = 0b0000_0000_0000_1010;
+    ///
+    /// let mut expected = HashSet::new();
+    /// expected.insert(InnerClassAccess::Private);
+    /// expected.insert(InnerClassAccess::Static);
+    ///
+    /// assert_eq!(Ok(expected), InnerClassAccess::from_access_flags(access_flags));
+    /// ```
+    pub fn from_access_flags(access_flags: u16) -> Result<HashSet<InnerClassAccess>, String> {
+        // TODO: Add validation for inconsistent access flags
+
+        Ok(ALL_FLAGS
+            .iter()
+            .filter(|flag| flag_is_set(flag.access_flag(), access_flags))
+            .cloned()
+            .collect())
+    }
+
+    /// Converts the given set of nested class access flags into the access
+    /// flag value that represents them. This is the inverse of
+    /// `from_access_flags`.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::InnerClassAccess;
+    /// #
+    /// let access_flags = 0b0000_0110_0000_1001;
+    ///
+    /// let access = InnerClassAccess::from_access_flags(access_flags).unwrap();
+    ///
+    /// assert_eq!(access_flags, InnerClassAccess::to_access_flags(&access));
+    /// ```
+    #[allow(clippy::wrong_self_convention)]
+    pub fn to_access_flags(access_flags: &HashSet<InnerClassAccess>) -> u16 {
+        access_flags
+            .iter()
+            .fold(0, |flags, flag| flags | flag.access_flag())
+    }
+
+    /// Returns the bit of the access flags that corresponds to the flag.
+    fn access_flag(&self) -> u16 {
+        use InnerClassAccess::*;
+
+        match self {
+            Public => PUBLIC_FLAG,
+            Private => PRIVATE_FLAG,
+            Protected => PROTECTED_FLAG,
+            Static => STATIC_FLAG,
+            Final => FINAL_FLAG,
+            Interface => INTERFACE_FLAG,
+            Abstract => ABSTRACT_FLAG,
+            Synthetic => SYNTHETIC_FLAG,
+            Annotation => ANNOTATION_FLAG,
+            Enum => ENUM_FLAG,
+        }
+    }
+
+    /// Returns the given flags in the canonical order used by javap.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::InnerClassAccess;
+    /// #
+    /// let access_flags = InnerClassAccess::from_access_flags(0x001a).unwrap();
+    ///
+    /// assert_eq!(
+    ///     vec![
+    ///         InnerClassAccess::Private,
+    ///         InnerClassAccess::Static,
+    ///         InnerClassAccess::Final
+    ///     ],
+    ///     InnerClassAccess::canonical_order(&access_flags)
+    /// );
+    /// ```
+    pub fn canonical_order(access_flags: &HashSet<InnerClassAccess>) -> Vec<InnerClassAccess> {
+        sort_by_flag_bit(access_flags, InnerClassAccess::access_flag)
+    }
+
+    /// Returns the Java source keyword that corresponds to the flag, or
+    /// `None` if the flag has no source modifier form.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::InnerClassAccess;
+    /// #
+    /// assert_eq!(Some("static"), InnerClassAccess::Static.source_keyword());
+    /// assert_eq!(None, InnerClassAccess::Interface.source_keyword());
+    /// ```
+    pub fn source_keyword(&self) -> Option<&'static str> {
+        use InnerClassAccess::*;
+
+        match self {
+            Public => Some("public"),
+            Private => Some("private"),
+            Protected => Some("protected"),
+            Static => Some("static"),
+            Final => Some("final"),
+            Abstract => Some("abstract"),
+            Interface | Synthetic | Annotation | Enum => None,
+        }
+    }
+
+    /// Returns the source keywords of the given flags joined in the canonical
+    /// Java modifier order.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::InnerClassAccess;
+    /// #
+    /// let access_flags = InnerClassAccess::from_access_flags(0x000a).unwrap();
+    ///
+    /// assert_eq!("private static", InnerClassAccess::source_modifiers(&access_flags));
+    /// ```
+    pub fn source_modifiers(access_flags: &HashSet<InnerClassAccess>) -> String {
+        join_source_keywords(
+            access_flags
+                .iter()
+                .filter_map(InnerClassAccess::source_keyword),
+        )
+    }
+}
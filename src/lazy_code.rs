@@ -0,0 +1,72 @@
+use std::cell::OnceCell;
+use std::io;
+use std::slice;
+
+use attribute::Code;
+use bytecode::Bytecode;
+
+/// The body of a method, as given by its Code attribute, which is only
+/// decoded the first time that its instructions are accessed.
+///
+/// This avoids the cost of decoding the bytecode of methods that are never
+/// examined.
+///
+/// ```
+/// # use std::fs::File;
+/// # use jvm_class_file_parser::ClassFile;
+/// #
+/// let mut file = File::open("classes/Dummy.class").unwrap();
+/// let class_file = ClassFile::from_file(&mut file).unwrap();
+///
+/// let lazy_code = class_file.methods[0].get_lazy_code(&class_file).unwrap();
+/// assert!(!lazy_code.is_decoded());
+///
+/// assert_eq!(3, lazy_code.instructions().unwrap().count());
+/// assert!(lazy_code.is_decoded());
+/// ```
+#[derive(Debug)]
+pub struct LazyCode<'a> {
+    bytes: &'a [u8],
+    code: OnceCell<Code>,
+}
+
+impl<'a> LazyCode<'a> {
+    /// Creates a lazily decoded code from the given Code attribute info
+    /// bytes.
+    pub fn new(bytes: &'a [u8]) -> LazyCode<'a> {
+        LazyCode {
+            bytes,
+            code: OnceCell::new(),
+        }
+    }
+
+    /// Returns the undecoded Code attribute info bytes.
+    pub fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Returns whether the code has already been decoded.
+    pub fn is_decoded(&self) -> bool {
+        self.code.get().is_some()
+    }
+
+    /// Returns the decoded code, decoding it if it has not yet been decoded.
+    ///
+    /// Returns an error if the Code attribute info bytes are malformed. In
+    /// that case the code remains undecoded.
+    pub fn code(&self) -> io::Result<&Code> {
+        if let Some(code) = self.code.get() {
+            return Ok(code);
+        }
+
+        let code = Code::from_bytes(self.bytes)?;
+
+        Ok(self.code.get_or_init(|| code))
+    }
+
+    /// Returns an iterator over the instructions of the code and their
+    /// offsets, decoding the code if it has not yet been decoded.
+    pub fn instructions(&self) -> io::Result<slice::Iter<'_, (usize, Bytecode)>> {
+        self.code().map(|code| code.code.iter())
+    }
+}
@@ -12,23 +12,79 @@
 //! assert_eq!("Dummy", class_file.get_class_name());
 //! ```
 
+#[cfg(feature = "tokio")]
+extern crate tokio;
+
+mod annotation;
+#[cfg(feature = "tokio")]
+mod async_parsing;
 mod attribute;
+mod bootstrap_method;
+mod builder;
 mod bytecode;
 mod class_access;
 mod class_file;
+mod class_kind;
+mod compaction;
 mod constant_pool;
+mod constant_references;
+mod constant_validation;
+mod control_flow;
+mod crc;
+mod debug_info;
+mod descriptor;
+mod diff;
+mod directory;
+mod disassembly;
+mod error;
 mod field;
 mod field_access;
+mod hashing;
+mod inner_class;
+mod inner_class_access;
+mod lazy_code;
+mod maxes;
+mod member_ref;
 mod method;
+mod method_access;
+mod method_handle;
 mod parsing;
+mod record;
+mod relocation;
+mod signature;
+mod structure_validation;
 mod util;
+mod verification_type;
 mod writing;
 
+pub use annotation::*;
 pub use attribute::*;
+pub use bootstrap_method::*;
+pub use builder::*;
 pub use bytecode::*;
 pub use class_access::*;
 pub use class_file::*;
+pub use class_kind::*;
 pub use constant_pool::*;
+pub use constant_references::{AttributeOwner, ConstantReferrer};
+pub use control_flow::{BasicBlock, ControlFlowGraph};
+pub use debug_info::*;
+pub use descriptor::*;
+pub use diff::{DiffLocation, Difference};
+pub use directory::parse_directory;
+pub use disassembly::DisassemblyOptions;
+pub use error::*;
 pub use field::*;
 pub use field_access::*;
+pub use inner_class::*;
+pub use inner_class_access::*;
+pub use lazy_code::*;
+pub use member_ref::*;
 pub use method::*;
+pub use method_access::*;
+pub use method_handle::*;
+pub use parsing::ParseOptions;
+pub use record::*;
+pub use signature::TypeParameter;
+pub use verification_type::VerificationType;
+pub use writing::WriteOptions;
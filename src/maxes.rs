@@ -0,0 +1,212 @@
+use std::collections::{BTreeSet, HashMap};
+use std::io;
+
+use attribute::Code;
+use bytecode::Bytecode;
+use class_file::ClassFile;
+use descriptor::*;
+use method::Method;
+use util::{io_err, promote_result_to_io};
+
+/// Computes the maximum operand stack depth and number of local variable
+/// slots needed by the given code of the given method.
+///
+/// The maximum stack depth is found by simulating the stack depth along every
+/// path through the code, including the paths into exception handlers. The
+/// number of locals is one more than the largest local variable slot that is
+/// used by an instruction (including the second slot of a `long` or
+/// `double`), or the number of slots taken up by the parameters of the method
+/// if that is larger.
+pub fn compute_maxes(
+    code: &Code,
+    class_file: &ClassFile,
+    method: &Method,
+) -> io::Result<(u16, u16)> {
+    let max_stack = compute_max_stack(code, class_file)?;
+
+    let max_locals =
+        locals_needed(&code.used_local_slots()?).max(method.required_locals(class_file)?);
+
+    Ok((max_stack, max_locals))
+}
+
+/// Returns the number of local variable slots needed to hold the given used
+/// slots.
+pub fn locals_needed(slots: &BTreeSet<u16>) -> u16 {
+    slots
+        .iter()
+        .next_back()
+        .map_or(0, |slot| slot.saturating_add(1))
+}
+
+fn compute_max_stack(code: &Code, class_file: &ClassFile) -> io::Result<u16> {
+    let indexes: HashMap<usize, usize> = code
+        .code
+        .iter()
+        .enumerate()
+        .map(|(i, (pc, _))| (*pc, i))
+        .collect();
+
+    let index_of = |pc: usize| {
+        indexes.get(&pc).cloned().ok_or_else(|| {
+            io_err(format!(
+                "Found a jump to offset {}, which is not the start of an instruction.",
+                pc
+            ))
+        })
+    };
+
+    // the stack depth on entry to each instruction
+    let mut depths: Vec<Option<i32>> = vec![None; code.code.len()];
+    let mut worklist = vec![];
+
+    if !code.code.is_empty() {
+        worklist.push((0, 0));
+    }
+
+    // exception handlers are entered with only the exception on the stack
+    for entry in code.exception_table.iter() {
+        worklist.push((index_of(entry.handler_pc as usize)?, 1));
+    }
+
+    let mut max_stack = 0;
+    while let Some((index, depth)) = worklist.pop() {
+        match depths[index] {
+            Some(existing) if existing == depth => continue,
+            Some(existing) => {
+                return Err(io_err(format!(
+                    "Found inconsistent stack depths of {} and {} at offset {}.",
+                    existing, depth, code.code[index].0
+                )))
+            }
+            None => depths[index] = Some(depth),
+        }
+
+        let (pc, ref bytecode) = code.code[index];
+
+        let depth_after = depth + stack_delta(bytecode, class_file)?;
+        if depth_after < 0 {
+            return Err(io_err(format!(
+                "Found a stack underflow at offset {} ({:?}).",
+                pc, bytecode
+            )));
+        }
+
+        max_stack = max_stack.max(depth).max(depth_after);
+
+        if let Some(target) = bytecode.branch_target(pc) {
+            worklist.push((index_of(target)?, depth_after));
+        }
+
+        if bytecode.falls_through() {
+            // a subroutine pops its return address before returning to the
+            // instruction after the jsr
+            let fall_through_depth = match *bytecode {
+                Bytecode::Jsr(_) | Bytecode::Jsr_w(_) => depth,
+                _ => depth_after,
+            };
+
+            if index + 1 < code.code.len() {
+                worklist.push((index + 1, fall_through_depth));
+            }
+        }
+    }
+
+    Ok(max_stack as u16)
+}
+
+/// Returns the change in the depth of the operand stack caused by the
+/// instruction, in slots.
+fn stack_delta(bytecode: &Bytecode, class_file: &ClassFile) -> io::Result<i32> {
+    use Bytecode::*;
+
+    let delta = match *bytecode {
+        Nop | Goto(_) | Goto_w(_) | Ret(_) | Return | Checkcast(_) => 0,
+        Aconst_null | Iconst_0 | Iconst_1 | Iconst_2 | Fconst_0 | Fconst_1 | Fconst_2 | Ldc(_)
+        | Ldc_w(_) | Iload_1 | Aload_0 | Aload_1 | Dup | New(_) => 1,
+        Jsr(_) | Jsr_w(_) => 1,
+        Lconst_0 | Lconst_1 | Dconst_0 | Dconst_1 | Ldc2_w(_) => 2,
+        Astore_1 | Ifeq(_) | Ifne(_) | Ireturn | Freturn | Areturn | Athrow => -1,
+        Lreturn | Dreturn => -2,
+        Getstatic(index) => field_slots(class_file, index)?,
+        Putstatic(index) => -field_slots(class_file, index)?,
+        Getfield(index) => field_slots(class_file, index)? - 1,
+        Putfield(index) => -field_slots(class_file, index)? - 1,
+        Invokevirtual(index) | Invokespecial(index) | Invokeinterface(index, _) => {
+            invoke_delta(class_file, index)? - 1
+        }
+        Invokestatic(index) | Invokedynamic(index) => invoke_delta(class_file, index)?,
+        Unknown(opcode) => {
+            return Err(io_err(format!(
+                "The stack effect of the unknown opcode {} is not known.",
+                opcode
+            )))
+        }
+    };
+
+    Ok(delta)
+}
+
+/// Returns the number of stack slots taken up by the field referenced by the
+/// given constant.
+fn field_slots(class_file: &ClassFile, index: u16) -> io::Result<i32> {
+    let descriptor = member_descriptor(class_file, index)?;
+    let field_type = promote_result_to_io(FieldType::parse(descriptor))?;
+
+    Ok(i32::from(field_type.slot_size()))
+}
+
+/// Returns the change in stack depth caused by popping the arguments of the
+/// method referenced by the given constant and pushing its return value, not
+/// counting the receiver of the method.
+fn invoke_delta(class_file: &ClassFile, index: u16) -> io::Result<i32> {
+    let descriptor = member_descriptor(class_file, index)?;
+    let descriptor = promote_result_to_io(MethodDescriptor::parse(descriptor))?;
+
+    let return_slots = match descriptor.return_type {
+        ReturnType::Void => 0,
+        ReturnType::Field(ref field_type) => field_type.slot_size(),
+    };
+
+    Ok(i32::from(return_slots) - i32::from(descriptor.parameter_slots()))
+}
+
+/// Returns the descriptor of the member referenced by the given constant.
+fn member_descriptor(class_file: &ClassFile, index: u16) -> io::Result<&str> {
+    use ConstantPoolEntry::*;
+
+    let name_and_type_index = match *class_file.get_constant(index as usize) {
+        ConstantFieldref {
+            name_and_type_index,
+            ..
+        }
+        | ConstantMethodref {
+            name_and_type_index,
+            ..
+        } => name_and_type_index,
+        ConstantInterfaceMethodref {
+            name_and_type_index,
+            ..
+        }
+        | ConstantInvokeDynamic {
+            name_and_type_index,
+            ..
+        } => name_and_type_index as usize,
+        ref constant => {
+            return Err(io_err(format!(
+                "Expected constant #{} to reference a member, but found: {:?}",
+                index, constant
+            )))
+        }
+    };
+
+    match *class_file.get_constant(name_and_type_index) {
+        ConstantNameAndType {
+            descriptor_index, ..
+        } => Ok(class_file.get_constant_utf8(descriptor_index)),
+        ref constant => Err(io_err(format!(
+            "Expected constant #{} to be a NameAndType, but found: {:?}",
+            name_and_type_index, constant
+        ))),
+    }
+}
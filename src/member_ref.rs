@@ -0,0 +1,18 @@
+/// The kind of constant that references a field or method.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MemberRefKind {
+    Field,
+    Method,
+    InterfaceMethod,
+}
+
+/// A resolved Fieldref, Methodref, or InterfaceMethodref constant, consisting
+/// of the class that declares the member and the name and descriptor of the
+/// member.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct MemberRef<'a> {
+    pub kind: MemberRefKind,
+    pub class_name: &'a str,
+    pub name: &'a str,
+    pub descriptor: &'a str,
+}
@@ -1,7 +1,14 @@
+use std::collections::{BTreeSet, HashSet};
 use std::io;
 
 use attribute::*;
-use class_file::ClassFile;
+use bytecode;
+use class_file::{read_class_table, ClassFile};
+use descriptor::*;
+use lazy_code::LazyCode;
+use maxes;
+use method_access::MethodAccess;
+use util::{flag_is_set, io_err, promote_result_to_io};
 use ConstantPoolIndex;
 
 // Method flags are from Table 4.6-A of the JVM specification
@@ -20,19 +27,337 @@ pub const METHOD_ABSTRACT_FLAG: u16 = 0x0400;
 pub const METHOD_STRICT_FLAG: u16 = 0x0800;
 pub const METHOD_SYNTHETIC_FLAG: u16 = 0x1000;
 
+/// The bits of the access flags that correspond to a `MethodAccess` flag.
+const RECOGNIZED_FLAGS: u16 = METHOD_PUBLIC_FLAG
+    | METHOD_PRIVATE_FLAG
+    | METHOD_PROTECTED_FLAG
+    | METHOD_STATIC_FLAG
+    | METHOD_FINAL_FLAG
+    | METHOD_SYNCHRONIZED_FLAG
+    | METHOD_BRIDGE_FLAG
+    | METHOD_VARARGS_FLAG
+    | METHOD_NATIVE_FLAG
+    | METHOD_ABSTRACT_FLAG
+    | METHOD_STRICT_FLAG
+    | METHOD_SYNTHETIC_FLAG;
+
+/// The names and descriptors of the methods of `java.lang.Object` that are
+/// neither final nor static, and so can be overridden.
+const OBJECT_OVERRIDABLE_METHODS: [(&str, &str); 5] = [
+    ("equals", "(Ljava/lang/Object;)Z"),
+    ("hashCode", "()I"),
+    ("toString", "()Ljava/lang/String;"),
+    ("clone", "()Ljava/lang/Object;"),
+    ("finalize", "()V"),
+];
+
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct Method {
     pub access_flags: u16,
     pub name_index: ConstantPoolIndex,
     pub descriptor_index: ConstantPoolIndex,
     pub attributes: AttributeSet,
+
+    /// The offset of the method from the start of the class file that it was
+    /// parsed from, if offsets were recorded while parsing. See
+    /// `ParseOptions::record_offsets` for details.
+    pub file_offset: Option<usize>,
 }
 
 impl Method {
+    /// Returns whether the method has a body, meaning that it is neither
+    /// `abstract` nor `native`.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Interface.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// assert!(!class_file.methods[0].is_concrete());
+    /// ```
+    pub fn is_concrete(&self) -> bool {
+        !flag_is_set(METHOD_ABSTRACT_FLAG, self.access_flags)
+            && !flag_is_set(METHOD_NATIVE_FLAG, self.access_flags)
+    }
+
+    /// Returns whether the method is a bridge method generated by the
+    /// compiler.
+    pub fn is_bridge(&self) -> bool {
+        flag_is_set(METHOD_BRIDGE_FLAG, self.access_flags)
+    }
+
+    /// Returns the access flags of the method as a set.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::{ClassFile, MethodAccess};
+    /// #
+    /// let mut file = File::open("classes/HelloWorld.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let access = class_file.main_method().unwrap().access();
+    ///
+    /// assert!(access.contains(&MethodAccess::Public));
+    /// assert!(access.contains(&MethodAccess::Static));
+    /// ```
+    pub fn access(&self) -> HashSet<MethodAccess> {
+        match MethodAccess::from_access_flags(self.access_flags) {
+            Ok(access) => access,
+            Err(message) => panic!("Failed to read the method access flags. {}", message),
+        }
+    }
+
+    /// Replaces the access flags of the method with the given set of flags.
+    /// Any bits of the current access flags that do not correspond to a
+    /// `MethodAccess` flag are kept.
+    ///
+    /// Fails if the given combination of flags is not allowed by the JVM. See
+    /// `MethodAccess::validate` for details.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::{ClassFile, MethodAccess};
+    /// #
+    /// let mut file = File::open("classes/IntBox.class").unwrap();
+    /// let mut class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let get_value = &mut class_file.methods[1];
+    ///
+    /// let mut access = get_value.access();
+    /// access.insert(MethodAccess::Final);
+    /// get_value.set_access(&access).unwrap();
+    ///
+    /// assert_eq!(0x0011, get_value.access_flags);
+    /// ```
+    pub fn set_access(&mut self, access: &HashSet<MethodAccess>) -> io::Result<()> {
+        promote_result_to_io(MethodAccess::validate(access))?;
+
+        let unrecognized_flags = self.access_flags & !RECOGNIZED_FLAGS;
+        self.access_flags = MethodAccess::to_access_flags(access) | unrecognized_flags;
+
+        Ok(())
+    }
+
+    /// Returns whether the method was generated by the compiler, as marked
+    /// by either the `ACC_SYNTHETIC` flag or a `Synthetic` attribute.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Bridge.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let bridge_method = class_file.bridge_methods()[0];
+    ///
+    /// assert!(bridge_method.is_synthetic(&class_file));
+    /// ```
+    pub fn is_synthetic(&self, class_file: &ClassFile) -> bool {
+        flag_is_set(METHOD_SYNTHETIC_FLAG, self.access_flags)
+            || self
+                .attributes
+                .find_attribute(class_file, "Synthetic")
+                .is_some()
+    }
+
+    /// Returns whether the method overrides one of the methods of
+    /// `java.lang.Object` that subclasses may override (`equals`, `hashCode`,
+    /// `toString`, `clone`, and `finalize`), as matched by its name and
+    /// descriptor.
+    ///
+    /// Static and private methods never override, so they are not counted.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/Point.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let to_string = class_file.find_method("toString", "()Ljava/lang/String;").unwrap();
+    ///
+    /// assert!(to_string.is_object_override(&class_file));
+    /// ```
+    pub fn is_object_override(&self, class_file: &ClassFile) -> bool {
+        if flag_is_set(METHOD_STATIC_FLAG, self.access_flags)
+            || flag_is_set(METHOD_PRIVATE_FLAG, self.access_flags)
+        {
+            return false;
+        }
+
+        let name = class_file.get_constant_utf8(self.name_index);
+        let descriptor = class_file.get_constant_utf8(self.descriptor_index);
+
+        OBJECT_OVERRIDABLE_METHODS.contains(&(name, descriptor))
+    }
+
+    /// Returns whether the method has a `Code` attribute.
+    pub fn has_code(&self, class_file: &ClassFile) -> bool {
+        self.attributes.find_attribute(class_file, "Code").is_some()
+    }
+
+    /// Returns the body of the method, as given by its `Code` attribute.
+    ///
+    /// Abstract and native methods have no body, so a `None` option is
+    /// returned for them. Returns an error if the method is concrete but does
+    /// not have a `Code` attribute, or if its `Code` attribute is malformed.
     pub fn get_code(&self, class_file: &ClassFile) -> io::Result<Option<Code>> {
         match self.attributes.find_attribute(class_file, "Code") {
             Some(attr) => Ok(Some(Code::from_bytes(&attr.info)?)),
-            _ => Ok(None),
+            None if self.is_concrete() => Err(io_err(format!(
+                "The method \"{}\" is neither abstract nor native, but does not have a Code attribute.",
+                class_file.get_constant_utf8(self.name_index)
+            ))),
+            None => Ok(None),
         }
     }
+
+    /// Returns the body of the method, as given by its `Code` attribute,
+    /// without decoding its bytecode until it is accessed.
+    ///
+    /// If the method does not have a `Code` attribute, then a `None` option is
+    /// returned.
+    pub fn get_lazy_code(&self, class_file: &ClassFile) -> Option<LazyCode<'_>> {
+        self.attributes
+            .find_attribute(class_file, "Code")
+            .map(|attr| LazyCode::new(&attr.info))
+    }
+
+    /// Returns the names of the checked exceptions that the method declares
+    /// that it throws, as given by its `Exceptions` attribute.
+    ///
+    /// If the method does not have an `Exceptions` attribute, then an empty
+    /// list is returned.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/ExceptionThrows.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let method = class_file.find_method("declareExceptions", "()V").unwrap();
+    ///
+    /// assert_eq!(
+    ///     vec!["java/io/IOException", "java/lang/InterruptedException"],
+    ///     method.get_thrown_exceptions(&class_file)
+    /// );
+    /// ```
+    pub fn get_thrown_exceptions<'a>(&self, class_file: &'a ClassFile) -> Vec<&'a str> {
+        match self.attributes.find_attribute(class_file, "Exceptions") {
+            Some(attr) => read_class_table(class_file, attr, "Exceptions"),
+            None => vec![],
+        }
+    }
+
+    /// Returns the number of local variable slots needed to hold the
+    /// parameters of the method, including `this` for instance methods.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/IntBox.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let constructor = &class_file.methods[0];
+    ///
+    /// assert_eq!(2, constructor.required_locals(&class_file).unwrap());
+    /// ```
+    pub fn required_locals(&self, class_file: &ClassFile) -> io::Result<u16> {
+        let descriptor = class_file.get_constant_utf8(self.descriptor_index);
+        let descriptor = promote_result_to_io(MethodDescriptor::parse(descriptor))?;
+
+        let this_slots = if flag_is_set(METHOD_STATIC_FLAG, self.access_flags) {
+            0
+        } else {
+            1
+        };
+
+        Ok(descriptor.parameter_slots() + this_slots)
+    }
+
+    /// Returns the local variable slots that are used by the method, which
+    /// are the slots of its parameters (including `this`) along with the
+    /// slots used by the instructions of its code.
+    ///
+    /// The instructions are not decoded, so this supports every instruction,
+    /// including those that `get_code` cannot decode.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/HelloWorld.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let main = &class_file.methods[1];
+    ///
+    /// assert_eq!(
+    ///     vec![0],
+    ///     main.used_local_slots(&class_file).unwrap().into_iter().collect::<Vec<u16>>()
+    /// );
+    /// ```
+    pub fn used_local_slots(&self, class_file: &ClassFile) -> io::Result<BTreeSet<u16>> {
+        let mut slots: BTreeSet<u16> = (0..self.required_locals(class_file)?).collect();
+
+        if let Some(attr) = self.attributes.find_attribute(class_file, "Code") {
+            let (_, instructions) = read_code_undecoded(&attr.info)?;
+
+            slots.extend(bytecode::used_local_slots(instructions)?);
+        }
+
+        Ok(slots)
+    }
+
+    /// Computes the number of local variable slots needed by the method,
+    /// which is enough to hold its parameters and every local variable used
+    /// by its code. This is what the `max_locals` of its code should be.
+    ///
+    /// Like `used_local_slots`, this supports every instruction.
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use jvm_class_file_parser::ClassFile;
+    /// #
+    /// let mut file = File::open("classes/IntBox.class").unwrap();
+    /// let class_file = ClassFile::from_file(&mut file).unwrap();
+    ///
+    /// let constructor = &class_file.methods[0];
+    ///
+    /// assert_eq!(2, constructor.compute_max_locals(&class_file).unwrap());
+    /// ```
+    pub fn compute_max_locals(&self, class_file: &ClassFile) -> io::Result<u16> {
+        Ok(maxes::locals_needed(&self.used_local_slots(class_file)?))
+    }
+
+    /// Checks that the `max_locals` of the method's code is large enough to
+    /// hold the parameters of the method.
+    ///
+    /// Methods without code are always considered to be valid.
+    pub fn validate_max_locals(&self, class_file: &ClassFile) -> io::Result<()> {
+        if let Some(code) = self.get_code(class_file)? {
+            let required_locals = self.required_locals(class_file)?;
+
+            if code.max_locals < required_locals {
+                return Err(io_err(format!(
+                    "The method \"{}\" has a max locals of {}, but its parameters require {} locals.",
+                    class_file.get_constant_utf8(self.name_index),
+                    code.max_locals,
+                    required_locals
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the offset of the method from the start of the class file that it
+    /// was parsed from, or `None` if offsets were not recorded. See
+    /// `ParseOptions::record_offsets` for details.
+    pub fn file_offset(&self) -> Option<usize> {
+        self.file_offset
+    }
 }
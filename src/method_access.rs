@@ -0,0 +1,259 @@
+use std::collections::HashSet;
+
+use method::*;
+use util::{flag_is_set, join_source_keywords, sort_by_flag_bit};
+
+/// A flag that denotes an access level or property of a method.
+///
+/// See the `access_flags` section of Chapter 4.6 of the JVM specification for
+/// details.
+///
+/// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-4.html#jvms-4.6-200-A
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum MethodAccess {
+    Public,
+    Private,
+    Protected,
+    Static,
+    Final,
+    Synchronized,
+    Bridge,
+    Varargs,
+    Native,
+    Abstract,
+    Strict,
+    Synthetic,
+}
+
+impl MethodAccess {
+    /// Extracts the list of method access flags that are embedded in the
+    /// given access flag value.
+    ///
+    /// Returns an error message if the extracted combination of access flags
+    /// are inconsistent. (This validation has not yet been implemented)
+    ///
+    /// See Table 4.6-A of the JVM specification for more details.
+    ///
+    /// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-4.html#jvms-4.6-200-A.1
+    ///
+    /// ```
+    /// # use std::collections::HashSet;
+    /// # use jvm_class_file_parser::MethodAccess;
+    /// #
+    /// let access_flags = 0b0000_0000_0000_1001;
+    ///
+    /// let mut expected = HashSet::new();
+    /// expected.insert(MethodAccess::Public);
+    /// expected.insert(MethodAccess::Static);
+    ///
+    /// assert_eq!(Ok(expected), MethodAccess::from_access_flags(access_flags));
+    /// ```
+    pub fn from_access_flags(access_flags: u16) -> Result<HashSet<MethodAccess>, String> {
+        use MethodAccess::*;
+
+        let mut access = HashSet::new();
+
+        let is_public = flag_is_set(METHOD_PUBLIC_FLAG, access_flags);
+        let is_private = flag_is_set(METHOD_PRIVATE_FLAG, access_flags);
+        let is_protected = flag_is_set(METHOD_PROTECTED_FLAG, access_flags);
+        let is_static = flag_is_set(METHOD_STATIC_FLAG, access_flags);
+        let is_final = flag_is_set(METHOD_FINAL_FLAG, access_flags);
+        let is_synchronized = flag_is_set(METHOD_SYNCHRONIZED_FLAG, access_flags);
+        let is_bridge = flag_is_set(METHOD_BRIDGE_FLAG, access_flags);
+        let is_varargs = flag_is_set(METHOD_VARARGS_FLAG, access_flags);
+        let is_native = flag_is_set(METHOD_NATIVE_FLAG, access_flags);
+        let is_abstract = flag_is_set(METHOD_ABSTRACT_FLAG, access_flags);
+        let is_strict = flag_is_set(METHOD_STRICT_FLAG, access_flags);
+        let is_synthetic = flag_is_set(METHOD_SYNTHETIC_FLAG, access_flags);
+
+        // TODO: Add validation for inconsistent access flags
+
+        if is_public {
+            access.insert(Public);
+        }
+        if is_private {
+            access.insert(Private);
+        }
+        if is_protected {
+            access.insert(Protected);
+        }
+        if is_static {
+            access.insert(Static);
+        }
+        if is_final {
+            access.insert(Final);
+        }
+        if is_synchronized {
+            access.insert(Synchronized);
+        }
+        if is_bridge {
+            access.insert(Bridge);
+        }
+        if is_varargs {
+            access.insert(Varargs);
+        }
+        if is_native {
+            access.insert(Native);
+        }
+        if is_abstract {
+            access.insert(Abstract);
+        }
+        if is_strict {
+            access.insert(Strict);
+        }
+        if is_synthetic {
+            access.insert(Synthetic);
+        }
+
+        Ok(access)
+    }
+
+    /// Converts the given set of method access flags into the access flag
+    /// value that represents them. This is the inverse of `from_access_flags`.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::MethodAccess;
+    /// #
+    /// let access_flags = 0b0000_0000_0011_0001;
+    ///
+    /// let access = MethodAccess::from_access_flags(access_flags).unwrap();
+    ///
+    /// assert_eq!(access_flags, MethodAccess::to_access_flags(&access));
+    /// ```
+    #[allow(clippy::wrong_self_convention)]
+    pub fn to_access_flags(access_flags: &HashSet<MethodAccess>) -> u16 {
+        access_flags
+            .iter()
+            .fold(0, |flags, flag| flags | flag.access_flag())
+    }
+
+    /// Checks that the given combination of method access flags is one that
+    /// the JVM allows.
+    ///
+    /// A method may have at most one of `public`, `private`, and `protected`,
+    /// and an `abstract` method may not also be `private`, `static`, `final`,
+    /// `synchronized`, `native`, or `strictfp`.
+    ///
+    /// See Chapter 4.6 of the JVM specification for details.
+    ///
+    /// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-4.html#jvms-4.6
+    ///
+    /// ```
+    /// # use std::collections::HashSet;
+    /// # use jvm_class_file_parser::MethodAccess;
+    /// #
+    /// let access = MethodAccess::from_access_flags(0x0401).unwrap();
+    /// assert!(MethodAccess::validate(&access).is_ok());
+    ///
+    /// let access = MethodAccess::from_access_flags(0x0411).unwrap();
+    /// assert!(MethodAccess::validate(&access).is_err());
+    /// ```
+    pub fn validate(access_flags: &HashSet<MethodAccess>) -> Result<(), String> {
+        use MethodAccess::*;
+
+        let visibilities: Vec<&MethodAccess> = [Public, Private, Protected]
+            .iter()
+            .filter(|flag| access_flags.contains(flag))
+            .collect();
+        if visibilities.len() > 1 {
+            return Err(format!(
+                "A method can have at most one of public, private, and protected, but found: {:?}",
+                visibilities
+            ));
+        }
+
+        if access_flags.contains(&Abstract) {
+            let conflicting: Vec<&MethodAccess> =
+                [Private, Static, Final, Synchronized, Native, Strict]
+                    .iter()
+                    .filter(|flag| access_flags.contains(flag))
+                    .collect();
+
+            if !conflicting.is_empty() {
+                return Err(format!(
+                    "An abstract method cannot also have the flags: {:?}",
+                    conflicting
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the bit of the access flags that corresponds to the flag.
+    fn access_flag(&self) -> u16 {
+        use MethodAccess::*;
+
+        match self {
+            Public => METHOD_PUBLIC_FLAG,
+            Private => METHOD_PRIVATE_FLAG,
+            Protected => METHOD_PROTECTED_FLAG,
+            Static => METHOD_STATIC_FLAG,
+            Final => METHOD_FINAL_FLAG,
+            Synchronized => METHOD_SYNCHRONIZED_FLAG,
+            Bridge => METHOD_BRIDGE_FLAG,
+            Varargs => METHOD_VARARGS_FLAG,
+            Native => METHOD_NATIVE_FLAG,
+            Abstract => METHOD_ABSTRACT_FLAG,
+            Strict => METHOD_STRICT_FLAG,
+            Synthetic => METHOD_SYNTHETIC_FLAG,
+        }
+    }
+
+    /// Returns the given flags in the canonical order used by javap.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::MethodAccess;
+    /// #
+    /// let access_flags = MethodAccess::from_access_flags(0x0019).unwrap();
+    ///
+    /// assert_eq!(
+    ///     vec![MethodAccess::Public, MethodAccess::Static, MethodAccess::Final],
+    ///     MethodAccess::canonical_order(&access_flags)
+    /// );
+    /// ```
+    pub fn canonical_order(access_flags: &HashSet<MethodAccess>) -> Vec<MethodAccess> {
+        sort_by_flag_bit(access_flags, MethodAccess::access_flag)
+    }
+
+    /// Returns the Java source keyword that corresponds to the flag, or
+    /// `None` if the flag has no source form.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::MethodAccess;
+    /// #
+    /// assert_eq!(Some("synchronized"), MethodAccess::Synchronized.source_keyword());
+    /// assert_eq!(None, MethodAccess::Bridge.source_keyword());
+    /// ```
+    pub fn source_keyword(&self) -> Option<&'static str> {
+        use MethodAccess::*;
+
+        match self {
+            Public => Some("public"),
+            Private => Some("private"),
+            Protected => Some("protected"),
+            Static => Some("static"),
+            Final => Some("final"),
+            Synchronized => Some("synchronized"),
+            Native => Some("native"),
+            Abstract => Some("abstract"),
+            Strict => Some("strictfp"),
+            Bridge | Varargs | Synthetic => None,
+        }
+    }
+
+    /// Returns the source keywords of the given flags joined in the canonical
+    /// Java modifier order.
+    ///
+    /// ```
+    /// # use std::collections::HashSet;
+    /// # use jvm_class_file_parser::MethodAccess;
+    /// #
+    /// let access_flags = MethodAccess::from_access_flags(0x1009).unwrap();
+    ///
+    /// assert_eq!("public static", MethodAccess::source_modifiers(&access_flags));
+    /// ```
+    pub fn source_modifiers(access_flags: &HashSet<MethodAccess>) -> String {
+        join_source_keywords(access_flags.iter().filter_map(MethodAccess::source_keyword))
+    }
+}
@@ -0,0 +1,80 @@
+/// The kind of a method handle, which denotes the bytecode behavior of the
+/// handle.
+///
+/// See Table 5.4.3.5-A of the JVM specification for details.
+///
+/// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-5.html#jvms-5.4.3.5-220
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ReferenceKind {
+    GetField,
+    GetStatic,
+    PutField,
+    PutStatic,
+    InvokeVirtual,
+    InvokeStatic,
+    InvokeSpecial,
+    NewInvokeSpecial,
+    InvokeInterface,
+}
+
+impl ReferenceKind {
+    /// Returns the reference kind that corresponds to the given
+    /// `reference_kind` value of a MethodHandle constant.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::ReferenceKind;
+    /// #
+    /// assert_eq!(Some(ReferenceKind::InvokeStatic), ReferenceKind::from_u8(6));
+    /// assert_eq!(None, ReferenceKind::from_u8(10));
+    /// ```
+    pub fn from_u8(reference_kind: u8) -> Option<ReferenceKind> {
+        use ReferenceKind::*;
+
+        match reference_kind {
+            1 => Some(GetField),
+            2 => Some(GetStatic),
+            3 => Some(PutField),
+            4 => Some(PutStatic),
+            5 => Some(InvokeVirtual),
+            6 => Some(InvokeStatic),
+            7 => Some(InvokeSpecial),
+            8 => Some(NewInvokeSpecial),
+            9 => Some(InvokeInterface),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the reference kind, as used in the JVM
+    /// specification.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::ReferenceKind;
+    /// #
+    /// assert_eq!("invokeStatic", ReferenceKind::InvokeStatic.label());
+    /// ```
+    pub fn label(&self) -> &'static str {
+        use ReferenceKind::*;
+
+        match self {
+            GetField => "getField",
+            GetStatic => "getStatic",
+            PutField => "putField",
+            PutStatic => "putStatic",
+            InvokeVirtual => "invokeVirtual",
+            InvokeStatic => "invokeStatic",
+            InvokeSpecial => "invokeSpecial",
+            NewInvokeSpecial => "newInvokeSpecial",
+            InvokeInterface => "invokeInterface",
+        }
+    }
+}
+
+/// A resolved MethodHandle constant, consisting of the kind of the handle and
+/// the field or method that it references.
+#[derive(Debug, Eq, PartialEq)]
+pub struct MethodHandleInfo<'a> {
+    pub reference_kind: ReferenceKind,
+    pub class_name: &'a str,
+    pub name: &'a str,
+    pub descriptor: &'a str,
+}
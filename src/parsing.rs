@@ -1,16 +1,17 @@
 use std::io;
-use std::io::{Error, ErrorKind, Read};
-use std::str;
+use std::io::{ErrorKind, Read};
 
 use attribute::*;
 use class_access::*;
 use class_file::ClassFile;
 use constant_pool::*;
+use error::ParseError;
 use field::*;
 use field_access::*;
 use method::*;
 use std::ops::Deref;
-use util::{promote_result_to_io, Contextable, FloatBuffer};
+use util::{io_err, promote_result_to_io, Contextable, FloatBuffer, PositionedReader};
+use ConstantPoolIndex;
 
 const EXPECTED_MAGIC: u32 = 0xCAFE_BABE;
 
@@ -32,6 +33,13 @@ const CONSTANT_INVOKE_DYNAMIC: u8 = 18;
 const CONSTANT_MODULE: u8 = 19;
 const CONSTANT_PACKAGE: u8 = 20;
 
+/// The length of the access flags, name index, and descriptor index that start
+/// each field and method.
+const MEMBER_HEADER_LENGTH: usize = 6;
+
+/// The length of the name index and length that start each attribute.
+const ATTRIBUTE_HEADER_LENGTH: usize = 6;
+
 const READ_MINOR_VERSION: &str = "Failed to read minor version.";
 const READ_MAJOR_VERSION: &str = "Failed to read major version.";
 const READ_CONSTANT_POOL: &str = "Failed to read constant pool.";
@@ -43,29 +51,65 @@ const READ_FIELDS: &str = "Failed to read fields.";
 const READ_METHODS: &str = "Failed to read methods.";
 const READ_ATTRIBUTES: &str = "Failed to read attributes.";
 
+/// Options that control how `ClassFile::from_file_with_options` parses a
+/// class file.
+///
+/// ```
+/// # use jvm_class_file_parser::ParseOptions;
+/// #
+/// let options = ParseOptions::default();
+///
+/// assert!(!options.structured_attributes);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ParseOptions {
+    /// Whether to decode the attributes of known kinds into their structured
+    /// forms. See `ClassFile::decode_attributes` for details.
+    pub structured_attributes: bool,
+    /// Whether to record the offset of each field, method, and attribute of
+    /// the class from the start of the class file. This is off by default,
+    /// since most uses of the parsed class do not need the offsets.
+    ///
+    /// Only the attributes of the class itself and of its fields and methods
+    /// are given offsets, not those nested inside of other attributes.
+    pub record_offsets: bool,
+}
+
 pub fn read_class_file<R: Read>(file: &mut R) -> io::Result<ClassFile> {
-    let magic = read_u32(file)?;
+    read_class_file_with_options(file, &ParseOptions::default())
+}
 
-    if magic != EXPECTED_MAGIC {
-        let error_msg = format!("The given file does not appear to be a valid JVM class file. JVM class files must start with the magic bytes \"CAFEBABE\", but this file started with \"{:x}\"", magic);
+/// Reads a class file, recording the offsets of its parts if the given options
+/// ask for it. The attributes are not decoded, regardless of the options.
+pub fn read_class_file_with_options<R: Read>(
+    file: &mut R,
+    options: &ParseOptions,
+) -> io::Result<ClassFile> {
+    let file = &mut PositionedReader::new(file);
+    let record_offsets = options.record_offsets;
 
-        return Err(Error::new(ErrorKind::Other, error_msg));
-    }
+    read_magic(file)?;
+
+    let minor_version = read_u16(file).context(file.at(READ_MINOR_VERSION))?;
+    let major_version = read_u16(file).context(file.at(READ_MAJOR_VERSION))?;
 
-    let minor_version = read_u16(file).context(READ_MINOR_VERSION)?;
-    let major_version = read_u16(file).context(READ_MAJOR_VERSION)?;
+    let constant_pool = read_constant_pool(file).context(file.at(READ_CONSTANT_POOL))?;
 
-    let constant_pool = read_constant_pool(file).context(READ_CONSTANT_POOL)?;
+    let access_flags = read_u16(file).context(file.at(READ_ACCESS_FLAGS))?;
+    let this_class = read_cp_index(file).context(file.at(READ_THIS_CLASS))?;
+    let super_class = read_cp_index(file).context(file.at(READ_SUPER_CLASS))?;
 
-    let access_flags = read_u16(file).context(READ_ACCESS_FLAGS)?;
-    let this_class = read_cp_index(file).context(READ_THIS_CLASS)?;
-    let super_class = read_cp_index(file).context(READ_SUPER_CLASS)?;
+    let interfaces = read_interfaces(file).context(file.at(READ_INTERFACES))?;
+    let fields = read_fields(file, record_offsets).context(file.at(READ_FIELDS))?;
+    let methods = read_methods(file, record_offsets).context(file.at(READ_METHODS))?;
 
-    let interfaces = read_interfaces(file).context(READ_INTERFACES)?;
-    let fields = read_fields(file).context(READ_FIELDS)?;
-    let methods = read_methods(file).context(READ_METHODS)?;
-    let attributes = read_attributes(file).context(READ_ATTRIBUTES)?;
+    let attributes_offset = file.position();
+    let mut attributes = read_attributes(file).context(file.at(READ_ATTRIBUTES))?;
+    if record_offsets {
+        record_attribute_offsets(&mut attributes, attributes_offset);
+    }
 
+    let unrecognized_access_flags = ClassAccess::unrecognized_flags(access_flags);
     let access_flags = promote_result_to_io(ClassAccess::from_access_flags(access_flags))?;
 
     Ok(ClassFile {
@@ -73,6 +117,7 @@ pub fn read_class_file<R: Read>(file: &mut R) -> io::Result<ClassFile> {
         major_version,
         constant_pool,
         access_flags,
+        unrecognized_access_flags,
         this_class,
         super_class,
         interfaces,
@@ -82,7 +127,83 @@ pub fn read_class_file<R: Read>(file: &mut R) -> io::Result<ClassFile> {
     })
 }
 
-fn read_u8<R: Read>(file: &mut R) -> io::Result<u8> {
+/// Reads a class file only as far as is needed to find the name of the class,
+/// which is its header, constant pool, access flags, and `this_class`.
+pub fn read_class_name<R: Read>(file: &mut R) -> io::Result<String> {
+    use ConstantPoolEntry::*;
+
+    let file = &mut PositionedReader::new(file);
+
+    read_magic(file)?;
+
+    read_u16(file).context(file.at(READ_MINOR_VERSION))?;
+    read_u16(file).context(file.at(READ_MAJOR_VERSION))?;
+
+    let constant_pool = read_constant_pool(file).context(file.at(READ_CONSTANT_POOL))?;
+
+    read_u16(file).context(file.at(READ_ACCESS_FLAGS))?;
+    let this_class = read_cp_index(file).context(file.at(READ_THIS_CLASS))?;
+
+    let constant =
+        |index: ConstantPoolIndex| index.checked_sub(1).and_then(|i| constant_pool.get(i));
+
+    let name_index = match constant(this_class) {
+        Some(ConstantClass { name_index }) => *name_index,
+        other => {
+            return Err(io_err(format!(
+                "The \"this_class\" index {} does not point to a ConstantClass. Found: {:?}",
+                this_class, other
+            )))
+        }
+    };
+
+    match constant(name_index) {
+        Some(ConstantUtf8 { string }) => Ok(string.clone()),
+        other => Err(io_err(format!(
+            "The name of the class at index {} is not a ConstantUtf8. Found: {:?}",
+            name_index, other
+        ))),
+    }
+}
+
+pub fn read_class_files<R: Read>(file: &mut R) -> io::Result<Vec<ClassFile>> {
+    let mut class_files = Vec::new();
+
+    loop {
+        // peek a single byte so that a clean EOF between classes can be told
+        // apart from a truncated class
+        let mut first_byte = [0; 1];
+        let bytes_read = match file.read(&mut first_byte) {
+            Ok(n) => n,
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+
+        if bytes_read == 0 {
+            break;
+        }
+
+        let mut rest = (&first_byte[..]).chain(&mut *file);
+        let class_file = read_class_file(&mut rest)
+            .context(format!("Failed to read class #{}.", class_files.len() + 1))?;
+
+        class_files.push(class_file);
+    }
+
+    Ok(class_files)
+}
+
+fn read_magic<R: Read>(file: &mut R) -> io::Result<()> {
+    let magic = read_u32(file)?;
+
+    if magic != EXPECTED_MAGIC {
+        return Err(ParseError::InvalidMagic(magic).into());
+    }
+
+    Ok(())
+}
+
+pub fn read_u8<R: Read>(file: &mut R) -> io::Result<u8> {
     let mut buffer = [0; 1];
 
     file.read_exact(&mut buffer)?;
@@ -90,7 +211,7 @@ fn read_u8<R: Read>(file: &mut R) -> io::Result<u8> {
     Ok(u8::from_be_bytes(buffer))
 }
 
-fn read_u16<R: Read>(file: &mut R) -> io::Result<u16> {
+pub fn read_u16<R: Read>(file: &mut R) -> io::Result<u16> {
     let mut buffer = [0; 2];
 
     file.read_exact(&mut buffer)?;
@@ -98,11 +219,11 @@ fn read_u16<R: Read>(file: &mut R) -> io::Result<u16> {
     Ok(u16::from_be_bytes(buffer))
 }
 
-fn read_cp_index<R: Read>(file: &mut R) -> io::Result<ConstantPoolIndex> {
+pub fn read_cp_index<R: Read>(file: &mut R) -> io::Result<ConstantPoolIndex> {
     read_u16(file).map(ConstantPoolIndex::from)
 }
 
-fn read_u32<R: Read>(file: &mut R) -> io::Result<u32> {
+pub fn read_u32<R: Read>(file: &mut R) -> io::Result<u32> {
     let mut buffer = [0; 4];
 
     file.read_exact(&mut buffer)?;
@@ -110,7 +231,7 @@ fn read_u32<R: Read>(file: &mut R) -> io::Result<u32> {
     Ok(u32::from_be_bytes(buffer))
 }
 
-fn read_n_bytes<R: Read>(file: &mut R, length: usize) -> io::Result<Vec<u8>> {
+pub fn read_n_bytes<R: Read>(file: &mut R, length: usize) -> io::Result<Vec<u8>> {
     let mut bytes = vec![0u8; length as usize];
 
     file.read_exact(&mut bytes)?;
@@ -120,7 +241,15 @@ fn read_n_bytes<R: Read>(file: &mut R, length: usize) -> io::Result<Vec<u8>> {
 
 #[allow(clippy::vec_box)]
 fn read_constant_pool<R: Read>(file: &mut R) -> io::Result<Vec<ConstantPoolEntry>> {
-    let constant_pool_count = read_u16(file)? - 1;
+    // the constant_pool_count is one more than the number of entries
+    let constant_pool_count = match read_u16(file)?.checked_sub(1) {
+        Some(count) => count,
+        None => {
+            return Err(io_err(
+                "The constant pool count must be at least 1, but was 0.".to_string(),
+            ))
+        }
+    };
 
     let mut constant_pool = Vec::<ConstantPoolEntry>::with_capacity(constant_pool_count as usize);
 
@@ -149,6 +278,15 @@ fn read_constant_pool<R: Read>(file: &mut R) -> io::Result<Vec<ConstantPoolEntry
         idx = idx + 1
     }
 
+    // a long or double in the last slot takes up one more entry than the pool has
+    if constant_pool.len() != constant_pool_count as usize {
+        return Err(io_err(format!(
+            "The constant pool count declares {} entries, but {} entries were read. The last entry may be a long or double constant that does not have room for its second slot.",
+            constant_pool_count,
+            constant_pool.len()
+        )));
+    }
+
     Ok(constant_pool)
 }
 
@@ -182,39 +320,70 @@ fn read_constant_pool_entry<R: Read>(file: &mut R) -> io::Result<ConstantPoolEnt
     Ok(entry)
 }
 
-fn read_constant_utf8<R: Read>(file: &mut R) -> io::Result<ConstantPoolEntry> {
+pub(crate) fn read_constant_utf8<R: Read>(file: &mut R) -> io::Result<ConstantPoolEntry> {
     let length = read_u16(file)?;
 
     let bytes = read_n_bytes(file, length as usize)?;
 
-    // try str::from_utf8 which handles the happy path efficiently and then fall back to handling NULLs as needed
+    // try String::from_utf8 which handles the happy path efficiently by reusing the buffer, and
+    // then fall back to handling NULLs and supplementary characters as needed
     // https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-4.html#jvms-4.4.7
-    match str::from_utf8(&bytes) {
-        Ok(parsed) => Ok(ConstantPoolEntry::ConstantUtf8 {
-            string: parsed.to_string(),
-        }),
-        _ => {
-            let mut new_bytes = Vec::with_capacity(bytes.len());
-
-            // go through the bytes and when we find an encoded null (2 bytes), replace it with the single byte null
-            let mut iter = bytes.iter().peekable();
-            while let Some(&b) = iter.next() {
-                if b == 0xc0 && iter.peek() == Some(&&(0x80 as u8)) {
-                    new_bytes.push(0);
-                    iter.next();
-                } else {
-                    new_bytes.push(b);
-                }
-            }
+    let mut bytes = match String::from_utf8(bytes) {
+        Ok(string) => return Ok(ConstantPoolEntry::ConstantUtf8 { string }),
+        Err(e) => e.into_bytes(),
+    };
 
-            // try parsing again and return the Err if it fails
-            str::from_utf8(&new_bytes)
-                .map(|string| ConstantPoolEntry::ConstantUtf8 {
-                    string: string.to_string(),
-                })
-                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    // go through the bytes and when we find an encoded null (2 bytes), replace it with the single
+    // byte null. Each replacement is shorter than what it replaces, so this can be done in place
+    let mut read = 0;
+    let mut write = 0;
+    while read < bytes.len() {
+        if bytes[read] == 0xc0 && bytes.get(read + 1) == Some(&0x80) {
+            bytes[write] = 0;
+            write += 1;
+            read += 2;
+        } else if let Some(c) = decode_surrogate_pair(&bytes[read..]) {
+            // supplementary characters are encoded as a surrogate pair of 3 byte sequences
+            let mut buffer = [0; 4];
+            let encoded = c.encode_utf8(&mut buffer).as_bytes();
+            bytes[write..write + encoded.len()].copy_from_slice(encoded);
+            write += encoded.len();
+            read += 6;
+        } else {
+            bytes[write] = bytes[read];
+            write += 1;
+            read += 1;
         }
     }
+    bytes.truncate(write);
+
+    // try parsing again and return the Err if it fails
+    String::from_utf8(bytes)
+        .map(|string| ConstantPoolEntry::ConstantUtf8 { string })
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Decodes the supplementary character encoded as a surrogate pair at the
+/// start of the given modified UTF-8 bytes, if there is one.
+fn decode_surrogate_pair(bytes: &[u8]) -> Option<char> {
+    if bytes.len() < 6 {
+        return None;
+    }
+
+    let is_high_surrogate = bytes[0] == 0xed && (bytes[1] & 0xf0) == 0xa0;
+    let is_low_surrogate = bytes[3] == 0xed && (bytes[4] & 0xf0) == 0xb0;
+    let is_continued = bytes[2] & 0xc0 == 0x80 && bytes[5] & 0xc0 == 0x80;
+
+    if !(is_high_surrogate && is_low_surrogate && is_continued) {
+        return None;
+    }
+
+    let high = 0xd000 | (u16::from(bytes[1] & 0x3f) << 6) | u16::from(bytes[2] & 0x3f);
+    let low = 0xd000 | (u16::from(bytes[4] & 0x3f) << 6) | u16::from(bytes[5] & 0x3f);
+
+    std::char::decode_utf16([high, low].iter().cloned())
+        .next()
+        .and_then(|c| c.ok())
 }
 
 fn read_constant_integer<R: Read>(file: &mut R) -> io::Result<ConstantPoolEntry> {
@@ -319,13 +488,22 @@ fn read_interfaces<R: Read>(file: &mut R) -> io::Result<Vec<ConstantPoolIndex>>
     Ok(interfaces)
 }
 
-fn read_fields<R: Read>(file: &mut R) -> io::Result<Vec<Field>> {
+fn read_fields<R: Read>(
+    file: &mut PositionedReader<R>,
+    record_offsets: bool,
+) -> io::Result<Vec<Field>> {
     let fields_count = i32::from(read_u16(file)?);
 
     let mut fields = Vec::<Field>::new();
 
     for _ in 0..fields_count {
-        let entry = read_field(file)?;
+        let offset = file.position();
+        let mut entry = read_field(file)?;
+
+        if record_offsets {
+            entry.file_offset = Some(offset);
+            record_attribute_offsets(&mut entry.attributes, offset + MEMBER_HEADER_LENGTH);
+        }
 
         fields.push(entry);
     }
@@ -347,16 +525,26 @@ fn read_field<R: Read>(file: &mut R) -> io::Result<Field> {
         name_index,
         descriptor_index,
         attributes,
+        file_offset: None,
     })
 }
 
-fn read_methods<R: Read>(file: &mut R) -> io::Result<Vec<Method>> {
+fn read_methods<R: Read>(
+    file: &mut PositionedReader<R>,
+    record_offsets: bool,
+) -> io::Result<Vec<Method>> {
     let methods_count = i32::from(read_u16(file)?);
 
     let mut methods = Vec::<Method>::new();
 
     for _ in 0..methods_count {
-        let entry = read_method(file)?;
+        let offset = file.position();
+        let mut entry = read_method(file)?;
+
+        if record_offsets {
+            entry.file_offset = Some(offset);
+            record_attribute_offsets(&mut entry.attributes, offset + MEMBER_HEADER_LENGTH);
+        }
 
         methods.push(entry);
     }
@@ -376,9 +564,26 @@ fn read_method<R: Read>(file: &mut R) -> io::Result<Method> {
         name_index,
         descriptor_index,
         attributes,
+        file_offset: None,
     })
 }
 
+/// Sets the offsets of the given attributes, which were read as an attribute
+/// table starting at the given offset.
+///
+/// The attributes are laid out one after another following the attribute
+/// count, so their offsets follow from the lengths of their info bytes.
+fn record_attribute_offsets(attributes: &mut AttributeSet, table_offset: usize) {
+    // skip over the attributes count
+    let mut offset = table_offset + 2;
+
+    for attr in attributes.attributes.iter_mut() {
+        attr.file_offset = Some(offset);
+
+        offset += ATTRIBUTE_HEADER_LENGTH + attr.info.len();
+    }
+}
+
 pub fn read_attributes<R: Read>(file: &mut R) -> io::Result<AttributeSet> {
     let attributes_count = read_u16(file)?;
 
@@ -393,15 +598,68 @@ pub fn read_attributes<R: Read>(file: &mut R) -> io::Result<AttributeSet> {
     Ok(AttributeSet { attributes })
 }
 
+/// Reads an attribute table that is nested inside of a structure of a known
+/// length (ex. the attributes of a Code attribute), given the number of bytes
+/// of the structure that remain.
+///
+/// Fails if the attributes would take up more than the remaining bytes, such
+/// as when the attribute count is too high, rather than reading past the end
+/// of the enclosing structure.
+pub fn read_nested_attributes<R: Read>(file: &mut R, budget: usize) -> io::Result<AttributeSet> {
+    let attributes_count = read_u16(file)?;
+    let mut remaining = budget.saturating_sub(2);
+
+    let mut attributes = Vec::<Attribute>::new();
+
+    for i in 0..attributes_count {
+        if remaining < ATTRIBUTE_HEADER_LENGTH {
+            return Err(io_err(format!(
+                "The attribute count of {} is too large, since only {} bytes remain for attribute {}.",
+                attributes_count, remaining, i
+            )));
+        }
+
+        let attribute_name_index = read_cp_index(file)?;
+        let attribute_length = read_u32(file)? as usize;
+        remaining -= ATTRIBUTE_HEADER_LENGTH;
+
+        if attribute_length > remaining {
+            return Err(io_err(format!(
+                "The declared length of {} of attribute {} exceeds the {} remaining bytes.",
+                attribute_length, i, remaining
+            )));
+        }
+        remaining -= attribute_length;
+
+        attributes.push(read_attribute_info(
+            file,
+            attribute_name_index,
+            attribute_length,
+        )?);
+    }
+
+    Ok(AttributeSet { attributes })
+}
+
 fn read_attribute<R: Read>(file: &mut R) -> io::Result<Attribute> {
     let attribute_name_index = read_cp_index(file)?;
     let attribute_length = read_u32(file)?;
 
-    let info = read_n_bytes(file, attribute_length as usize)?;
+    read_attribute_info(file, attribute_name_index, attribute_length as usize)
+}
+
+fn read_attribute_info<R: Read>(
+    file: &mut R,
+    attribute_name_index: ConstantPoolIndex,
+    attribute_length: usize,
+) -> io::Result<Attribute> {
+    let info = read_n_bytes(file, attribute_length)?;
 
     Ok(Attribute {
         attribute_name_index,
         info,
+        structured: None,
+        file_offset: None,
     })
 }
 
@@ -457,7 +715,7 @@ fn read_package<R: Read>(file: &mut R) -> io::Result<ConstantPoolEntry> {
 mod tests {
     use std::io::{self, Cursor};
 
-    use super::read_constant_utf8;
+    use super::{read_constant_pool, read_constant_utf8};
 
     #[test]
     fn read_utf8_with_embedded_null() -> io::Result<()> {
@@ -479,4 +737,64 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn read_utf8_with_null_and_supplementary_character() -> io::Result<()> {
+        let bytes = vec![
+            0, 10, // length
+            0xc0, 0x80, // 2-byte encoded null
+            0xed, 0xa0, 0xbd, 0xed, 0xb8, 0x80, // surrogate pair encoded U+1F600
+            0x41, 0x42,
+        ];
+        let parsed = read_constant_utf8(&mut &bytes[..])?;
+        assert_eq!(
+            parsed,
+            crate::ConstantPoolEntry::ConstantUtf8 {
+                string: "\0\u{1F600}AB".to_string()
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn read_constant_pool_with_long_in_last_slot() {
+        let bytes = vec![
+            0, 2, // constant pool count, which leaves room for a single slot
+            5, // long tag
+            0, 0, 0, 0, 0, 0, 0, 1, // long value
+        ];
+
+        let error = read_constant_pool(&mut &bytes[..]).unwrap_err();
+
+        assert!(
+            error
+                .to_string()
+                .contains("declares 1 entries, but 2 entries were read"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn read_constant_pool_with_zero_count() {
+        let bytes = [0, 0];
+
+        assert!(read_constant_pool(&mut &bytes[..]).is_err());
+    }
+
+    #[test]
+    fn read_constant_pool_with_long_before_last_slot() -> io::Result<()> {
+        let bytes = vec![
+            0, 4, // constant pool count
+            5, // long tag
+            0, 0, 0, 0, 0, 0, 0, 1, // long value
+            3, // integer tag
+            0, 0, 0, 2, // integer value
+        ];
+
+        let constant_pool = read_constant_pool(&mut &bytes[..])?;
+
+        assert_eq!(3, constant_pool.len());
+        Ok(())
+    }
 }
@@ -0,0 +1,59 @@
+use std::io;
+
+use attribute::*;
+use class_file::ClassFile;
+use parsing;
+use util::Contextable;
+use ConstantPoolIndex;
+
+/// A component of a record class, as given by the `Record` attribute.
+///
+/// See Chapter 4.7.30 of the JVM specification for details.
+///
+/// https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7.30
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct RecordComponent {
+    pub name_index: ConstantPoolIndex,
+    pub descriptor_index: ConstantPoolIndex,
+    pub attributes: AttributeSet,
+}
+
+impl RecordComponent {
+    /// Parses the given `Record` attribute info bytes into the components
+    /// that they describe.
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Vec<RecordComponent>> {
+        let mut reader = bytes;
+
+        let components_count =
+            parsing::read_u16(&mut reader).context("Failed to read record components count.")?;
+
+        let mut components = Vec::with_capacity(components_count as usize);
+        for _ in 0..components_count {
+            let name_index = parsing::read_cp_index(&mut reader)
+                .context("Failed to read record component name.")?;
+            let descriptor_index = parsing::read_cp_index(&mut reader)
+                .context("Failed to read record component descriptor.")?;
+            let budget = reader.len();
+            let attributes = parsing::read_nested_attributes(&mut reader, budget)
+                .context("Failed to read record component attributes.")?;
+
+            components.push(RecordComponent {
+                name_index,
+                descriptor_index,
+                attributes,
+            });
+        }
+
+        Ok(components)
+    }
+
+    /// Returns the name of the record component.
+    pub fn get_name<'a>(&self, class_file: &'a ClassFile) -> &'a str {
+        class_file.get_constant_utf8(self.name_index)
+    }
+
+    /// Returns the descriptor of the record component.
+    pub fn get_descriptor<'a>(&self, class_file: &'a ClassFile) -> &'a str {
+        class_file.get_constant_utf8(self.descriptor_index)
+    }
+}
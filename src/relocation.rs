@@ -0,0 +1,380 @@
+use std::collections::{HashMap, HashSet};
+
+use attribute::{read_code_undecoded, Attribute, AttributeSet};
+use class_file::ClassFile;
+use compaction::remap_attribute_contents;
+use constant_pool::*;
+use constant_references::AttributeOwner;
+use descriptor::map_class_names;
+
+/// A part of a class file that references a Utf8 constant holding a class
+/// name, descriptor, or signature.
+#[derive(Clone, Copy, Debug)]
+enum TypeReference {
+    ClassName(ConstantPoolIndex),
+    NameAndTypeDescriptor(ConstantPoolIndex),
+    MethodTypeDescriptor(ConstantPoolIndex),
+    FieldDescriptor(usize),
+    MethodDescriptor(usize),
+    /// A `Signature` attribute, given by its owner and its index among the
+    /// attributes of its owner.
+    Signature(AttributeOwner, usize),
+    /// The descriptor or signature of an entry of a `LocalVariableTable` or
+    /// `LocalVariableTypeTable` attribute, given by the index of its method,
+    /// the index of the Code attribute among the attributes of the method,
+    /// and the offset of the constant pool index within the Code attribute.
+    LocalVariable(usize, usize, usize),
+}
+
+/// Replaces the given prefix of each class name in the class names,
+/// descriptors, and signatures of the given class file with the other given
+/// prefix.
+///
+/// Each Utf8 constant that needs to be rewritten is updated in place, unless
+/// it is also used for something other than a type (ex. a string literal), in
+/// which case the new value is added as a new constant instead.
+pub fn relocate(class_file: &mut ClassFile, from_prefix: &str, to_prefix: &str) {
+    rename_classes(class_file, |name| match name.strip_prefix(from_prefix) {
+        Some(rest) => format!("{}{}", to_prefix, rest),
+        None => name.to_string(),
+    })
+}
+
+/// Replaces each class name in the class names, descriptors, and signatures
+/// of the given class file with the result of the given function, in the same
+/// way as `relocate`.
+pub(crate) fn rename_classes<F: Fn(&str) -> String>(class_file: &mut ClassFile, rename: F) {
+    let references = find_type_references(class_file);
+
+    let mut new_values = HashMap::new();
+    for (reference, index) in references.iter() {
+        if new_values.contains_key(index) {
+            continue;
+        }
+
+        let value = class_file.get_constant_utf8(*index);
+
+        // array classes are named by their descriptors
+        let new_value = match *reference {
+            TypeReference::ClassName(_) if !value.starts_with('[') => Some(rename(value)),
+            _ => map_class_names(value, &rename),
+        };
+
+        if let Some(new_value) = new_value.filter(|new_value| new_value != value) {
+            new_values.insert(*index, new_value);
+        }
+    }
+
+    let shared = find_non_type_utf8s(class_file);
+
+    let mut new_indexes = HashMap::new();
+    for (index, new_value) in new_values {
+        if shared.contains(&index) {
+            new_indexes.insert(index, class_file.intern_utf8(&new_value));
+        } else {
+            class_file.constant_pool[index - 1] =
+                ConstantPoolEntry::ConstantUtf8 { string: new_value };
+        }
+    }
+
+    for (reference, index) in references {
+        if let Some(new_index) = new_indexes.get(&index) {
+            set_type_reference(class_file, reference, *new_index);
+        }
+    }
+}
+
+/// Returns the parts of the given class file that reference class names,
+/// descriptors, and signatures, along with the indexes of the Utf8 constants
+/// that they reference.
+fn find_type_references(class_file: &ClassFile) -> Vec<(TypeReference, ConstantPoolIndex)> {
+    use self::TypeReference::*;
+    use ConstantPoolEntry::*;
+
+    let mut references = Vec::new();
+
+    for (i, entry) in class_file.constant_pool.iter().enumerate() {
+        // Account for 1 indexing
+        let i = i + 1;
+
+        match *entry {
+            ConstantClass { name_index } => references.push((ClassName(i), name_index)),
+            ConstantNameAndType {
+                descriptor_index, ..
+            } => references.push((NameAndTypeDescriptor(i), descriptor_index)),
+            ConstantMethodType { descriptor_index } => {
+                references.push((MethodTypeDescriptor(i), descriptor_index as usize))
+            }
+            _ => {}
+        }
+    }
+
+    for (i, field) in class_file.fields.iter().enumerate() {
+        references.push((FieldDescriptor(i), field.descriptor_index));
+    }
+
+    for (i, method) in class_file.methods.iter().enumerate() {
+        references.push((MethodDescriptor(i), method.descriptor_index));
+    }
+
+    for owner in attribute_owners(class_file) {
+        let attributes = attributes_of(class_file, owner);
+
+        for (i, attr) in attributes.attributes.iter().enumerate() {
+            if attr.name(class_file) == "Signature" && attr.info.len() == 2 {
+                let index = u16::from_be_bytes([attr.info[0], attr.info[1]]);
+
+                references.push((Signature(owner, i), index as usize));
+            }
+        }
+    }
+
+    for (method, attribute, code) in code_attributes(class_file) {
+        for variable in local_variables(class_file, code) {
+            references.push((
+                LocalVariable(method, attribute, variable.descriptor_offset),
+                variable.descriptor_index,
+            ));
+        }
+    }
+
+    references
+}
+
+/// Returns whether the given Utf8 constant is used for anything other than the
+/// name of a class constant, such as a descriptor or a string literal.
+pub(crate) fn is_used_as_non_class_name(class_file: &ClassFile, index: ConstantPoolIndex) -> bool {
+    find_non_type_utf8s(class_file).contains(&index)
+        || find_type_references(class_file)
+            .iter()
+            .any(|&(reference, i)| match reference {
+                TypeReference::ClassName(_) => false,
+                _ => i == index,
+            })
+}
+
+/// Returns the indexes of the Utf8 constants that are used for something other
+/// than a class name, descriptor, or signature.
+fn find_non_type_utf8s(class_file: &ClassFile) -> HashSet<ConstantPoolIndex> {
+    use ConstantPoolEntry::*;
+
+    let mut indexes = HashSet::new();
+
+    for entry in class_file.constant_pool.iter() {
+        match *entry {
+            ConstantString { string_index } => {
+                indexes.insert(string_index);
+            }
+            ConstantNameAndType { name_index, .. } => {
+                indexes.insert(name_index);
+            }
+            ConstantModule { name_index } | ConstantPackage { name_index } => {
+                indexes.insert(name_index as usize);
+            }
+            _ => {}
+        }
+    }
+
+    indexes.extend(class_file.fields.iter().map(|field| field.name_index));
+    indexes.extend(class_file.methods.iter().map(|method| method.name_index));
+
+    for owner in attribute_owners(class_file) {
+        for attr in attributes_of(class_file, owner).attributes.iter() {
+            indexes.insert(attr.attribute_name_index);
+
+            match attr.name(class_file) {
+                "Signature" => {}
+                "Code" => {
+                    add_nested_non_type_utf8s(class_file, attr, &mut indexes);
+                }
+                _ => add_attribute_references(class_file, attr, &mut indexes),
+            }
+        }
+    }
+
+    indexes
+}
+
+/// Adds the indexes of the Utf8 constants that are used by the nested
+/// attributes of the given Code attribute for something other than a
+/// descriptor or signature, including the names of the local variables.
+fn add_nested_non_type_utf8s(
+    class_file: &ClassFile,
+    code: &Attribute,
+    indexes: &mut HashSet<ConstantPoolIndex>,
+) {
+    let nested = match read_code_undecoded(&code.info) {
+        Ok((code, _)) => code.attributes,
+        Err(_) => return,
+    };
+
+    for attr in nested.attributes.iter() {
+        indexes.insert(attr.attribute_name_index);
+
+        match attr.name(class_file) {
+            "LocalVariableTable" | "LocalVariableTypeTable" => {}
+            _ => add_attribute_references(class_file, attr, indexes),
+        }
+    }
+
+    indexes.extend(
+        local_variables(class_file, &code.info)
+            .into_iter()
+            .map(|variable| variable.name_index),
+    );
+}
+
+/// Adds the indexes of all of the constants referenced by the contents of the
+/// given attribute. Attributes that cannot be read are skipped, as they are
+/// left unchanged.
+fn add_attribute_references(
+    class_file: &ClassFile,
+    attr: &Attribute,
+    indexes: &mut HashSet<ConstantPoolIndex>,
+) {
+    let _ = remap_attribute_contents(class_file, attr, &mut |index| {
+        indexes.insert(index);
+        index
+    });
+}
+
+/// An entry of a `LocalVariableTable` or `LocalVariableTypeTable` attribute
+/// nested in a Code attribute.
+struct LocalVariable {
+    name_index: ConstantPoolIndex,
+    descriptor_index: ConstantPoolIndex,
+    /// The offset of the descriptor (or signature) index within the info of
+    /// the Code attribute.
+    descriptor_offset: usize,
+}
+
+/// Returns the entries of the local variable tables of the given Code
+/// attribute info bytes. The tables of a Code attribute that cannot be read
+/// are skipped, as it is left unchanged.
+fn local_variables(class_file: &ClassFile, bytes: &[u8]) -> Vec<LocalVariable> {
+    let (code, instructions) = match read_code_undecoded(bytes) {
+        Ok(code) => code,
+        Err(_) => return Vec::new(),
+    };
+
+    let read_u16 = |offset: usize| u16::from_be_bytes([bytes[offset], bytes[offset + 1]]);
+
+    // max stack, max locals, code length, code, exception table, and the
+    // number of attributes
+    let mut offset = 8 + instructions.len() + 2 + code.exception_table.len() * 8 + 2;
+
+    let mut variables = Vec::new();
+    for attr in code.attributes.attributes.iter() {
+        // attribute name index and attribute length
+        offset += 6;
+
+        let name = attr.name(class_file);
+        if (name == "LocalVariableTable" || name == "LocalVariableTypeTable")
+            && attr.info.len() >= 2
+        {
+            let count = read_u16(offset) as usize;
+
+            // start pc, length, name index, descriptor index, and index
+            for entry in (0..count).map(|i| offset + 2 + i * 10) {
+                if entry + 10 > offset + attr.info.len() {
+                    break;
+                }
+
+                variables.push(LocalVariable {
+                    name_index: read_u16(entry + 4) as usize,
+                    descriptor_index: read_u16(entry + 6) as usize,
+                    descriptor_offset: entry + 6,
+                });
+            }
+        }
+
+        offset += attr.info.len();
+    }
+
+    variables
+}
+
+/// Returns the Code attributes of the methods of the given class file, along
+/// with the index of the method and the index of the attribute among the
+/// attributes of the method.
+fn code_attributes(class_file: &ClassFile) -> Vec<(usize, usize, &[u8])> {
+    let mut code_attributes = Vec::new();
+
+    for (i, method) in class_file.methods.iter().enumerate() {
+        for (j, attr) in method.attributes.attributes.iter().enumerate() {
+            if attr.name(class_file) == "Code" {
+                code_attributes.push((i, j, &attr.info[..]));
+            }
+        }
+    }
+
+    code_attributes
+}
+
+fn set_type_reference(
+    class_file: &mut ClassFile,
+    reference: TypeReference,
+    index: ConstantPoolIndex,
+) {
+    use self::TypeReference::*;
+    use ConstantPoolEntry::*;
+
+    match reference {
+        ClassName(i) => {
+            if let ConstantClass { ref mut name_index } = class_file.constant_pool[i - 1] {
+                *name_index = index;
+            }
+        }
+        NameAndTypeDescriptor(i) => {
+            if let ConstantNameAndType {
+                ref mut descriptor_index,
+                ..
+            } = class_file.constant_pool[i - 1]
+            {
+                *descriptor_index = index;
+            }
+        }
+        MethodTypeDescriptor(i) => {
+            if let ConstantMethodType {
+                ref mut descriptor_index,
+            } = class_file.constant_pool[i - 1]
+            {
+                *descriptor_index = index as u16;
+            }
+        }
+        FieldDescriptor(i) => class_file.fields[i].descriptor_index = index,
+        MethodDescriptor(i) => class_file.methods[i].descriptor_index = index,
+        Signature(owner, i) => {
+            let attributes = match owner {
+                AttributeOwner::Class => &mut class_file.attributes,
+                AttributeOwner::Field(field) => &mut class_file.fields[field].attributes,
+                AttributeOwner::Method(method) => &mut class_file.methods[method].attributes,
+            };
+
+            let attr = &mut attributes.attributes[i];
+            attr.info = (index as u16).to_be_bytes().to_vec();
+            attr.structured = None;
+        }
+        LocalVariable(method, attribute, offset) => {
+            let attr = &mut class_file.methods[method].attributes.attributes[attribute];
+            attr.info[offset..offset + 2].copy_from_slice(&(index as u16).to_be_bytes());
+            attr.structured = None;
+        }
+    }
+}
+
+fn attribute_owners(class_file: &ClassFile) -> Vec<AttributeOwner> {
+    let mut owners = vec![AttributeOwner::Class];
+    owners.extend((0..class_file.fields.len()).map(AttributeOwner::Field));
+    owners.extend((0..class_file.methods.len()).map(AttributeOwner::Method));
+
+    owners
+}
+
+fn attributes_of(class_file: &ClassFile, owner: AttributeOwner) -> &AttributeSet {
+    match owner {
+        AttributeOwner::Class => &class_file.attributes,
+        AttributeOwner::Field(i) => &class_file.fields[i].attributes,
+        AttributeOwner::Method(i) => &class_file.methods[i].attributes,
+    }
+}
@@ -0,0 +1,204 @@
+use std::fmt;
+
+/// A formal type parameter declared by a generic class or method, with its
+/// bounds rendered as they would appear in Java source code.
+///
+/// See Chapter 4.7.9.1 of the JVM specification for details.
+///
+/// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-4.html#jvms-4.7.9.1
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct TypeParameter {
+    pub name: String,
+    pub bounds: Vec<String>,
+}
+
+impl fmt::Display for TypeParameter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+
+        // an unbounded type parameter is given an implicit bound of Object
+        if self.bounds.is_empty() || self.bounds == [IMPLICIT_BOUND] {
+            return Ok(());
+        }
+
+        write!(f, " extends {}", self.bounds.join(" & "))
+    }
+}
+
+const IMPLICIT_BOUND: &str = "java.lang.Object";
+
+/// Parses the formal type parameters at the start of the given class or method
+/// signature.
+///
+/// Returns an empty list if the signature does not declare any type
+/// parameters, and `None` if the type parameters are malformed.
+pub(crate) fn parse_type_parameters(signature: &str) -> Option<Vec<TypeParameter>> {
+    let mut parser = SignatureParser {
+        input: signature,
+        position: 0,
+    };
+
+    let mut type_parameters = Vec::new();
+    if parser.peek() != Some('<') {
+        return Some(type_parameters);
+    }
+    parser.next();
+
+    while parser.peek()? != '>' {
+        let name = parser.take_until(&[':'])?.to_string();
+        let mut bounds = Vec::new();
+
+        // the class bound may be empty, but the interface bounds may not
+        parser.next();
+        if parser.peek()? != ':' {
+            bounds.push(parser.read_type()?);
+        }
+
+        while parser.peek()? == ':' {
+            parser.next();
+            bounds.push(parser.read_type()?);
+        }
+
+        type_parameters.push(TypeParameter { name, bounds });
+    }
+
+    Some(type_parameters)
+}
+
+/// A parser of signatures that renders the types it reads as Java source.
+struct SignatureParser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> SignatureParser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+
+        Some(c)
+    }
+
+    /// Reads the input up to, but not including, the first of the given
+    /// delimiters.
+    fn take_until(&mut self, delimiters: &[char]) -> Option<&'a str> {
+        let rest = &self.input[self.position..];
+        let end = rest.find(|c| delimiters.contains(&c))?;
+
+        self.position += end;
+
+        Some(&rest[..end])
+    }
+
+    fn read_type(&mut self) -> Option<String> {
+        let rendered = match self.next()? {
+            'L' => return self.read_class_type(),
+            'T' => {
+                let type_variable = self.take_until(&[';'])?.to_string();
+                self.next();
+
+                type_variable
+            }
+            '[' => format!("{}[]", self.read_type()?),
+            'B' => "byte".to_string(),
+            'C' => "char".to_string(),
+            'D' => "double".to_string(),
+            'F' => "float".to_string(),
+            'I' => "int".to_string(),
+            'J' => "long".to_string(),
+            'S' => "short".to_string(),
+            'Z' => "boolean".to_string(),
+            _ => return None,
+        };
+
+        Some(rendered)
+    }
+
+    fn read_class_type(&mut self) -> Option<String> {
+        let mut rendered = self.take_until(&[';', '<', '.'])?.replace('/', ".");
+
+        loop {
+            match self.next()? {
+                '<' => {
+                    let arguments = self.read_type_arguments()?;
+                    rendered += &format!("<{}>", arguments.join(", "));
+                }
+                '.' => {
+                    rendered.push('.');
+                    rendered += self.take_until(&[';', '<', '.'])?;
+                }
+                ';' => return Some(rendered),
+                _ => return None,
+            }
+        }
+    }
+
+    fn read_type_arguments(&mut self) -> Option<Vec<String>> {
+        let mut arguments = Vec::new();
+
+        loop {
+            let argument = match self.peek()? {
+                '>' => {
+                    self.next();
+                    return Some(arguments);
+                }
+                '*' => {
+                    self.next();
+                    "?".to_string()
+                }
+                '+' => {
+                    self.next();
+                    format!("? extends {}", self.read_type()?)
+                }
+                '-' => {
+                    self.next();
+                    format!("? super {}", self.read_type()?)
+                }
+                _ => self.read_type()?,
+            };
+
+            arguments.push(argument);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(signature: &str) -> Vec<String> {
+        parse_type_parameters(signature)
+            .unwrap()
+            .iter()
+            .map(|parameter| parameter.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn parse_type_parameters_of_non_generic_signature() {
+        assert!(parse("Ljava/lang/Object;").is_empty());
+        assert!(parse("(I)V").is_empty());
+    }
+
+    #[test]
+    fn parse_type_parameters_bounds() {
+        assert_eq!(
+            vec!["T", "E extends java.lang.Enum<E>", "A extends int[][]"],
+            parse("<T:Ljava/lang/Object;E:Ljava/lang/Enum<TE;>;A:[[I>Ljava/lang/Object;")
+        );
+        assert_eq!(
+            vec!["M extends java.util.Map<?, ? extends T>.Entry<K, V>"],
+            parse("<M::Ljava/util/Map<*+TT;>.Entry<TK;TV;>;>()V")
+        );
+    }
+
+    #[test]
+    fn parse_type_parameters_malformed() {
+        assert_eq!(None, parse_type_parameters("<T:>"));
+        assert_eq!(None, parse_type_parameters("<T:Ljava/lang/Object"));
+    }
+}
@@ -0,0 +1,73 @@
+use attribute::AttributeSet;
+use class_file::ClassFile;
+use constant_validation::{check_reference, ExpectedKind};
+
+/// Checks that the constant pool indexes held by the structures of the given
+/// class file, outside of its constant pool, reference the kinds of constants
+/// that they are required to.
+///
+/// This covers `this_class`, `super_class`, the interfaces, the names and
+/// descriptors of the fields and methods, and the names of the attributes of
+/// the class, its fields, and its methods.
+///
+/// Returns a message describing each of the problems that were found.
+pub fn validate_structure(class_file: &ClassFile) -> Result<(), Vec<String>> {
+    use self::ExpectedKind::*;
+
+    let mut errors = Vec::new();
+
+    let mut check = |index, expected_kind, location: &dyn Fn() -> String| {
+        if let Err(e) = check_reference(class_file, index, expected_kind) {
+            errors.push(format!("Invalid {}. {}", location(), e));
+        }
+    };
+
+    check(class_file.this_class, Class, &|| "this_class".to_string());
+
+    // the super class index is 0 for java/lang/Object and module-info classes
+    if class_file.super_class != 0 {
+        check(class_file.super_class, Class, &|| "super_class".to_string());
+    }
+
+    for (i, interface) in class_file.interfaces.iter().enumerate() {
+        check(*interface, Class, &|| format!("interface {}", i));
+    }
+
+    check_attribute_names(&mut check, &class_file.attributes, "class");
+
+    for (i, field) in class_file.fields.iter().enumerate() {
+        check(field.name_index, Utf8, &|| format!("name of field {}", i));
+        check(field.descriptor_index, Utf8, &|| {
+            format!("descriptor of field {}", i)
+        });
+
+        check_attribute_names(&mut check, &field.attributes, &format!("field {}", i));
+    }
+
+    for (i, method) in class_file.methods.iter().enumerate() {
+        check(method.name_index, Utf8, &|| format!("name of method {}", i));
+        check(method.descriptor_index, Utf8, &|| {
+            format!("descriptor of method {}", i)
+        });
+
+        check_attribute_names(&mut check, &method.attributes, &format!("method {}", i));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn check_attribute_names<F: FnMut(usize, ExpectedKind, &dyn Fn() -> String)>(
+    check: &mut F,
+    attributes: &AttributeSet,
+    owner: &str,
+) {
+    for (i, attr) in attributes.attributes.iter().enumerate() {
+        check(attr.attribute_name_index, ExpectedKind::Utf8, &|| {
+            format!("name of attribute {} of {}", i, owner)
+        });
+    }
+}
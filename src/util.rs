@@ -1,5 +1,8 @@
+use std::collections::HashSet;
 use std::io;
-use std::io::{Error, ErrorKind};
+use std::io::{Error, Read};
+
+use error::ParseError;
 
 /// Checks if the given unary flag is set within the given binary encoding of a
 /// list of flags.
@@ -9,15 +12,94 @@ pub fn flag_is_set(flag_to_check: u16, flags: u16) -> bool {
     check > 0
 }
 
-pub fn promote_result_to_io<A>(result: Result<A, String>) -> io::Result<A> {
-    match result {
-        Ok(v) => Ok(v),
-        Err(s) => Err(Error::new(ErrorKind::Other, s)),
+/// The conventional order of Java source modifiers, as recommended by the
+/// Java Language Specification.
+const SOURCE_MODIFIER_ORDER: [&str; 11] = [
+    "public",
+    "protected",
+    "private",
+    "abstract",
+    "static",
+    "final",
+    "transient",
+    "volatile",
+    "synchronized",
+    "native",
+    "strictfp",
+];
+
+/// Joins the given source modifier keywords into a single space separated
+/// string in the canonical Java modifier order.
+pub fn join_source_keywords<'a, I: Iterator<Item = &'a str>>(keywords: I) -> String {
+    let mut keywords = keywords.collect::<Vec<&str>>();
+    keywords.sort_by_key(|keyword| {
+        SOURCE_MODIFIER_ORDER
+            .iter()
+            .position(|m| m == keyword)
+            .unwrap_or(SOURCE_MODIFIER_ORDER.len())
+    });
+
+    keywords.join(" ")
+}
+
+/// Returns the given access flags sorted by their bits in the access flags
+/// value, as given by the `access_flag` function.
+///
+/// This is the order in which javap renders flags, with the visibility flags
+/// first, followed by `static`, `final`, and so on.
+pub fn sort_by_flag_bit<T: Clone, F: Fn(&T) -> u16>(
+    access_flags: &HashSet<T>,
+    access_flag: F,
+) -> Vec<T> {
+    let mut ordered = access_flags.iter().cloned().collect::<Vec<T>>();
+    ordered.sort_by_key(access_flag);
+
+    ordered
+}
+
+/// Formats the given bytes as space separated pairs of hex digits.
+pub fn hex_dump(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// The number of bytes shown on each side of the offset at which parsing
+/// failed, by `with_hex_context`.
+const HEX_CONTEXT_RADIUS: usize = 8;
+
+/// Wraps the given error, which occurred while parsing the given bytes after
+/// the given number of them had been read, with a hex dump of the bytes around
+/// that offset.
+pub fn with_hex_context(error: Error, bytes: &[u8], offset: usize) -> Error {
+    let offset = offset.min(bytes.len());
+    let start = offset.saturating_sub(HEX_CONTEXT_RADIUS);
+    let end = (offset + HEX_CONTEXT_RADIUS).min(bytes.len());
+
+    let description = format!(
+        "Failed at offset {:#X}. Bytes {:#X}..{:#X}: [{}] | [{}]",
+        offset,
+        start,
+        end,
+        hex_dump(&bytes[start..offset]),
+        hex_dump(&bytes[offset..end])
+    );
+
+    ParseError::Context {
+        description,
+        source: error,
     }
+    .into()
+}
+
+pub fn promote_result_to_io<A>(result: Result<A, String>) -> io::Result<A> {
+    result.map_err(io_err)
 }
 
 pub fn io_err<S: Into<String>>(message: S) -> Error {
-    Error::new(ErrorKind::Other, message.into())
+    ParseError::Invalid(message.into()).into()
 }
 
 /// A trait that is used to add a method to Result types to allow a context
@@ -28,7 +110,50 @@ pub trait Contextable {
 
 impl<A> Contextable for Result<A, io::Error> {
     fn context<S: Into<String>>(self, error_description: S) -> io::Result<A> {
-        self.map_err(|e| io_err(format!("{} {}", error_description.into(), e)))
+        self.map_err(|source| {
+            ParseError::Context {
+                description: error_description.into(),
+                source,
+            }
+            .into()
+        })
+    }
+}
+
+/// A reader that keeps track of the number of bytes that have been read from
+/// the reader it wraps, so that errors can report where they occurred.
+pub struct PositionedReader<R: Read> {
+    inner: R,
+    position: usize,
+}
+
+impl<R: Read> PositionedReader<R> {
+    pub fn new(inner: R) -> PositionedReader<R> {
+        PositionedReader { inner, position: 0 }
+    }
+
+    /// Returns the number of bytes that have been read so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the given error description with the current position
+    /// appended to it.
+    pub fn at<S: Into<String>>(&self, error_description: S) -> String {
+        format!(
+            "{} (at offset {:#X})",
+            error_description.into(),
+            self.position()
+        )
+    }
+}
+
+impl<R: Read> Read for PositionedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let bytes_read = self.inner.read(buf)?;
+        self.position += bytes_read;
+
+        Ok(bytes_read)
     }
 }
 
@@ -53,7 +178,9 @@ impl From<&FloatBuffer<[u8; 8]>> for f64 {
 
 #[cfg(test)]
 mod tests {
-    use util::flag_is_set;
+    use std::io::Read;
+
+    use util::{flag_is_set, join_source_keywords, PositionedReader};
 
     #[test]
     fn flag_is_set_finds_a_set_flag() {
@@ -70,4 +197,26 @@ mod tests {
 
         assert_eq!(false, flag_is_set(volatile_flag, access_flags))
     }
+
+    #[test]
+    fn join_source_keywords_uses_canonical_order() {
+        let keywords = vec!["final", "static", "public"];
+
+        assert_eq!(
+            "public static final",
+            join_source_keywords(keywords.into_iter())
+        )
+    }
+
+    #[test]
+    fn positioned_reader_tracks_position() {
+        let bytes = [1, 2, 3, 4, 5];
+        let mut reader = PositionedReader::new(&bytes[..]);
+
+        let mut buffer = [0; 3];
+        reader.read_exact(&mut buffer).unwrap();
+
+        assert_eq!(3, reader.position());
+        assert_eq!("Failed. (at offset 0x3)", reader.at("Failed."));
+    }
 }
@@ -0,0 +1,75 @@
+use descriptor::FieldType;
+
+/// The type of a value in a local variable or on the operand stack, as seen
+/// by the type checking verifier.
+///
+/// See Chapter 4.10.1.2 of the JVM specification for details.
+///
+/// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-4.html#jvms-4.10.1.2
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum VerificationType {
+    Top,
+    Integer,
+    Float,
+    Long,
+    Double,
+    Null,
+    UninitializedThis,
+    /// An instance of the class with the given internal name, or an array
+    /// with the given descriptor (ex. `[I`).
+    Object(String),
+    /// An object created by the `new` instruction at the given offset, whose
+    /// constructor has not yet been called.
+    Uninitialized(u16),
+}
+
+impl VerificationType {
+    /// Returns the verification type of a value of the given field type.
+    ///
+    /// Types narrower than `int` are represented as `int`s.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::{FieldType, VerificationType};
+    /// #
+    /// assert_eq!(VerificationType::Integer, VerificationType::from_field_type(&FieldType::Boolean));
+    /// assert_eq!(
+    ///     VerificationType::Object("[I".to_string()),
+    ///     VerificationType::from_field_type(&FieldType::Array(Box::new(FieldType::Int)))
+    /// );
+    /// ```
+    pub fn from_field_type(field_type: &FieldType) -> VerificationType {
+        match field_type {
+            FieldType::Byte
+            | FieldType::Char
+            | FieldType::Int
+            | FieldType::Short
+            | FieldType::Boolean => VerificationType::Integer,
+            FieldType::Float => VerificationType::Float,
+            FieldType::Long => VerificationType::Long,
+            FieldType::Double => VerificationType::Double,
+            FieldType::Object(class_name) => VerificationType::Object(class_name.clone()),
+            FieldType::Array(_) => VerificationType::Object(field_type.to_descriptor()),
+        }
+    }
+
+    /// Returns true if values of the type take up two slots, rather than one.
+    ///
+    /// ```
+    /// # use jvm_class_file_parser::VerificationType;
+    /// #
+    /// assert!(VerificationType::Long.is_category_2());
+    /// assert!(!VerificationType::Integer.is_category_2());
+    /// ```
+    pub fn is_category_2(&self) -> bool {
+        matches!(self, VerificationType::Long | VerificationType::Double)
+    }
+
+    /// Returns the number of slots that values of the type take up.
+    pub fn slot_size(&self) -> u16 {
+        if self.is_category_2() {
+            2
+        } else {
+            1
+        }
+    }
+}
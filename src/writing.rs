@@ -1,19 +1,57 @@
 use std::io;
 use std::io::{Error, ErrorKind, Write};
 
+use annotation::*;
 use attribute::*;
-use class_access::*;
+use bytecode::*;
 use class_file::ClassFile;
 use constant_pool::*;
+use debug_info::*;
+use field::*;
+use field_access::*;
 use method::*;
+use method_access::MethodAccess;
 
 const MAGIC: u32 = 0xCAFE_BABE;
 
 const CONSTANT_TAG_UTF8: u8 = 1;
+const CONSTANT_TAG_INTEGER: u8 = 3;
+const CONSTANT_TAG_FLOAT: u8 = 4;
+const CONSTANT_TAG_LONG: u8 = 5;
+const CONSTANT_TAG_DOUBLE: u8 = 6;
 const CONSTANT_TAG_CLASS: u8 = 7;
-const _CONSTANT_TAG_FIELDREF: u8 = 9;
+const CONSTANT_TAG_STRING: u8 = 8;
+const CONSTANT_TAG_FIELDREF: u8 = 9;
 const CONSTANT_TAG_METHODREF: u8 = 10;
+const CONSTANT_TAG_INTERFACE_METHODREF: u8 = 11;
 const CONSTANT_TAG_NAME_AND_TYPE: u8 = 12;
+const CONSTANT_METHOD_HANDLE: u8 = 15;
+const CONSTANT_METHOD_TYPE: u8 = 16;
+const CONSTANT_DYNAMIC: u8 = 17;
+const CONSTANT_INVOKE_DYNAMIC: u8 = 18;
+const CONSTANT_MODULE: u8 = 19;
+const CONSTANT_PACKAGE: u8 = 20;
+
+/// Options that control how `ClassFile::to_file_with_options` writes a class
+/// file.
+///
+/// ```
+/// # use jvm_class_file_parser::WriteOptions;
+/// #
+/// let options = WriteOptions::default();
+///
+/// assert!(!options.dedup_constants);
+/// assert!(!options.validate_method_access);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct WriteOptions {
+    /// Whether to merge identical constants before writing the constant pool.
+    /// See `ClassFile::dedup_constant_pool` for details.
+    pub dedup_constants: bool,
+    /// Whether to refuse to write a method whose access flags are an illegal
+    /// combination. See `MethodAccess::validate` for details.
+    pub validate_method_access: bool,
+}
 
 pub fn write_class_file<W: Write>(file: &mut W, class_file: &ClassFile) -> io::Result<()> {
     write_u32(file, MAGIC)?;
@@ -23,12 +61,13 @@ pub fn write_class_file<W: Write>(file: &mut W, class_file: &ClassFile) -> io::R
 
     write_constant_pool(file, &class_file.constant_pool)?;
 
-    write_u16(file, ClassAccess::to_access_flags(&class_file.access_flags))?;
+    write_u16(file, class_file.raw_access_flags())?;
     write_cp_index(file, class_file.this_class)?;
-    write_cp_index(file, class_file.super_class)?;
+    // the super class index is 0 for java/lang/Object and module-info classes
+    write_optional_cp_index(file, class_file.super_class)?;
 
-    write_u16(file, 0)?; // interfaces
-    write_u16(file, 0)?; // fields
+    write_interfaces(file, &class_file.interfaces)?;
+    write_fields(file, &class_file.fields)?;
     write_methods(file, &class_file.methods)?;
     write_attributes(file, &class_file.attributes.attributes)?;
 
@@ -57,6 +96,13 @@ fn write_cp_index<W: Write>(file: &mut W, value: ConstantPoolIndex) -> io::Resul
     }
 }
 
+fn write_optional_cp_index<W: Write>(file: &mut W, value: ConstantPoolIndex) -> io::Result<()> {
+    match value {
+        0 => write_u16(file, 0),
+        _ => write_cp_index(file, value),
+    }
+}
+
 fn write_u32<W: Write>(file: &mut W, value: u32) -> io::Result<()> {
     file.write_all(&u32::to_be_bytes(value))
 }
@@ -78,29 +124,114 @@ fn write_constant_pool<W: Write>(
     Ok(())
 }
 
-#[allow(clippy::borrowed_box)]
 fn write_constant_pool_entry<W: Write>(file: &mut W, entry: &ConstantPoolEntry) -> io::Result<()> {
     use ConstantPoolEntry::*;
 
     match *entry {
         ConstantUtf8 { ref string } => write_constant_utf8(file, &string)?,
+        ConstantInteger { val } => {
+            write_u8(file, CONSTANT_TAG_INTEGER)?;
+            write_n_bytes(file, &val.to_be_bytes())?;
+        }
+        ConstantFloat { ref val } => {
+            write_u8(file, CONSTANT_TAG_FLOAT)?;
+            write_n_bytes(file, &val.buf)?;
+        }
+        ConstantLong { val } => {
+            write_u8(file, CONSTANT_TAG_LONG)?;
+            write_n_bytes(file, &val.to_be_bytes())?;
+        }
+        ConstantDouble { ref val } => {
+            write_u8(file, CONSTANT_TAG_DOUBLE)?;
+            write_n_bytes(file, &val.buf)?;
+        }
         ConstantClass { name_index } => write_constant_class(file, name_index)?,
+        ConstantString { string_index } => {
+            write_u8(file, CONSTANT_TAG_STRING)?;
+            write_cp_index(file, string_index)?;
+        }
+        ConstantFieldref {
+            class_index,
+            name_and_type_index,
+        } => write_constant_ref(
+            file,
+            CONSTANT_TAG_FIELDREF,
+            class_index,
+            name_and_type_index,
+        )?,
         ConstantMethodref {
             class_index,
             name_and_type_index,
         } => write_constant_methodref(file, class_index, name_and_type_index)?,
+        ConstantInterfaceMethodref {
+            class_index,
+            name_and_type_index,
+        } => write_constant_ref(
+            file,
+            CONSTANT_TAG_INTERFACE_METHODREF,
+            class_index as ConstantPoolIndex,
+            name_and_type_index as ConstantPoolIndex,
+        )?,
         ConstantNameAndType {
             name_index,
             descriptor_index,
         } => write_constant_name_and_type(file, name_index, descriptor_index)?,
-        _ => panic!(),
+        ConstantMethodHandle {
+            reference_kind,
+            reference_index,
+        } => {
+            write_u8(file, CONSTANT_METHOD_HANDLE)?;
+            write_u8(file, reference_kind)?;
+            write_u16(file, reference_index)?;
+        }
+        ConstantMethodType { descriptor_index } => {
+            write_u8(file, CONSTANT_METHOD_TYPE)?;
+            write_u16(file, descriptor_index)?;
+        }
+        ConstantDynamic {
+            bootstrap_method_attr_index,
+            name_and_type_index,
+        } => {
+            write_u8(file, CONSTANT_DYNAMIC)?;
+            write_u16(file, bootstrap_method_attr_index)?;
+            write_u16(file, name_and_type_index)?;
+        }
+        ConstantInvokeDynamic {
+            bootstrap_method_attr_index,
+            name_and_type_index,
+        } => {
+            write_u8(file, CONSTANT_INVOKE_DYNAMIC)?;
+            write_u16(file, bootstrap_method_attr_index)?;
+            write_u16(file, name_and_type_index)?;
+        }
+        ConstantModule { name_index } => {
+            write_u8(file, CONSTANT_MODULE)?;
+            write_u16(file, name_index)?;
+        }
+        ConstantPackage { name_index } => {
+            write_u8(file, CONSTANT_PACKAGE)?;
+            write_u16(file, name_index)?;
+        }
+        // the second slot of a long or double constant is not written, as it
+        // is implied by the constant before it
+        ConstantEmptySlot {} => {}
     }
 
     Ok(())
 }
 
 fn write_constant_utf8<W: Write>(file: &mut W, string: &str) -> io::Result<()> {
-    let bytes = string.as_bytes();
+    let bytes = encode_modified_utf8(string);
+
+    if bytes.len() > usize::from(u16::MAX) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "Utf8 constant is too long, its encoding takes {} bytes",
+                bytes.len()
+            ),
+        ));
+    }
 
     write_u8(file, CONSTANT_TAG_UTF8)?;
     write_u16(file, bytes.len() as u16)?;
@@ -109,6 +240,35 @@ fn write_constant_utf8<W: Write>(file: &mut W, string: &str) -> io::Result<()> {
     Ok(())
 }
 
+/// Encodes the given string in the modified UTF-8 format used by class files,
+/// where nulls are encoded as 2 bytes and supplementary characters are encoded
+/// as surrogate pairs of 3 bytes each.
+///
+/// https://docs.oracle.com/javase/specs/jvms/se11/html/jvms-4.html#jvms-4.4.7
+fn encode_modified_utf8(string: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(string.len());
+
+    for c in string.chars() {
+        match c {
+            '\0' => bytes.extend_from_slice(&[0xc0, 0x80]),
+            c if (c as u32) > 0xffff => {
+                let mut surrogates = [0; 2];
+                for surrogate in c.encode_utf16(&mut surrogates).iter() {
+                    bytes.push(0xe0 | (surrogate >> 12) as u8);
+                    bytes.push(0x80 | ((surrogate >> 6) & 0x3f) as u8);
+                    bytes.push(0x80 | (surrogate & 0x3f) as u8);
+                }
+            }
+            c => {
+                let mut buffer = [0; 4];
+                bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+            }
+        }
+    }
+
+    bytes
+}
+
 fn write_constant_class<W: Write>(file: &mut W, name_index: ConstantPoolIndex) -> io::Result<()> {
     write_u8(file, CONSTANT_TAG_CLASS)?;
     write_cp_index(file, name_index)?;
@@ -121,7 +281,21 @@ fn write_constant_methodref<W: Write>(
     class_index: ConstantPoolIndex,
     name_and_type_index: ConstantPoolIndex,
 ) -> io::Result<()> {
-    write_u8(file, CONSTANT_TAG_METHODREF)?;
+    write_constant_ref(
+        file,
+        CONSTANT_TAG_METHODREF,
+        class_index,
+        name_and_type_index,
+    )
+}
+
+fn write_constant_ref<W: Write>(
+    file: &mut W,
+    tag: u8,
+    class_index: ConstantPoolIndex,
+    name_and_type_index: ConstantPoolIndex,
+) -> io::Result<()> {
+    write_u8(file, tag)?;
     write_cp_index(file, class_index)?;
     write_cp_index(file, name_and_type_index)?;
 
@@ -140,6 +314,36 @@ fn write_constant_name_and_type<W: Write>(
     Ok(())
 }
 
+fn write_interfaces<W: Write>(file: &mut W, interfaces: &[ConstantPoolIndex]) -> io::Result<()> {
+    write_u16(file, interfaces.len() as u16)?;
+
+    for interface in interfaces.iter() {
+        write_cp_index(file, *interface)?;
+    }
+
+    Ok(())
+}
+
+fn write_fields<W: Write>(file: &mut W, fields: &[Field]) -> io::Result<()> {
+    write_u16(file, fields.len() as u16)?;
+
+    for field in fields.iter() {
+        write_field(file, field)?;
+    }
+
+    Ok(())
+}
+
+fn write_field<W: Write>(file: &mut W, field: &Field) -> io::Result<()> {
+    write_u16(file, FieldAccess::to_access_flags(&field.access_flags))?;
+    write_cp_index(file, field.name_index)?;
+    write_cp_index(file, field.descriptor_index)?;
+
+    write_attributes(file, &field.attributes.attributes)?;
+
+    Ok(())
+}
+
 fn write_methods<W: Write>(file: &mut W, methods: &[Method]) -> io::Result<()> {
     write_u16(file, methods.len() as u16)?;
 
@@ -150,6 +354,19 @@ fn write_methods<W: Write>(file: &mut W, methods: &[Method]) -> io::Result<()> {
     Ok(())
 }
 
+/// Checks that the access flags of each of the given methods are a legal
+/// combination, as when they were set directly on the raw access flags rather
+/// than through `Method::set_access`.
+pub fn validate_method_access(methods: &[Method]) -> io::Result<()> {
+    for method in methods.iter() {
+        if let Err(message) = MethodAccess::validate(&method.access()) {
+            return Err(Error::new(ErrorKind::InvalidInput, message));
+        }
+    }
+
+    Ok(())
+}
+
 fn write_method<W: Write>(file: &mut W, method: &Method) -> io::Result<()> {
     write_u16(file, method.access_flags)?;
     write_cp_index(file, method.name_index)?;
@@ -160,7 +377,7 @@ fn write_method<W: Write>(file: &mut W, method: &Method) -> io::Result<()> {
     Ok(())
 }
 
-fn write_attributes<W: Write>(file: &mut W, attributes: &[Attribute]) -> io::Result<()> {
+pub fn write_attributes<W: Write>(file: &mut W, attributes: &[Attribute]) -> io::Result<()> {
     write_u16(file, attributes.len() as u16)?;
 
     for attribute in attributes.iter() {
@@ -178,8 +395,162 @@ fn write_attribute<W: Write>(file: &mut W, attributes: &Attribute) -> io::Result
     Ok(())
 }
 
+pub fn write_code<W: Write>(file: &mut W, code: &Code) -> io::Result<()> {
+    let code_bytes = Bytecode::to_bytes(&code.code);
+
+    write_u16(file, code.max_stack)?;
+    write_u16(file, code.max_locals)?;
+    write_u32(file, code_bytes.len() as u32)?;
+    write_n_bytes(file, &code_bytes)?;
+
+    write_u16(file, code.exception_table.len() as u16)?;
+    for entry in code.exception_table.iter() {
+        write_u16(file, entry.start_pc)?;
+        write_u16(file, entry.end_pc)?;
+        write_u16(file, entry.handler_pc)?;
+        write_u16(file, entry.catch_type)?;
+    }
+
+    write_attributes(file, &code.attributes.attributes)?;
+
+    Ok(())
+}
+
+pub fn write_line_number_table<W: Write>(
+    file: &mut W,
+    entries: &[LineNumberTableEntry],
+) -> io::Result<()> {
+    write_u16(file, entries.len() as u16)?;
+    for entry in entries.iter() {
+        write_u16(file, entry.start_pc)?;
+        write_u16(file, entry.line_number)?;
+    }
+
+    Ok(())
+}
+
+pub fn write_local_variable_table<W: Write>(
+    file: &mut W,
+    entries: &[LocalVariableTableEntry],
+) -> io::Result<()> {
+    write_u16(file, entries.len() as u16)?;
+    for entry in entries.iter() {
+        write_u16(file, entry.start_pc)?;
+        write_u16(file, entry.length)?;
+        write_cp_index(file, entry.name_index)?;
+        write_cp_index(file, entry.descriptor_index)?;
+        write_u16(file, entry.index)?;
+    }
+
+    Ok(())
+}
+
+pub fn write_annotation<W: Write>(file: &mut W, annotation: &Annotation) -> io::Result<()> {
+    write_cp_index(file, annotation.type_index)?;
+
+    write_u16(file, annotation.element_value_pairs.len() as u16)?;
+    for pair in annotation.element_value_pairs.iter() {
+        write_cp_index(file, pair.element_name_index)?;
+        write_element_value(file, &pair.value)?;
+    }
+
+    Ok(())
+}
+
+pub fn write_element_value<W: Write>(file: &mut W, value: &ElementValue) -> io::Result<()> {
+    match *value {
+        ElementValue::Const {
+            tag,
+            const_value_index,
+        } => {
+            write_u8(file, tag as u8)?;
+            write_cp_index(file, const_value_index)?;
+        }
+        ElementValue::EnumConst {
+            type_name_index,
+            const_name_index,
+        } => {
+            write_u8(file, b'e')?;
+            write_cp_index(file, type_name_index)?;
+            write_cp_index(file, const_name_index)?;
+        }
+        ElementValue::Class { class_info_index } => {
+            write_u8(file, b'c')?;
+            write_cp_index(file, class_info_index)?;
+        }
+        ElementValue::Annotation(ref annotation) => {
+            write_u8(file, b'@')?;
+            write_annotation(file, annotation)?;
+        }
+        ElementValue::Array(ref values) => {
+            write_u8(file, b'[')?;
+
+            write_u16(file, values.len() as u16)?;
+            for value in values.iter() {
+                write_element_value(file, value)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn write_type_annotation<W: Write>(
+    file: &mut W,
+    annotation: &TypeAnnotation,
+) -> io::Result<()> {
+    write_u8(file, annotation.target_type.to_u8())?;
+
+    match annotation.target_info {
+        TargetInfo::TypeParameter {
+            type_parameter_index,
+        } => write_u8(file, type_parameter_index)?,
+        TargetInfo::Supertype { supertype_index } => write_u16(file, supertype_index)?,
+        TargetInfo::TypeParameterBound {
+            type_parameter_index,
+            bound_index,
+        } => {
+            write_u8(file, type_parameter_index)?;
+            write_u8(file, bound_index)?;
+        }
+        TargetInfo::Empty => {}
+        TargetInfo::FormalParameter {
+            formal_parameter_index,
+        } => write_u8(file, formal_parameter_index)?,
+        TargetInfo::Throws { throws_type_index } => write_u16(file, throws_type_index)?,
+        TargetInfo::Localvar { ref table } => {
+            write_u16(file, table.len() as u16)?;
+            for entry in table.iter() {
+                write_u16(file, entry.start_pc)?;
+                write_u16(file, entry.length)?;
+                write_u16(file, entry.index)?;
+            }
+        }
+        TargetInfo::Catch {
+            exception_table_index,
+        } => write_u16(file, exception_table_index)?,
+        TargetInfo::Offset { offset } => write_u16(file, offset)?,
+        TargetInfo::TypeArgument {
+            offset,
+            type_argument_index,
+        } => {
+            write_u16(file, offset)?;
+            write_u8(file, type_argument_index)?;
+        }
+    }
+
+    write_u8(file, annotation.target_path.len() as u8)?;
+    for entry in annotation.target_path.iter() {
+        write_u8(file, entry.type_path_kind)?;
+        write_u8(file, entry.type_argument_index)?;
+    }
+
+    write_annotation(file, &annotation.annotation)
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::ConstantPoolEntry;
     use crate::ConstantPoolIndex;
 
     #[test]
@@ -199,4 +570,49 @@ mod tests {
             "Expected error"
         );
     }
+
+    #[test]
+    fn write_utf8_with_embedded_null() {
+        let mut buf = Vec::new();
+        super::write_constant_utf8(&mut buf, "Ъ\0Ы\0B").unwrap();
+
+        assert_eq!(
+            vec![
+                1, // tag
+                0, 9, // length
+                0xd0, 0xaa, // cyrillic letter Ъ
+                0xc0, 0x80, // 2-byte encoded null
+                0xd0, 0xab, // cyrillic letter Ы
+                0xc0, 0x80, // 2-byte encoded null
+                0x42,
+            ],
+            buf
+        );
+    }
+
+    #[test]
+    fn write_utf8_round_trip() {
+        for string in ["Ъ\0Ы\0B", "\u{1F600} smile"].iter() {
+            let mut buf = Vec::new();
+            super::write_constant_utf8(&mut buf, string).unwrap();
+
+            // skip the tag, which is read before the rest of the constant
+            let parsed = crate::parsing::read_constant_utf8(&mut &buf[1..]).unwrap();
+
+            assert_eq!(
+                ConstantPoolEntry::ConstantUtf8 {
+                    string: string.to_string()
+                },
+                parsed
+            );
+        }
+    }
+
+    #[test]
+    fn write_utf8_supplementary_character() {
+        let mut buf = Vec::new();
+        super::write_constant_utf8(&mut buf, "\u{1F600}").unwrap();
+
+        assert_eq!(vec![1, 0, 6, 0xed, 0xa0, 0xbd, 0xed, 0xb8, 0x80], buf);
+    }
 }
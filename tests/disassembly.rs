@@ -0,0 +1,243 @@
+extern crate jvm_class_file_parser;
+
+use std::fs::File;
+
+use jvm_class_file_parser::{Attribute, ClassFile, ConstantPoolEntry, DisassemblyOptions};
+
+fn disassemble(filepath: &str, options: &DisassemblyOptions) -> String {
+    let mut file = File::open(filepath).unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    class_file.disassemble(options)
+}
+
+#[test]
+fn disassemble_dummy_with_custom_widths() {
+    let options = DisassemblyOptions {
+        print_code: true,
+        constant_index_width: 3,
+        constant_kind_width: 12,
+        constant_operands_width: 8,
+        instruction_width: 16,
+        ..DisassemblyOptions::default()
+    };
+
+    let output = disassemble("classes/Dummy.class", &options);
+
+    assert!(output.contains("\n #1 = Methodref   #3.#10  // java/lang/Object.\"<init>\":()V\n"));
+    assert!(output.contains("\n#10 = NameAndType #4:#5   // \"<init>\":()V\n"));
+    assert!(output.contains("          1: invokespecial #1\n"));
+
+    let default_output = disassemble("classes/Dummy.class", &DisassemblyOptions::default());
+
+    assert!(default_output.contains(
+        "\n   #1 = Methodref           #3.#10          // java/lang/Object.\"<init>\":()V\n"
+    ));
+}
+
+#[test]
+fn disassemble_invoke_dynamic_and_module_constants() {
+    let output = disassemble("classes/Lambda.class", &DisassemblyOptions::default());
+
+    assert!(output.contains(
+        "\n   #7 = InvokeDynamic       #0:#8           // #0:get:()Ljava/util/function/Supplier;\n"
+    ));
+
+    let output = disassemble("classes/module-info.class", &DisassemblyOptions::default());
+
+    assert!(
+        output.contains("\n   #5 = Module              #4              // \"com.example.app\"\n")
+    );
+    assert!(output.contains("\n   #9 = Package             #8              // com/example/app\n"));
+}
+
+#[test]
+fn disassemble_args_size() {
+    let options = DisassemblyOptions {
+        print_code: true,
+        ..DisassemblyOptions::default()
+    };
+
+    // the size of the arguments of an instance method includes `this`
+    let output = disassemble("classes/IntBox.class", &options);
+
+    assert!(output.contains("  IntBox(int);\n"));
+    assert!(output.contains("      stack=2, locals=2, args_size=2\n"));
+}
+
+#[test]
+fn disassemble_generic_class_header() {
+    let output = disassemble("classes/Generic.class", &DisassemblyOptions::default());
+
+    assert!(output.contains(
+        "\nclass Generic<T, U extends java.lang.Number & java.lang.Comparable<U>, L extends java.util.List<? super T>>\n"
+    ));
+}
+
+#[test]
+fn disassemble_dummy_without_comments() {
+    let options = DisassemblyOptions {
+        resolve_comments: false,
+        ..DisassemblyOptions::default()
+    };
+
+    let output = disassemble("classes/Dummy.class", &options);
+
+    assert!(output.contains("\n   #1 = Methodref           #3.#10\n"));
+    assert!(!output.contains("//"));
+}
+
+#[test]
+fn disassemble_unknown_attribute_as_hex() {
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let attribute_name_index = class_file.intern_utf8("VendorSpecific");
+    class_file.attributes.attributes.push(Attribute {
+        attribute_name_index,
+        info: vec![0xca, 0xfe, 0x00, 0x1f],
+        structured: None,
+        file_offset: None,
+    });
+
+    let output = class_file.disassemble(&DisassemblyOptions::default());
+
+    assert!(output.contains("\n  VendorSpecific: length = 0x4, bytes = ca fe 00 1f\n"));
+}
+
+#[test]
+fn disassemble_unsupported_opcode() {
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    // replace the aload_0 of the constructor with the unsupported opcode 254
+    let code_attribute = &mut class_file.methods[0].attributes.attributes[0];
+    code_attribute.info[8] = 254;
+
+    let options = DisassemblyOptions {
+        print_code: true,
+        ..DisassemblyOptions::default()
+    };
+
+    let output = class_file.disassemble(&options);
+
+    assert!(output.contains("          0: <unknown>     254"));
+    assert!(output.contains("          1: invokespecial #1"));
+    assert!(output.contains("          4: return"));
+}
+
+#[test]
+fn disassemble_dynamic_constant() {
+    let mut file = File::open("classes/Lambda.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let name_index = class_file.intern_utf8("_");
+    let descriptor_index = class_file.intern_utf8("Ljava/lang/Object;");
+    let name_and_type_index = class_file.add_constant(ConstantPoolEntry::ConstantNameAndType {
+        name_index,
+        descriptor_index,
+    });
+    let index = class_file.add_constant(ConstantPoolEntry::ConstantDynamic {
+        bootstrap_method_attr_index: 0,
+        name_and_type_index: name_and_type_index as u16,
+    });
+
+    let output = class_file.disassemble(&DisassemblyOptions::default());
+
+    let expected = format!(
+        "#{} = Dynamic             #0:#{:<12}// #0:_:Ljava/lang/Object;\n",
+        index, name_and_type_index
+    );
+    assert!(output.contains(&expected), "{}", output);
+}
+
+#[test]
+fn disassemble_dummy_with_constant_references() {
+    let options = DisassemblyOptions {
+        show_constant_references: true,
+        ..DisassemblyOptions::default()
+    };
+
+    let output = disassemble("classes/Dummy.class", &options);
+
+    assert!(output.contains(
+        "\n   #6 = Utf8                Code\n        referenced by: name of attribute of method 0\n"
+    ));
+    assert!(output.contains("\n   #3 = Class               #12             // java/lang/Object\n        referenced by: #1, super_class\n"));
+}
+
+#[test]
+fn disassemble_empty_interface() {
+    let output = disassemble("classes/Empty.class", &DisassemblyOptions::default());
+
+    assert!(output.ends_with("   #4 = Utf8                java/lang/Object\n{\n}\n"));
+    assert!(!output.contains("Attributes:"));
+}
+
+#[test]
+fn disassemble_flags_in_canonical_order() {
+    let mut file = File::open("classes/HelloWorld.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    // public static final
+    class_file.methods[1].access_flags = 0x0019;
+
+    let output = class_file.disassemble(&DisassemblyOptions::default());
+
+    assert!(output.contains("    flags: ACC_PUBLIC, ACC_STATIC, ACC_FINAL\n"));
+    assert!(output.contains("  flags: ACC_PUBLIC, ACC_SUPER\n"));
+}
+
+#[test]
+fn disassemble_volatile_and_transient_fields() {
+    let output = disassemble(
+        "classes/VolatileFields.class",
+        &DisassemblyOptions::default(),
+    );
+
+    assert!(output.contains(
+        "  private volatile int x;\n    descriptor: I\n    flags: ACC_PRIVATE, ACC_VOLATILE\n"
+    ));
+    assert!(output.contains("  protected transient java.lang.String name;\n"));
+    assert!(output.contains("    flags: ACC_PROTECTED, ACC_TRANSIENT\n"));
+}
+
+#[test]
+fn disassemble_synthetic_field() {
+    let output = disassemble("classes/Nest$Inner.class", &DisassemblyOptions::default());
+
+    // synthetic has no source keyword, so it only shows up in the flags
+    assert!(output.contains(
+        "  final Nest this$0;\n    descriptor: LNest;\n    flags: ACC_FINAL, ACC_SYNTHETIC\n"
+    ));
+}
+
+#[test]
+fn disassemble_finally_exception_handler() {
+    let options = DisassemblyOptions {
+        print_code: true,
+        ..DisassemblyOptions::default()
+    };
+
+    let output = disassemble("classes/TryFinally.class", &options);
+
+    assert!(output.contains(
+        "      Exception table:\n         from    to  target type\n             0     5    13   any\n"
+    ));
+}
+
+#[test]
+fn disassemble_static_initializer() {
+    let options = DisassemblyOptions {
+        print_code: true,
+        ..DisassemblyOptions::default()
+    };
+
+    let output = disassemble("classes/StaticInitializer.class", &options);
+
+    assert!(output.contains("  static {};\n    descriptor: ()V\n    flags: ACC_STATIC\n"));
+    assert!(!output.contains("<clinit>()"));
+
+    // the field initialization done by the static initializer
+    assert!(output.contains("          7: putstatic     #7"));
+}
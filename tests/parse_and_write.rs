@@ -1,15 +1,21 @@
 extern crate jvm_class_file_parser;
 
-use std::fs::File;
-use std::io::{BufReader, BufWriter};
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::PathBuf;
 
-use jvm_class_file_parser::ClassFile;
+use jvm_class_file_parser::ConstantPoolEntry::*;
+use jvm_class_file_parser::{
+    parse_directory, AttributeSet, Bytecode, ClassFile, ClassFileBuilder, Code, JavaVersion,
+    LineNumberTableEntry, LocalVariableTableEntry, MethodAccess, WriteOptions,
+};
 
 /// Round-trip test of a class file where we read the file, then serialize it
-/// and parse it again asserting that the contents are the same
+/// and parse it again asserting that the contents are the same, and that the
+/// serialized bytes are identical to those of the original file
 fn parse_and_write(filepath: &str) {
-    let mut file = File::open(filepath).unwrap();
-    let class_file = ClassFile::from_file(&mut file).unwrap();
+    let original_bytes = fs::read(filepath).unwrap();
+    let class_file = ClassFile::from_file(&mut &original_bytes[..]).unwrap();
 
     let mut write_buffer = BufWriter::new(vec![]);
 
@@ -21,9 +27,807 @@ fn parse_and_write(filepath: &str) {
     let class_file_2 = ClassFile::from_file(&mut read_buffer).unwrap();
 
     assert_eq!(class_file, class_file_2);
+    assert_eq!(original_bytes, tmp_file);
+}
+
+/// Parses each of the class files in the fixtures directory
+fn parse_fixtures() -> Vec<(PathBuf, ClassFile)> {
+    parse_directory("classes")
+        .into_iter()
+        .map(|(path, result)| (path, result.unwrap()))
+        .collect()
+}
+
+#[test]
+fn parse_and_write_class_bridge() {
+    parse_and_write("classes/Bridge.class");
+}
+
+#[test]
+fn parse_and_write_class_circle() {
+    parse_and_write("classes/Circle.class");
+}
+
+#[test]
+fn parse_and_write_class_color() {
+    parse_and_write("classes/Color.class");
+}
+
+#[test]
+fn parse_and_write_class_constantvalues() {
+    parse_and_write("classes/ConstantValues.class");
 }
 
 #[test]
 fn parse_and_write_class_dummy() {
     parse_and_write("classes/Dummy.class");
 }
+
+#[test]
+fn parse_and_write_class_empty() {
+    parse_and_write("classes/Empty.class");
+}
+
+#[test]
+fn parse_and_write_class_exceptionthrows() {
+    parse_and_write("classes/ExceptionThrows.class");
+}
+
+#[test]
+fn parse_and_write_class_helloworld() {
+    parse_and_write("classes/HelloWorld.class");
+}
+
+#[test]
+fn parse_and_write_class_intbox() {
+    parse_and_write("classes/IntBox.class");
+}
+
+#[test]
+fn parse_and_write_class_interface() {
+    parse_and_write("classes/Interface.class");
+}
+
+#[test]
+fn parse_and_write_class_lambda() {
+    parse_and_write("classes/Lambda.class");
+}
+
+#[test]
+fn parse_and_write_class_nest_hidden() {
+    parse_and_write("classes/Nest$Hidden.class");
+}
+
+#[test]
+fn parse_and_write_class_nest_inner() {
+    parse_and_write("classes/Nest$Inner.class");
+}
+
+#[test]
+fn parse_and_write_class_nest() {
+    parse_and_write("classes/Nest.class");
+}
+
+#[test]
+fn parse_and_write_class_nonnull() {
+    parse_and_write("classes/NonNull.class");
+}
+
+#[test]
+fn parse_and_write_class_point() {
+    parse_and_write("classes/Point.class");
+}
+
+#[test]
+fn parse_and_write_class_primitiveconstants() {
+    parse_and_write("classes/PrimitiveConstants.class");
+}
+
+#[test]
+fn parse_and_write_class_shape() {
+    parse_and_write("classes/Shape.class");
+}
+
+#[test]
+fn parse_and_write_class_square() {
+    parse_and_write("classes/Square.class");
+}
+
+#[test]
+fn parse_and_write_class_staticconstantvalues() {
+    parse_and_write("classes/StaticConstantValues.class");
+}
+
+#[test]
+fn parse_and_write_class_stringconcat() {
+    parse_and_write("classes/StringConcat.class");
+}
+
+#[test]
+fn parse_and_write_class_typeannotated() {
+    parse_and_write("classes/TypeAnnotated.class");
+}
+
+#[test]
+fn parse_and_write_class_debug_dummy() {
+    parse_and_write("classes/debug/Dummy.class");
+}
+
+#[test]
+fn parse_and_write_class_module_info() {
+    parse_and_write("classes/module-info.class");
+}
+
+#[test]
+fn parse_and_write_class_no_debug_intbox() {
+    parse_and_write("classes/no_debug/IntBox.class");
+}
+
+/// A writer that counts the number of writes made to it
+struct CountingWriter {
+    bytes: Vec<u8>,
+    writes: usize,
+}
+
+impl Write for CountingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writes += 1;
+        self.bytes.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn to_file_buffers_writes() {
+    let mut file = File::open("classes/HelloWorld.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let mut writer = CountingWriter {
+        bytes: vec![],
+        writes: 0,
+    };
+
+    class_file.to_file(&mut writer).unwrap();
+
+    // the whole class file fits in the buffer, so it is written all at once
+    assert_eq!(1, writer.writes);
+    assert_eq!(
+        class_file,
+        ClassFile::from_file(&mut &writer.bytes[..]).unwrap()
+    );
+}
+
+#[test]
+fn rename_class_dummy() {
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    class_file.rename_class("Renamed");
+
+    let mut bytes = vec![];
+    class_file.to_file(&mut bytes).unwrap();
+
+    let class_file_2 = ClassFile::from_file(&mut &bytes[..]).unwrap();
+
+    assert_eq!("Renamed", class_file_2.get_class_name());
+    assert_eq!(class_file, class_file_2);
+}
+
+#[test]
+fn rename_class_shared_with_method_name() {
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    // a method named after the class shares the Utf8 of the class name
+    let name_index = match class_file.constant_pool[class_file.this_class - 1] {
+        ConstantClass { name_index } => name_index,
+        ref constant => panic!("Expected a ConstantClass. Found: {:?}", constant),
+    };
+    class_file.methods[0].name_index = name_index;
+
+    class_file.rename_class("Renamed");
+
+    assert_eq!("Renamed", class_file.get_class_name());
+    assert_eq!(
+        "Dummy",
+        class_file.get_constant_utf8(class_file.methods[0].name_index)
+    );
+}
+
+#[test]
+fn rename_class_and_descriptors_color() {
+    let mut file = File::open("classes/Color.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    // a string literal that happens to share the Utf8 of a descriptor
+    let string_index = class_file.add_constant(ConstantString { string_index: 39 });
+
+    class_file.rename_class_and_descriptors("Renamed");
+
+    let mut bytes = vec![];
+    class_file.to_file(&mut bytes).unwrap();
+
+    let class_file = ClassFile::from_file(&mut &bytes[..]).unwrap();
+
+    assert_eq!("Renamed", class_file.get_class_name());
+    assert_eq!("[LRenamed;", class_file.get_constant_class_str(18));
+    match class_file.constant_pool[37 - 1] {
+        ConstantNameAndType {
+            descriptor_index, ..
+        } => assert_eq!(
+            "()[LRenamed;",
+            class_file.get_constant_utf8(descriptor_index)
+        ),
+        ref constant => panic!("Expected a ConstantNameAndType. Found: {:?}", constant),
+    }
+    assert_eq!(
+        Some("Ljava/lang/Enum<LRenamed;>;".to_string()),
+        class_file.attributes.get_signature(&class_file)
+    );
+
+    match class_file.constant_pool[string_index - 1] {
+        ConstantString { string_index } => {
+            assert_eq!("()[LColor;", class_file.get_constant_utf8(string_index))
+        }
+        ref constant => panic!("Expected a ConstantString. Found: {:?}", constant),
+    }
+}
+
+#[test]
+fn rename_class_and_descriptors_local_variables() {
+    let mut file = File::open("classes/debug/Dummy.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    class_file.rename_class_and_descriptors("Renamed");
+
+    let mut bytes = vec![];
+    class_file.to_file(&mut bytes).unwrap();
+
+    let class_file = ClassFile::from_file(&mut &bytes[..]).unwrap();
+
+    let constructor = &class_file.methods[0];
+    let code = constructor.get_code(&class_file).unwrap().unwrap();
+    let local_variables = code
+        .attributes
+        .get_local_variable_table(&class_file)
+        .unwrap();
+
+    assert_eq!(1, local_variables.len());
+    assert_eq!(
+        "this",
+        class_file.get_constant_utf8(local_variables[0].name_index)
+    );
+    assert_eq!(
+        "LRenamed;",
+        class_file.get_constant_utf8(local_variables[0].descriptor_index)
+    );
+}
+
+#[test]
+fn strip_debug_info_dummy() {
+    let mut file = File::open("classes/debug/Dummy.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    class_file.strip_debug_info().unwrap();
+
+    let mut bytes = vec![];
+    class_file.to_file(&mut bytes).unwrap();
+
+    let class_file_2 = ClassFile::from_file(&mut &bytes[..]).unwrap();
+
+    assert_eq!(class_file, class_file_2);
+    assert_eq!(None, class_file_2.get_source_file_name());
+
+    let constructor = &class_file_2.methods[0];
+    let code = constructor.get_code(&class_file_2).unwrap().unwrap();
+
+    use Bytecode::*;
+    assert_eq!(
+        vec![(0, Aload_0), (1, Invokespecial(1)), (4, Return)],
+        code.code
+    );
+    assert!(code.attributes.attributes.is_empty());
+}
+
+#[test]
+fn strip_debug_info_with_duplicate_code_name() {
+    let mut file = File::open("classes/debug/Dummy.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    // the Code attribute uses a second Utf8 holding "Code"
+    let code_name_index = class_file.add_constant(ConstantUtf8 {
+        string: "Code".to_string(),
+    });
+    class_file.methods[0].attributes.attributes[0].attribute_name_index = code_name_index;
+
+    class_file.strip_debug_info().unwrap();
+
+    let code = class_file.methods[0]
+        .get_code(&class_file)
+        .unwrap()
+        .unwrap();
+
+    assert!(code.attributes.attributes.is_empty());
+}
+
+#[test]
+fn strip_debug_info_color_and_point() {
+    for path in ["classes/Color.class", "classes/Point.class"].iter() {
+        let mut file = File::open(path).unwrap();
+        let original = ClassFile::from_file(&mut file).unwrap();
+
+        let mut class_file = original.clone();
+        class_file.strip_debug_info().unwrap();
+
+        let mut bytes = vec![];
+        class_file.to_file(&mut bytes).unwrap();
+
+        let class_file_2 = ClassFile::from_file(&mut &bytes[..]).unwrap();
+
+        assert_eq!(class_file, class_file_2);
+        assert_eq!(None, class_file_2.get_source_file_name());
+
+        // the constants only used by the debug attributes are removed
+        assert!(class_file_2.constant_pool.len() < original.constant_pool.len());
+        assert!(!class_file_2.constant_pool.contains(&ConstantUtf8 {
+            string: "LineNumberTable".to_string(),
+        }));
+
+        for (method, original_method) in class_file_2.methods.iter().zip(original.methods.iter()) {
+            let code = method.attributes.find_attribute(&class_file_2, "Code");
+            let original_code = original_method.attributes.find_attribute(&original, "Code");
+
+            // the max stack, max locals, and code length are unchanged
+            assert_eq!(
+                original_code.map(|attr| attr.info[..8].to_vec()),
+                code.map(|attr| attr.info[..8].to_vec())
+            );
+        }
+    }
+}
+
+#[test]
+fn add_method_dummy() {
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    use Bytecode::*;
+    let code = Code {
+        max_stack: 0,
+        max_locals: 0,
+        code: vec![(0, Return)],
+        exception_table: vec![],
+        attributes: AttributeSet { attributes: vec![] },
+    };
+
+    class_file.add_method(0x0009, "noop", "()V", code).unwrap();
+
+    let mut bytes = vec![];
+    class_file.to_file(&mut bytes).unwrap();
+
+    let class_file_2 = ClassFile::from_file(&mut &bytes[..]).unwrap();
+
+    assert_eq!(class_file, class_file_2);
+    assert_eq!(2, class_file_2.methods.len());
+
+    let noop = class_file_2.find_method("noop", "()V").unwrap();
+    let noop_code = noop.get_code(&class_file_2).unwrap().unwrap();
+
+    assert_eq!(0x0009, noop.access_flags);
+    assert_eq!(vec![(0, Return)], noop_code.code);
+}
+
+#[test]
+fn gc_constant_pool_dummy() {
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+    let original_pool = class_file.constant_pool.clone();
+
+    class_file.add_constant(ConstantUtf8 {
+        string: "unused".to_string(),
+    });
+
+    class_file.gc_constant_pool().unwrap();
+
+    assert_eq!(original_pool, class_file.constant_pool);
+
+    let mut bytes = vec![];
+    class_file.to_file(&mut bytes).unwrap();
+
+    let class_file_2 = ClassFile::from_file(&mut &bytes[..]).unwrap();
+
+    assert_eq!(class_file, class_file_2);
+}
+
+#[test]
+fn gc_constant_pool_all_fixtures() {
+    for (path, class_file) in parse_fixtures() {
+        let mut compacted = class_file.clone();
+        compacted
+            .gc_constant_pool()
+            .unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+
+        assert!(compacted.constant_pool.len() <= class_file.constant_pool.len());
+
+        let mut bytes = vec![];
+        compacted.to_file(&mut bytes).unwrap();
+
+        let mut class_file_2 = ClassFile::from_file(&mut &bytes[..]).unwrap();
+
+        assert_eq!(compacted, class_file_2);
+        assert_eq!(class_file.get_class_name(), class_file_2.get_class_name());
+
+        // collecting again finds nothing more to remove
+        class_file_2.gc_constant_pool().unwrap();
+
+        assert_eq!(compacted.constant_pool, class_file_2.constant_pool);
+    }
+}
+
+#[test]
+fn write_method_with_added_final_flag() {
+    let mut file = File::open("classes/IntBox.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let get_value = &mut class_file.methods[1];
+    let mut access = get_value.access();
+    access.insert(MethodAccess::Final);
+    get_value.set_access(&access).unwrap();
+
+    let mut bytes = vec![];
+    class_file.to_file(&mut bytes).unwrap();
+
+    let class_file_2 = ClassFile::from_file(&mut &bytes[..]).unwrap();
+    let get_value = class_file_2.find_method("getValue", "()I").unwrap();
+
+    assert_eq!(class_file, class_file_2);
+    assert_eq!(0x0011, get_value.access_flags);
+    assert_eq!(access, get_value.access());
+}
+
+#[test]
+fn write_method_with_illegal_flags() {
+    let mut file = File::open("classes/IntBox.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    // abstract methods cannot be final
+    let mut access = class_file.methods[1].access();
+    access.insert(MethodAccess::Abstract);
+    access.insert(MethodAccess::Final);
+
+    assert!(class_file.methods[1].set_access(&access).is_err());
+    assert_eq!(0x0001, class_file.methods[1].access_flags);
+
+    // nor can illegal raw flags be written, when asked to validate them
+    class_file.methods[1].access_flags = MethodAccess::to_access_flags(&access);
+
+    let options = WriteOptions {
+        validate_method_access: true,
+        ..WriteOptions::default()
+    };
+
+    let mut bytes = vec![];
+    assert!(class_file
+        .to_file_with_options(&mut bytes, &options)
+        .is_err());
+}
+
+#[test]
+fn write_method_with_odd_parsed_flags() {
+    let mut file = File::open("classes/IntBox.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    // a class file from an old compiler may have flags that are only illegal
+    // in some versions, such as an abstract strictfp method, and these are
+    // accepted by the parser
+    class_file.methods[1].access_flags = 0x0c01;
+
+    let mut bytes = vec![];
+    class_file.to_file(&mut bytes).unwrap();
+
+    let class_file_2 = ClassFile::from_file(&mut &bytes[..]).unwrap();
+
+    assert_eq!(class_file, class_file_2);
+    assert_eq!(0x0c01, class_file_2.methods[1].access_flags);
+}
+
+#[test]
+fn write_with_dedup_constants_hello_world() {
+    let mut file = File::open("classes/HelloWorld.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+    let original_pool = class_file.constant_pool.clone();
+
+    // a duplicate Utf8, and a Class constant that is only a duplicate once the
+    // Utf8 constants are merged
+    let object_name = ConstantUtf8 {
+        string: "java/lang/Object".to_string(),
+    };
+    let duplicate_name = class_file.add_constant(object_name.clone());
+    let duplicate_class = class_file.add_constant(ConstantClass {
+        name_index: duplicate_name,
+    });
+    class_file.super_class = duplicate_class;
+
+    // a long after the duplicates, which needs its empty slot kept
+    class_file.add_constant(ConstantLong { val: 42 });
+    class_file.add_constant(ConstantEmptySlot {});
+
+    let options = WriteOptions {
+        dedup_constants: true,
+        ..WriteOptions::default()
+    };
+
+    let mut bytes = vec![];
+    class_file
+        .to_file_with_options(&mut bytes, &options)
+        .unwrap();
+
+    let class_file_2 = ClassFile::from_file(&mut &bytes[..]).unwrap();
+
+    assert_eq!(
+        1,
+        class_file_2
+            .constant_pool
+            .iter()
+            .filter(|entry| **entry == object_name)
+            .count()
+    );
+    assert_eq!(
+        "java/lang/Object",
+        class_file_2.get_constant_class_str(class_file_2.super_class)
+    );
+    assert_eq!(
+        &original_pool[..],
+        &class_file_2.constant_pool[..original_pool.len()]
+    );
+    assert_eq!(
+        vec![ConstantLong { val: 42 }, ConstantEmptySlot {}],
+        class_file_2.constant_pool[original_pool.len()..].to_vec()
+    );
+
+    // the class file itself is left unchanged
+    assert_eq!(duplicate_class, class_file.super_class);
+}
+
+#[test]
+fn write_with_dedup_constants_all_fixtures() {
+    let options = WriteOptions {
+        dedup_constants: true,
+        ..WriteOptions::default()
+    };
+
+    for (path, class_file) in parse_fixtures() {
+        let mut bytes = vec![];
+        class_file
+            .to_file_with_options(&mut bytes, &options)
+            .unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+
+        let class_file_2 = ClassFile::from_file(&mut &bytes[..]).unwrap();
+
+        assert!(class_file_2.constant_pool.len() <= class_file.constant_pool.len());
+        assert_eq!(class_file.get_class_name(), class_file_2.get_class_name());
+        assert_eq!(class_file.methods.len(), class_file_2.methods.len());
+
+        // writing again finds nothing more to merge
+        let mut bytes_2 = vec![];
+        class_file_2
+            .to_file_with_options(&mut bytes_2, &options)
+            .unwrap();
+
+        assert_eq!(bytes, bytes_2);
+    }
+}
+
+#[test]
+fn gc_constant_pool_after_strip_debug_info() {
+    let mut file = File::open("classes/debug/Dummy.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let constructor = &class_file.methods[0];
+    let code = constructor.get_code(&class_file).unwrap().unwrap();
+    let resolved_code = code
+        .code
+        .iter()
+        .map(|(i, bytecode)| bytecode.to_string_resolved(*i as u16, &class_file))
+        .collect::<Vec<String>>();
+
+    class_file.strip_debug_info().unwrap();
+    class_file.gc_constant_pool().unwrap();
+
+    let mut bytes = vec![];
+    class_file.to_file(&mut bytes).unwrap();
+
+    let class_file_2 = ClassFile::from_file(&mut &bytes[..]).unwrap();
+
+    assert_eq!(class_file, class_file_2);
+    assert!(!class_file_2.constant_pool.contains(&ConstantUtf8 {
+        string: "LocalVariableTable".to_string(),
+    }));
+
+    let constructor = &class_file_2.methods[0];
+    let code = constructor.get_code(&class_file_2).unwrap().unwrap();
+
+    assert_eq!("Dummy", class_file_2.get_class_name());
+    assert_eq!(
+        "<init>",
+        class_file_2.get_constant_utf8(constructor.name_index)
+    );
+    assert_eq!(
+        resolved_code,
+        code.code
+            .iter()
+            .map(|(i, bytecode)| bytecode.to_string_resolved(*i as u16, &class_file_2))
+            .collect::<Vec<String>>()
+    );
+}
+
+#[test]
+fn write_line_number_table() {
+    let entries = vec![
+        LineNumberTableEntry {
+            start_pc: 0,
+            line_number: 3,
+        },
+        LineNumberTableEntry {
+            start_pc: 4,
+            line_number: 5,
+        },
+    ];
+
+    let bytes = LineNumberTableEntry::to_bytes(&entries).unwrap();
+
+    assert_eq!(entries, LineNumberTableEntry::from_bytes(&bytes).unwrap());
+}
+
+#[test]
+fn write_local_variable_table_dummy() {
+    let mut file = File::open("classes/debug/Dummy.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let code = class_file.methods[0]
+        .get_code(&class_file)
+        .unwrap()
+        .unwrap();
+    let entries = code
+        .attributes
+        .get_local_variable_table(&class_file)
+        .unwrap();
+
+    assert_eq!("this", class_file.get_constant_utf8(entries[0].name_index));
+    assert_eq!(
+        "LDummy;",
+        class_file.get_constant_utf8(entries[0].descriptor_index)
+    );
+
+    let bytes = LocalVariableTableEntry::to_bytes(&entries).unwrap();
+    let attr = code
+        .attributes
+        .find_attribute(&class_file, "LocalVariableTable")
+        .unwrap();
+
+    assert_eq!(attr.info, bytes);
+}
+
+#[test]
+fn set_method_code_with_duplicate_code_name() {
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    // the Code attribute uses a second Utf8 holding "Code"
+    let code_name_index = class_file.add_constant(ConstantUtf8 {
+        string: "Code".to_string(),
+    });
+    class_file.methods[0].attributes.attributes[0].attribute_name_index = code_name_index;
+
+    let mut code = class_file.methods[0]
+        .get_code(&class_file)
+        .unwrap()
+        .unwrap();
+    code.code.insert(0, (0, Bytecode::Nop));
+
+    let attributes_count = class_file.methods[0].attributes.attributes.len();
+
+    class_file.set_method_code(0, code.clone()).unwrap();
+
+    assert_eq!(
+        attributes_count,
+        class_file.methods[0].attributes.attributes.len()
+    );
+    assert_eq!(
+        code_name_index,
+        class_file.methods[0].attributes.attributes[0].attribute_name_index
+    );
+    assert_eq!(
+        code.to_bytes().unwrap(),
+        class_file.methods[0].attributes.attributes[0].info
+    );
+}
+
+#[test]
+fn set_method_code_dummy() {
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let mut code = class_file.methods[0]
+        .get_code(&class_file)
+        .unwrap()
+        .unwrap();
+    code.code = vec![
+        (0, Bytecode::Nop),
+        (1, Bytecode::Aload_0),
+        (2, Bytecode::Invokespecial(1)),
+        (5, Bytecode::Return),
+    ];
+
+    let attributes_count = class_file.methods[0].attributes.attributes.len();
+
+    class_file.set_method_code(0, code).unwrap();
+
+    assert_eq!(
+        attributes_count,
+        class_file.methods[0].attributes.attributes.len()
+    );
+
+    let mut bytes = vec![];
+    class_file.to_file(&mut bytes).unwrap();
+
+    let class_file_2 = ClassFile::from_file(&mut &bytes[..]).unwrap();
+    let code_2 = class_file_2.methods[0]
+        .get_code(&class_file_2)
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(class_file, class_file_2);
+    assert_eq!(
+        vec![
+            (0, Bytecode::Nop),
+            (1, Bytecode::Aload_0),
+            (2, Bytecode::Invokespecial(1)),
+            (5, Bytecode::Return),
+        ],
+        code_2.code
+    );
+}
+
+#[test]
+fn parse_and_write_unrecognized_access_flags() {
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    assert_eq!(0, class_file.unrecognized_access_flags);
+
+    // 0x0100 is not a class access flag, and 0x0800 is reserved for future use
+    class_file.unrecognized_access_flags = 0x0900;
+
+    let mut bytes = vec![];
+    class_file.to_file(&mut bytes).unwrap();
+
+    let class_file_2 = ClassFile::from_file(&mut &bytes[..]).unwrap();
+
+    assert_eq!(0x0900, class_file_2.unrecognized_access_flags);
+    assert_eq!(0x0921, class_file_2.raw_access_flags());
+    assert_eq!(class_file.access_flags, class_file_2.access_flags);
+}
+
+#[test]
+fn write_built_class_targeting_java_8() {
+    let class_file = ClassFileBuilder::new("Generated")
+        .java_version(JavaVersion::Java8)
+        .build();
+
+    let mut bytes = vec![];
+    class_file.to_file(&mut bytes).unwrap();
+
+    // the major version follows the magic number and minor version
+    assert_eq!([0x00, 0x00, 0x00, 0x34], bytes[4..8]);
+
+    let reparsed = ClassFile::from_file(&mut &bytes[..]).unwrap();
+
+    assert_eq!(52, reparsed.major_version);
+    assert_eq!(0, reparsed.minor_version);
+    assert_eq!("Generated", reparsed.get_class_name());
+    assert_eq!(class_file, reparsed);
+}
@@ -1,11 +1,18 @@
 extern crate jvm_class_file_parser;
+#[cfg(feature = "tokio")]
+extern crate tokio;
 
-use std::collections::HashSet;
-use std::fs::File;
+use std::collections::{BTreeSet, HashSet};
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read};
+use std::path::Path;
 
 use jvm_class_file_parser::{
-    Attribute, AttributeSet, Bytecode, ClassAccess, ClassFile, Code, ConstantPoolEntry, Field,
-    FieldAccess,
+    parse_directory, Attribute, AttributeOwner, AttributeSet, Bytecode, CatchHandler, ClassAccess,
+    ClassFile, ClassKind, ClassType, Code, ConstantPoolEntry, ConstantReferrer, DiffLocation,
+    Difference, Field, FieldAccess, FieldType, InnerClassAccess, MemberRef, MemberRefKind,
+    ParseError, ParseOptions, ReferenceKind, StructuredAttribute, TargetInfo, TargetType,
+    TryCatchBlock, TypeParameter, VerificationType,
 };
 use std::ops::Deref;
 
@@ -40,6 +47,8 @@ fn parse_class_dummy() {
                 attributes: vec![Attribute {
                     attribute_name_index: 7,
                     info: vec![0, 1, 0, 0, 0, 1],
+                    structured: None,
+                    file_offset: None,
                 }]
             },
         }),
@@ -73,6 +82,7 @@ fn parse_class_intbox() {
             name_index: 5,
             descriptor_index: 6,
             attributes: AttributeSet { attributes: vec![] },
+            file_offset: None,
         },
         *field
     );
@@ -100,6 +110,8 @@ fn parse_class_intbox() {
                 attributes: vec![Attribute {
                     attribute_name_index: 10,
                     info: vec![0, 3, 0, 0, 0, 4, 0, 4, 0, 5, 0, 9, 0, 6],
+                    structured: None,
+                    file_offset: None,
                 }]
             },
         }),
@@ -119,6 +131,8 @@ fn parse_class_intbox() {
                 attributes: vec![Attribute {
                     attribute_name_index: 10,
                     info: vec![0, 1, 0, 0, 0, 9],
+                    structured: None,
+                    file_offset: None,
                 }]
             },
         }),
@@ -160,3 +174,1588 @@ fn parse_class_constant_values() {
         _ => panic!("Expected a utf8 string"),
     }
 }
+
+#[test]
+fn parse_all_concatenated_classes() {
+    let mut bytes = fs::read("classes/Dummy.class").unwrap();
+    bytes.extend(fs::read("classes/HelloWorld.class").unwrap());
+
+    let class_files = ClassFile::parse_all(&mut &bytes[..]).unwrap();
+
+    assert_eq!(2, class_files.len());
+    assert_eq!("Dummy", class_files[0].get_class_name());
+    assert_eq!("HelloWorld", class_files[1].get_class_name());
+}
+
+#[test]
+fn parse_all_truncated_class() {
+    let mut bytes = fs::read("classes/Dummy.class").unwrap();
+    let hello_world = fs::read("classes/HelloWorld.class").unwrap();
+    bytes.extend(&hello_world[..hello_world.len() / 2]);
+
+    assert!(ClassFile::parse_all(&mut &bytes[..]).is_err());
+}
+
+#[test]
+fn resolve_method_handle_lambda() {
+    let mut file = File::open("classes/Lambda.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let lambda_handle = class_file.resolve_method_handle(51);
+
+    assert_eq!(ReferenceKind::InvokeStatic, lambda_handle.reference_kind);
+    assert_eq!("invokeStatic", lambda_handle.reference_kind.label());
+    assert_eq!("Lambda", lambda_handle.class_name);
+    assert_eq!("lambda$main$0", lambda_handle.name);
+    assert_eq!("()Ljava/lang/String;", lambda_handle.descriptor);
+}
+
+#[test]
+fn semantic_hash_ignores_debug_info() {
+    let mut file = File::open("classes/IntBox.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let mut file = File::open("classes/no_debug/IntBox.class").unwrap();
+    let class_file_no_debug = ClassFile::from_file(&mut file).unwrap();
+
+    assert_ne!(class_file.constant_pool, class_file_no_debug.constant_pool);
+    assert_eq!(
+        class_file.semantic_hash().unwrap(),
+        class_file_no_debug.semantic_hash().unwrap()
+    );
+
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let dummy = ClassFile::from_file(&mut file).unwrap();
+
+    assert_ne!(
+        class_file.semantic_hash().unwrap(),
+        dummy.semantic_hash().unwrap()
+    );
+}
+
+#[test]
+fn semantic_hash_of_unsupported_instructions() {
+    for path in ["classes/Color.class", "classes/Point.class"].iter() {
+        let mut file = File::open(path).unwrap();
+        let class_file = ClassFile::from_file(&mut file).unwrap();
+
+        let mut stripped = class_file.clone();
+        stripped.strip_debug_info().unwrap();
+
+        assert_eq!(
+            class_file.semantic_hash().unwrap(),
+            stripped.semantic_hash().unwrap()
+        );
+    }
+}
+
+#[test]
+fn semantic_hash_is_stable() {
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    // hashes may be persisted, so they must not change between versions
+    assert_eq!(12520348109698672740, class_file.semantic_hash().unwrap());
+}
+
+#[test]
+fn semantic_hash_of_self_referential_super_class() {
+    let mut file = File::open("classes/HelloWorld.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let super_class = class_file.super_class;
+    class_file.constant_pool[super_class - 1] = ConstantPoolEntry::ConstantClass {
+        name_index: super_class,
+    };
+
+    let error = class_file.semantic_hash().unwrap_err();
+
+    assert!(error
+        .to_string()
+        .contains(&format!("constant #{}", super_class)));
+}
+
+/// A reader that counts the number of reads made to the reader it wraps.
+struct CountingReader<R: Read> {
+    inner: R,
+    reads: usize,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.reads += 1;
+        self.inner.read(buf)
+    }
+}
+
+#[test]
+fn from_buf_reader_buffers_reads() {
+    let file = File::open("classes/IntBox.class").unwrap();
+    let mut reader = BufReader::new(CountingReader {
+        inner: file,
+        reads: 0,
+    });
+
+    let class_file = ClassFile::from_buf_reader(&mut reader).unwrap();
+
+    assert_eq!("IntBox", class_file.get_class_name());
+    assert!(
+        reader.get_ref().reads <= 2,
+        "Expected at most 2 reads, found {}",
+        reader.get_ref().reads
+    );
+}
+
+#[test]
+fn from_file_does_not_read_past_class_file() {
+    let mut bytes = fs::read("classes/Dummy.class").unwrap();
+    bytes.extend(fs::read("classes/IntBox.class").unwrap());
+
+    let mut reader = &bytes[..];
+
+    let dummy = ClassFile::from_file(&mut reader).unwrap();
+    let int_box = ClassFile::from_file(&mut reader).unwrap();
+
+    assert_eq!("Dummy", dummy.get_class_name());
+    assert_eq!("IntBox", int_box.get_class_name());
+    assert!(reader.is_empty());
+}
+
+#[test]
+fn bytecode_to_string_resolved_helloworld() {
+    let mut file = File::open("classes/HelloWorld.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let main = &class_file.methods[1];
+    let code = main.get_code(&class_file).unwrap().unwrap();
+
+    let resolved = code
+        .code
+        .iter()
+        .map(|(i, bytecode)| bytecode.to_string_resolved(*i as u16, &class_file))
+        .collect::<Vec<String>>();
+
+    assert_eq!(
+        vec![
+            "getstatic     java/lang/System.out:Ljava/io/PrintStream;",
+            "ldc           \"Hello, World!\"",
+            "invokevirtual java/io/PrintStream.println:(Ljava/lang/String;)V",
+            "return",
+        ],
+        resolved
+    );
+}
+
+#[test]
+fn parse_class_nest() {
+    let mut file = File::open("classes/Nest.class").unwrap();
+    let host = ClassFile::from_file(&mut file).unwrap();
+
+    assert_eq!(None, host.get_nest_host());
+    assert_eq!(vec!["Nest$Hidden", "Nest$Inner"], host.get_nest_members());
+
+    let mut file = File::open("classes/Nest$Hidden.class").unwrap();
+    let member = ClassFile::from_file(&mut file).unwrap();
+
+    assert_eq!(Some("Nest"), member.get_nest_host());
+    assert!(member.get_nest_members().is_empty());
+}
+
+#[test]
+fn parse_class_module_info() {
+    let mut file = File::open("classes/module-info.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    assert_eq!(vec!["com/example/app"], class_file.get_module_packages());
+    assert_eq!(
+        Some("com/example/app/Main"),
+        class_file.get_module_main_class()
+    );
+
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    assert!(class_file.get_module_packages().is_empty());
+    assert_eq!(None, class_file.get_module_main_class());
+}
+
+#[test]
+fn parse_class_sealed() {
+    let mut file = File::open("classes/Shape.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    assert_eq!(
+        Some(vec!["Circle", "Square"]),
+        class_file.get_permitted_subclasses()
+    );
+
+    let mut file = File::open("classes/Circle.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    assert_eq!(None, class_file.get_permitted_subclasses());
+}
+
+#[test]
+fn parse_class_record() {
+    let mut file = File::open("classes/Point.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let components = class_file.get_record_components().unwrap();
+
+    assert_eq!(2, components.len());
+
+    assert_eq!("x", components[0].get_name(&class_file));
+    assert_eq!("I", components[0].get_descriptor(&class_file));
+    assert_eq!(None, components[0].attributes.get_signature(&class_file));
+
+    assert_eq!("names", components[1].get_name(&class_file));
+    assert_eq!(
+        "Ljava/util/List;",
+        components[1].get_descriptor(&class_file)
+    );
+    assert_eq!(
+        Some("Ljava/util/List<Ljava/lang/String;>;".to_string()),
+        components[1].attributes.get_signature(&class_file)
+    );
+
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    assert_eq!(None, class_file.get_record_components());
+}
+
+#[test]
+fn parse_truncated_class_reports_offset() {
+    let bytes = fs::read("classes/IntBox.class").unwrap();
+
+    // cut the file off partway through the second method
+    let truncated = &bytes[..bytes.len() - 40];
+
+    let error = ClassFile::from_file(&mut &truncated[..]).unwrap_err();
+    let message = error.to_string();
+
+    assert!(message.contains("Failed to read methods."), "{}", message);
+
+    let offset_start = message.find("at offset 0x").unwrap() + "at offset 0x".len();
+    let offset_end = offset_start + message[offset_start..].find(')').unwrap();
+    let offset = usize::from_str_radix(&message[offset_start..offset_end], 16).unwrap();
+
+    assert!(offset > 0 && offset <= truncated.len());
+}
+
+#[test]
+fn parse_truncated_class_with_diagnostics() {
+    let bytes = fs::read("classes/IntBox.class").unwrap();
+
+    // cut the file off partway through the second method
+    let truncated = &bytes[..bytes.len() - 40];
+
+    let error = ClassFile::from_bytes_with_diagnostics(truncated).unwrap_err();
+    let message = error.to_string();
+
+    let expected_dump = truncated[truncated.len() - 8..]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    assert!(
+        message.starts_with(&format!(
+            "Failed at offset {:#X}. Bytes {:#X}..{:#X}: [{}] | []",
+            truncated.len(),
+            truncated.len() - 8,
+            truncated.len(),
+            expected_dump
+        )),
+        "{}",
+        message
+    );
+    assert!(message.contains("Failed to read methods."), "{}", message);
+}
+
+#[test]
+fn parse_class_type_annotations() {
+    let mut file = File::open("classes/TypeAnnotated.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let field = &class_file.fields[0];
+    let annotations = field
+        .attributes
+        .get_visible_type_annotations(&class_file)
+        .unwrap();
+
+    assert_eq!(1, annotations.len());
+    assert_eq!(TargetType::Field, annotations[0].target_type);
+    assert_eq!(TargetInfo::Empty, annotations[0].target_info);
+    assert!(annotations[0].target_path.is_empty());
+    assert_eq!(
+        "LNonNull;",
+        class_file.get_constant_utf8(annotations[0].annotation.type_index)
+    );
+    assert!(annotations[0].annotation.element_value_pairs.is_empty());
+
+    assert!(field
+        .attributes
+        .get_invisible_type_annotations(&class_file)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn parse_code_type_annotations_on_cast() {
+    let mut file = File::open("classes/TypeAnnotated.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let cast = class_file
+        .find_method("cast", "(Ljava/lang/Object;)Ljava/lang/Object;")
+        .unwrap();
+    let code = cast.get_code(&class_file).unwrap().unwrap();
+
+    // the annotations are on the Code attribute, not on the method
+    assert!(cast
+        .attributes
+        .get_visible_type_annotations(&class_file)
+        .unwrap()
+        .is_empty());
+
+    let annotations = code.get_visible_type_annotations(&class_file).unwrap();
+
+    assert_eq!(1, annotations.len());
+    assert_eq!(TargetType::Cast, annotations[0].target_type);
+    assert_eq!(
+        TargetInfo::TypeArgument {
+            offset: 1,
+            type_argument_index: 0,
+        },
+        annotations[0].target_info
+    );
+    assert_eq!(
+        "LNonNull;",
+        class_file.get_constant_utf8(annotations[0].annotation.type_index)
+    );
+
+    let offset = annotations[0].target_info.offset().unwrap();
+    let annotated_instruction = code
+        .code
+        .iter()
+        .find(|(pc, _)| *pc == usize::from(offset))
+        .map(|(_, bytecode)| bytecode);
+    assert!(matches!(
+        annotated_instruction,
+        Some(Bytecode::Checkcast(_))
+    ));
+
+    assert!(code
+        .get_invisible_type_annotations(&class_file)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn main_method_helloworld() {
+    let mut file = File::open("classes/HelloWorld.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let main = class_file.main_method().unwrap();
+
+    assert_eq!("main", class_file.get_constant_utf8(main.name_index));
+    assert_eq!(
+        "([Ljava/lang/String;)V",
+        class_file.get_constant_utf8(main.descriptor_index)
+    );
+
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    assert_eq!(None, class_file.main_method());
+}
+
+#[test]
+fn method_body_interface() {
+    let mut file = File::open("classes/Interface.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let method = &class_file.methods[0];
+
+    assert_eq!(
+        "interfaceMethod",
+        class_file.get_constant_utf8(method.name_index)
+    );
+    assert!(!method.is_concrete());
+    assert!(!method.has_code(&class_file));
+
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let constructor = &class_file.methods[0];
+
+    assert!(constructor.is_concrete());
+    assert!(constructor.has_code(&class_file));
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn from_async_read_dummy() {
+    let bytes = fs::read("classes/Dummy.class").unwrap();
+    let mut cursor = io::Cursor::new(bytes);
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let class_file = runtime
+        .block_on(ClassFile::from_async_read(&mut cursor))
+        .unwrap();
+
+    assert_eq!("Dummy", class_file.get_class_name());
+}
+
+#[test]
+fn method_throws_map_exception_throws() {
+    let mut file = File::open("classes/ExceptionThrows.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let throws = class_file.method_throws_map();
+
+    assert_eq!(1, throws.len());
+    assert_eq!(
+        vec![
+            "java/io/IOException".to_string(),
+            "java/lang/InterruptedException".to_string(),
+        ],
+        throws["declareExceptions()V"]
+    );
+}
+
+#[test]
+fn find_string_concats_string_concat() {
+    let mut file = File::open("classes/StringConcat.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let greet = class_file
+        .find_method("greet", "(Ljava/lang/String;)V")
+        .unwrap();
+    let code = greet.get_code(&class_file).unwrap().unwrap();
+
+    assert_eq!(Bytecode::Invokedynamic(13), code.code[2].1);
+    assert_eq!(
+        vec![(4, "Hello, \u{1}!".to_string())],
+        code.find_string_concats(&class_file)
+    );
+
+    let constructor = &class_file.methods[0];
+    let code = constructor.get_code(&class_file).unwrap().unwrap();
+
+    assert!(code.find_string_concats(&class_file).is_empty());
+}
+
+#[test]
+fn bridge_methods_bridge() {
+    let mut file = File::open("classes/Bridge.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let compare_to = class_file.find_method("compareTo", "(LBridge;)I").unwrap();
+
+    assert!(!compare_to.is_bridge());
+
+    let bridge = class_file
+        .find_method("compareTo", "(Ljava/lang/Object;)I")
+        .unwrap();
+
+    assert!(bridge.is_bridge());
+    assert_eq!(vec![bridge], class_file.bridge_methods());
+}
+
+#[test]
+fn instructions_in_range_exception_throws() {
+    use jvm_class_file_parser::Bytecode::*;
+
+    let mut file = File::open("classes/ExceptionThrows.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let method = class_file.find_method("throwException", "()V").unwrap();
+    let code = method.get_code(&class_file).unwrap().unwrap();
+
+    let entry = &code.exception_table[1];
+    let instructions = code.instructions_in_range(entry.start_pc, entry.end_pc);
+
+    assert_eq!(10, instructions.len());
+    assert_eq!(&(28, Getstatic(7)), instructions[0]);
+    assert_eq!(&(51, Athrow), instructions[9]);
+}
+
+#[test]
+fn class_kind_interface() {
+    let mut file = File::open("classes/Interface.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    assert!(class_file.is_interface());
+    assert!(class_file.is_abstract());
+    assert!(!class_file.is_enum());
+    assert!(!class_file.is_annotation());
+}
+
+#[test]
+fn class_kind_dummy() {
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    assert!(!class_file.is_interface());
+    assert!(!class_file.is_abstract());
+    assert!(!class_file.is_enum());
+    assert!(!class_file.is_annotation());
+}
+
+#[test]
+fn class_kind_annotation() {
+    let mut file = File::open("classes/NonNull.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    assert!(class_file.is_interface());
+    assert!(class_file.is_annotation());
+}
+
+#[test]
+fn enum_constants_color() {
+    let mut file = File::open("classes/Color.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    assert!(class_file.is_enum());
+    assert_eq!(vec!["RED", "GREEN", "BLUE"], class_file.enum_constants());
+
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    assert!(class_file.enum_constants().is_empty());
+}
+
+#[test]
+fn parse_class_lazy_code_matches_eager() {
+    let mut file = File::open("classes/HelloWorld.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    for method in class_file.methods.iter() {
+        let eager = method.get_code(&class_file).unwrap().unwrap();
+        let lazy = method.get_lazy_code(&class_file).unwrap();
+
+        assert!(!lazy.is_decoded());
+
+        let instructions: Vec<_> = lazy.instructions().unwrap().cloned().collect();
+
+        assert!(lazy.is_decoded());
+        assert_eq!(eager.code, instructions);
+        assert_eq!(&eager, lazy.code().unwrap());
+    }
+}
+
+#[test]
+fn parse_class_iter_with_next() {
+    let mut file = File::open("classes/HelloWorld.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let main = class_file
+        .find_method("main", "([Ljava/lang/String;)V")
+        .unwrap();
+    let code = main.get_code(&class_file).unwrap().unwrap();
+
+    let instructions: Vec<_> = code.iter_with_next().collect();
+
+    assert_eq!(
+        vec![
+            (0, &Bytecode::Getstatic(2), Some(3)),
+            (3, &Bytecode::Ldc(3), Some(5)),
+            (5, &Bytecode::Invokevirtual(4), Some(8)),
+            (8, &Bytecode::Return, None),
+        ],
+        instructions
+    );
+}
+
+#[test]
+fn parse_class_intbox_access_queries() {
+    let mut file = File::open("classes/IntBox.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let public_methods: Vec<&str> = class_file
+        .public_methods()
+        .iter()
+        .map(|method| class_file.get_constant_utf8(method.name_index))
+        .collect();
+
+    assert_eq!(vec!["<init>", "getValue"], public_methods);
+    assert!(class_file.static_methods().is_empty());
+
+    assert_eq!(1, class_file.fields.len());
+    assert!(class_file.public_fields().is_empty());
+    assert!(class_file.static_fields().is_empty());
+
+    let private_fields =
+        class_file.fields_matching(|field| field.access_flags.contains(&FieldAccess::Private));
+    assert_eq!(
+        "value",
+        class_file.get_constant_utf8(private_fields[0].name_index)
+    );
+}
+
+#[test]
+fn parse_class_compute_maxes() {
+    for filepath in [
+        "classes/Dummy.class",
+        "classes/HelloWorld.class",
+        "classes/IntBox.class",
+    ]
+    .iter()
+    {
+        let mut file = File::open(filepath).unwrap();
+        let class_file = ClassFile::from_file(&mut file).unwrap();
+
+        for method in class_file.methods.iter() {
+            let code = method.get_code(&class_file).unwrap().unwrap();
+
+            assert_eq!(
+                (code.max_stack, code.max_locals),
+                code.compute_maxes(&class_file, method).unwrap()
+            );
+        }
+    }
+}
+
+#[test]
+fn parse_class_compute_max_locals() {
+    for filepath in [
+        "classes/Dummy.class",
+        "classes/IntBox.class",
+        "classes/Locals.class",
+        "classes/TryFinally.class",
+    ]
+    .iter()
+    {
+        let mut file = File::open(filepath).unwrap();
+        let class_file = ClassFile::from_file(&mut file).unwrap();
+
+        for method in class_file.methods.iter() {
+            // the lenient decoding supports the instructions of every method
+            let code = method.get_lazy_code(&class_file).unwrap();
+            let code = Code::from_bytes_lenient(code.bytes()).unwrap();
+
+            assert_eq!(
+                code.max_locals,
+                method.compute_max_locals(&class_file).unwrap()
+            );
+        }
+    }
+}
+
+#[test]
+fn parse_class_primitive_constants() {
+    let mut file = File::open("classes/PrimitiveConstants.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let code_of = |name: &str, descriptor: &str| {
+        class_file
+            .find_method(name, descriptor)
+            .unwrap()
+            .get_code(&class_file)
+            .unwrap()
+            .unwrap()
+            .code
+    };
+
+    use Bytecode::*;
+    assert_eq!(
+        vec![(0, Fconst_1), (1, Freturn)],
+        code_of("floatOne", "()F")
+    );
+    assert_eq!(
+        vec![(0, Lconst_0), (1, Lreturn)],
+        code_of("longZero", "()J")
+    );
+    assert_eq!(
+        vec![(0, Dconst_1), (1, Dreturn)],
+        code_of("doubleOne", "()D")
+    );
+}
+
+#[test]
+fn parse_class_exception_throws_try_catch_blocks() {
+    let mut file = File::open("classes/ExceptionThrows.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let method = class_file.find_method("throwException", "()V").unwrap();
+    let code = method.get_code(&class_file).unwrap().unwrap();
+
+    assert_eq!(
+        vec![
+            TryCatchBlock {
+                start_pc: 0,
+                end_pc: 24,
+                handlers: vec![CatchHandler {
+                    handler_pc: 27,
+                    catch_type: "java/lang/Exception".to_string(),
+                }],
+            },
+            TryCatchBlock {
+                start_pc: 28,
+                end_pc: 52,
+                handlers: vec![CatchHandler {
+                    handler_pc: 55,
+                    catch_type: "java/lang/Exception".to_string(),
+                }],
+            },
+        ],
+        code.try_catch_blocks(&class_file)
+    );
+}
+
+#[test]
+fn classify_application() {
+    let mut file = File::open("classes/HelloWorld.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    assert_eq!(ClassKind::Application, class_file.classify());
+}
+
+#[test]
+fn classify_interface() {
+    let mut file = File::open("classes/Interface.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    assert_eq!(ClassKind::Interface, class_file.classify());
+}
+
+#[test]
+fn classify_plain() {
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    assert_eq!(ClassKind::Plain, class_file.classify());
+}
+
+#[test]
+fn is_preview_minor_version() {
+    let mut bytes = fs::read("classes/Dummy.class").unwrap();
+    let class_file = ClassFile::from_file(&mut &bytes[..]).unwrap();
+
+    assert!(!class_file.is_preview());
+
+    // the minor version follows the 4 byte magic number
+    bytes[4] = 0xFF;
+    bytes[5] = 0xFF;
+    let class_file = ClassFile::from_file(&mut &bytes[..]).unwrap();
+
+    assert_eq!(0xFFFF, class_file.minor_version);
+    assert!(class_file.is_preview());
+}
+
+#[test]
+fn find_duplicate_members_methods() {
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    assert!(class_file.find_duplicate_members().is_empty());
+
+    // a second constructor with the same descriptor, but separate constants
+    let mut constructor = class_file.methods[0].clone();
+    constructor.name_index = class_file.add_constant(ConstantPoolEntry::ConstantUtf8 {
+        string: "<init>".to_string(),
+    });
+    class_file.methods.push(constructor.clone());
+    class_file.methods.push(constructor);
+
+    assert_eq!(
+        vec!["method <init>:()V"],
+        class_file.find_duplicate_members()
+    );
+}
+
+#[test]
+fn find_duplicate_members_fields_and_methods_separately() {
+    let mut file = File::open("classes/IntBox.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    assert!(class_file.find_duplicate_members().is_empty());
+
+    // a field and method may share a name, as long as each is unique among
+    // its own kind
+    let mut field = class_file.fields[0].clone();
+    field.name_index = class_file.intern_utf8("getValue");
+    field.descriptor_index = class_file.intern_utf8("()I");
+    class_file.fields.push(field);
+
+    assert!(class_file.find_duplicate_members().is_empty());
+
+    let value = class_file.fields[0].clone();
+    class_file.fields.push(value);
+
+    assert_eq!(vec!["field value:I"], class_file.find_duplicate_members());
+}
+
+#[test]
+fn read_class_name_only_matches_full_parse() {
+    for filepath in [
+        "classes/Dummy.class",
+        "classes/Nest$Inner.class",
+        "classes/module-info.class",
+    ]
+    .iter()
+    {
+        let mut file = File::open(filepath).unwrap();
+        let class_file = ClassFile::from_file(&mut file).unwrap();
+
+        let mut file = File::open(filepath).unwrap();
+        let class_name = ClassFile::read_class_name_only(&mut file).unwrap();
+
+        assert_eq!(class_file.get_class_name(), class_name);
+    }
+}
+
+#[test]
+fn read_class_name_only_stops_after_this_class() {
+    let bytes = fs::read("classes/Dummy.class").unwrap();
+
+    let mut cursor = io::Cursor::new(&bytes[..]);
+    assert_eq!(
+        "Dummy",
+        ClassFile::read_class_name_only(&mut cursor).unwrap()
+    );
+
+    // the fields, methods, and attributes are never read
+    let end = cursor.position() as usize;
+    assert!(end < bytes.len());
+    assert_eq!(
+        "Dummy",
+        ClassFile::read_class_name_only(&mut &bytes[..end]).unwrap()
+    );
+
+    // but the constant pool is needed
+    assert!(ClassFile::read_class_name_only(&mut &bytes[..40]).is_err());
+}
+
+#[test]
+fn get_constant_class_type_array() {
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let name_index = class_file.intern_utf8("[Ljava/lang/Object;");
+    let array_class = class_file.add_constant(ConstantPoolEntry::ConstantClass { name_index });
+
+    let class_type = class_file.get_constant_class_type(array_class);
+
+    assert_eq!(
+        ClassType::Array {
+            dimensions: 1,
+            element_type: FieldType::Object("java/lang/Object".to_string()),
+        },
+        class_type
+    );
+    assert!(class_type.is_array());
+    assert_eq!("java.lang.Object[]", class_type.to_string());
+    assert_eq!(
+        "checkcast     \"[Ljava/lang/Object;\"",
+        Bytecode::Checkcast(array_class as u16).to_string_resolved(0, &class_file)
+    );
+
+    // #2 of Dummy is the class itself
+    assert_eq!(
+        ClassType::Class("Dummy".to_string()),
+        class_file.get_constant_class_type(2)
+    );
+    assert_eq!(
+        "checkcast     Dummy",
+        Bytecode::Checkcast(2).to_string_resolved(0, &class_file)
+    );
+}
+
+#[test]
+fn get_name_and_type_dummy() {
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    // #10 of Dummy is the NameAndType of the constructor
+    assert_eq!(
+        "\"<init>\":()V",
+        class_file.get_constant_name_and_type_str(10)
+    );
+    assert_eq!(Some(("<init>", "()V")), class_file.get_name_and_type(10));
+
+    // #1 is a Methodref, and #0 and #100 are not valid indexes
+    assert_eq!(None, class_file.get_name_and_type(1));
+    assert_eq!(None, class_file.get_name_and_type(0));
+    assert_eq!(None, class_file.get_name_and_type(100));
+}
+
+#[test]
+fn diff_int_box_with_method_removed() {
+    let mut file = File::open("classes/IntBox.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let mut modified = class_file.clone();
+    modified
+        .methods
+        .retain(|method| class_file.get_constant_utf8(method.name_index) != "getValue");
+
+    assert!(class_file.diff(&class_file.clone()).is_empty());
+
+    let differences = class_file.diff(&modified);
+    assert_eq!(
+        vec![Difference::MethodRemoved("getValue:()I".to_string())],
+        differences
+    );
+    assert_eq!("method removed: getValue:()I", differences[0].to_string());
+
+    assert_eq!(
+        vec![Difference::MethodAdded("getValue:()I".to_string())],
+        modified.diff(&class_file)
+    );
+}
+
+#[test]
+fn diff_classes_with_unsupported_instructions() {
+    for path in ["classes/Color.class", "classes/Point.class"].iter() {
+        let mut file = File::open(path).unwrap();
+        let class_file = ClassFile::from_file(&mut file).unwrap();
+
+        assert!(class_file.diff(&class_file.clone()).is_empty());
+
+        let mut compacted = class_file.clone();
+        compacted.dedup_constant_pool().unwrap();
+        compacted.gc_constant_pool().unwrap();
+
+        assert!(class_file.diff(&compacted).is_empty());
+
+        // code with an undefined opcode is compared byte for byte
+        let mut modified = class_file.clone();
+        let method = &mut modified.methods[0];
+        let location = DiffLocation::Method(format!(
+            "{}:{}",
+            class_file.get_constant_utf8(method.name_index),
+            class_file.get_constant_utf8(method.descriptor_index)
+        ));
+        let code_index = method
+            .attributes
+            .attributes
+            .iter()
+            .position(|attr| attr.name(&class_file) == "Code")
+            .unwrap();
+        method.attributes.attributes[code_index].info[8] = 0xfe;
+
+        assert_eq!(
+            vec![Difference::AttributeChanged(location, "Code".to_string())],
+            class_file.diff(&modified)
+        );
+    }
+}
+
+#[test]
+fn diff_int_box_with_changed_members() {
+    let mut file = File::open("classes/IntBox.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let mut modified = class_file.clone();
+    let long_descriptor = modified.intern_utf8("()J");
+    let get_value = modified
+        .methods
+        .iter_mut()
+        .find(|method| class_file.get_constant_utf8(method.name_index) == "getValue")
+        .unwrap();
+    get_value.descriptor_index = long_descriptor;
+    modified.fields[0].access_flags.clear();
+    modified.gc_constant_pool().unwrap();
+
+    let differences: Vec<String> = class_file
+        .diff(&modified)
+        .iter()
+        .map(|difference| difference.to_string())
+        .collect();
+    assert_eq!(
+        vec![
+            "field value:I access flags changed",
+            "method getValue descriptor changed: ()I -> ()J",
+        ],
+        differences
+    );
+    assert_eq!(
+        Difference::AccessFlagsChanged(DiffLocation::Field("value:I".to_string())),
+        class_file.diff(&modified)[0]
+    );
+}
+
+#[test]
+fn type_parameters_of_generic_class() {
+    let mut file = File::open("classes/Generic.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    assert_eq!(
+        vec![
+            TypeParameter {
+                name: "T".to_string(),
+                bounds: vec!["java.lang.Object".to_string()],
+            },
+            TypeParameter {
+                name: "U".to_string(),
+                bounds: vec![
+                    "java.lang.Number".to_string(),
+                    "java.lang.Comparable<U>".to_string(),
+                ],
+            },
+            TypeParameter {
+                name: "L".to_string(),
+                bounds: vec!["java.util.List<? super T>".to_string()],
+            },
+        ],
+        class_file.type_parameters()
+    );
+}
+
+#[test]
+fn type_parameters_of_non_generic_class() {
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    assert!(class_file.type_parameters().is_empty());
+}
+
+#[test]
+fn parse_class_dummy_structured_attributes() {
+    let options = ParseOptions {
+        structured_attributes: true,
+        ..ParseOptions::default()
+    };
+
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let class_file = ClassFile::from_file_with_options(&mut file, &options).unwrap();
+
+    let code_attribute = &class_file.methods[0].attributes.attributes[0];
+
+    use Bytecode::*;
+    assert_eq!(
+        Some(StructuredAttribute::Code(Code {
+            max_stack: 1,
+            max_locals: 1,
+            code: vec![(0, Aload_0), (1, Invokespecial(1)), (4, Return)],
+            exception_table: vec![],
+            attributes: AttributeSet {
+                attributes: vec![Attribute {
+                    attribute_name_index: 7,
+                    info: vec![0, 1, 0, 0, 0, 1],
+                    structured: None,
+                    file_offset: None,
+                }]
+            },
+        })),
+        code_attribute.structured
+    );
+
+    assert_eq!(
+        Some(StructuredAttribute::SourceFile(9)),
+        class_file.attributes.attributes[0].structured
+    );
+}
+
+/// Adds a Dynamic constant named `_` of type `Ljava/lang/Object;` that is
+/// computed by the first bootstrap method of the class, returning its index.
+fn add_dynamic_constant(class_file: &mut ClassFile) -> usize {
+    let name_index = class_file.intern_utf8("_");
+    let descriptor_index = class_file.intern_utf8("Ljava/lang/Object;");
+
+    let name_and_type_index = class_file.add_constant(ConstantPoolEntry::ConstantNameAndType {
+        name_index,
+        descriptor_index,
+    });
+
+    class_file.add_constant(ConstantPoolEntry::ConstantDynamic {
+        bootstrap_method_attr_index: 0,
+        name_and_type_index: name_and_type_index as u16,
+    })
+}
+
+#[test]
+fn parse_class_lambda_resolve_dynamic() {
+    let mut file = File::open("classes/Lambda.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let index = add_dynamic_constant(&mut class_file);
+
+    let dynamic = class_file.resolve_dynamic(index);
+
+    assert_eq!(0, dynamic.bootstrap_method_attr_index);
+    assert_eq!(
+        ReferenceKind::InvokeStatic,
+        dynamic.bootstrap_method.reference_kind
+    );
+    assert_eq!(
+        "java/lang/invoke/LambdaMetafactory",
+        dynamic.bootstrap_method.class_name
+    );
+    assert_eq!("metafactory", dynamic.bootstrap_method.name);
+    assert_eq!(
+        class_file.get_bootstrap_methods()[0].bootstrap_arguments,
+        dynamic.bootstrap_arguments
+    );
+    assert_eq!("_", dynamic.name);
+    assert_eq!("Ljava/lang/Object;", dynamic.descriptor);
+}
+
+#[test]
+fn parse_class_dummy_constant_references() {
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let references = class_file.constant_references().unwrap();
+
+    // #6 of Dummy is the Utf8 "Code"
+    assert_eq!("Code", class_file.get_constant_utf8(6));
+    assert_eq!(
+        vec![ConstantReferrer::AttributeName(AttributeOwner::Method(0))],
+        references[&6]
+    );
+
+    // #7 of Dummy is the Utf8 "LineNumberTable", which names an attribute
+    // nested in the Code attribute
+    assert_eq!(
+        vec![ConstantReferrer::AttributeInfo(AttributeOwner::Method(0))],
+        references[&7]
+    );
+
+    // #4 of Dummy is the Utf8 "<init>"
+    assert_eq!(
+        vec![
+            ConstantReferrer::Constant(10),
+            ConstantReferrer::MethodName(0)
+        ],
+        references[&4]
+    );
+}
+
+#[test]
+fn parse_class_nest_inner_synthetic_field() {
+    let mut file = File::open("classes/Nest$Inner.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let field = &class_file.fields[0];
+
+    assert_eq!("this$0", class_file.get_constant_utf8(field.name_index));
+    assert!(field.access_flags.contains(&FieldAccess::Synthetic));
+    assert!(field.is_synthetic(&class_file));
+}
+
+#[test]
+fn parse_class_bridge_synthetic_methods() {
+    let mut file = File::open("classes/Bridge.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    for method in class_file.methods.iter() {
+        assert_eq!(method.is_bridge(), method.is_synthetic(&class_file));
+    }
+}
+
+#[test]
+fn synthetic_attribute_method() {
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    assert!(!class_file.methods[0].is_synthetic(&class_file));
+
+    let attribute_name_index = class_file.intern_utf8("Synthetic");
+    class_file.methods[0].attributes.attributes.push(Attribute {
+        attribute_name_index,
+        info: vec![],
+        structured: None,
+        file_offset: None,
+    });
+
+    assert!(class_file.methods[0].is_synthetic(&class_file));
+}
+
+#[test]
+fn parse_class_hello_world_referenced_members() {
+    let mut file = File::open("classes/HelloWorld.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let members = class_file.referenced_members();
+
+    assert!(members.contains(&MemberRef {
+        kind: MemberRefKind::Method,
+        class_name: "java/io/PrintStream",
+        name: "println",
+        descriptor: "(Ljava/lang/String;)V",
+    }));
+    assert!(members.contains(&MemberRef {
+        kind: MemberRefKind::Field,
+        class_name: "java/lang/System",
+        name: "out",
+        descriptor: "Ljava/io/PrintStream;",
+    }));
+    assert_eq!(3, members.len());
+}
+
+#[test]
+fn relocate_java_lang_hello_world() {
+    let mut file = File::open("classes/HelloWorld.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    // a string literal that shares the Utf8 constant of a class name
+    let string_index =
+        class_file.add_constant(ConstantPoolEntry::ConstantString { string_index: 22 });
+
+    class_file.relocate("java/lang/", "shaded/java/lang/");
+
+    assert_eq!("HelloWorld", class_file.get_class_name());
+    assert_eq!(
+        "shaded/java/lang/Object",
+        class_file.get_constant_class_str(6)
+    );
+    assert_eq!(
+        "shaded/java/lang/System",
+        class_file.get_constant_class_str(16)
+    );
+    assert_eq!("java/io/PrintStream", class_file.get_constant_class_str(19));
+
+    let main = class_file.methods[1].descriptor_index;
+    assert_eq!(
+        "([Lshaded/java/lang/String;)V",
+        class_file.get_constant_utf8(main)
+    );
+    assert_eq!(
+        "\"println\":(Lshaded/java/lang/String;)V",
+        class_file.get_constant_name_and_type_str(20)
+    );
+    assert_eq!(
+        "\"out\":Ljava/io/PrintStream;",
+        class_file.get_constant_name_and_type_str(17)
+    );
+
+    assert_eq!("Hello, World!", class_file.get_constant_utf8(18));
+    match *class_file.get_constant(string_index) {
+        ConstantPoolEntry::ConstantString { string_index } => {
+            assert_eq!(
+                "java/lang/Object",
+                class_file.get_constant_utf8(string_index)
+            )
+        }
+        ref other => panic!("Expected a String constant, found: {:?}", other),
+    }
+
+    assert!(class_file.validate_constant_references().is_ok());
+}
+
+#[test]
+fn relocate_java_lang_signature() {
+    let mut file = File::open("classes/Color.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    class_file.relocate("java/lang/", "shaded/java/lang/");
+
+    assert_eq!(
+        Some("Lshaded/java/lang/Enum<LColor;>;".to_string()),
+        class_file.attributes.get_signature(&class_file)
+    );
+    assert_eq!(
+        "shaded/java/lang/Enum",
+        class_file.get_constant_class_str(class_file.super_class)
+    );
+}
+
+#[test]
+fn parse_class_empty() {
+    let mut file = File::open("classes/Empty.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    assert_eq!("Empty", class_file.get_class_name());
+    assert!(class_file.is_interface());
+
+    assert!(class_file.interfaces.is_empty());
+    assert!(class_file.fields.is_empty());
+    assert!(class_file.methods.is_empty());
+    assert!(class_file.attributes.attributes.is_empty());
+}
+
+#[test]
+fn parse_inner_class_access_of_private_static_class() {
+    let mut file = File::open("classes/Nest.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let inner_classes = class_file.get_inner_classes();
+    let hidden = inner_classes
+        .iter()
+        .find(|inner| inner.get_class_name(&class_file) == "Nest$Hidden")
+        .unwrap();
+
+    let mut expected = HashSet::new();
+    expected.insert(InnerClassAccess::Private);
+    expected.insert(InnerClassAccess::Static);
+
+    assert_eq!(expected, hidden.access());
+    assert_eq!(
+        "private static",
+        InnerClassAccess::source_modifiers(&hidden.access())
+    );
+    assert_eq!(
+        "Nest",
+        class_file.get_constant_class_str(hidden.outer_class_info_index)
+    );
+}
+
+#[test]
+fn ldc_result_type_of_long_is_category_2() {
+    let mut file = File::open("classes/StaticConstantValues.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let long_type = class_file.ldc_result_type(18).unwrap();
+
+    assert_eq!(VerificationType::Long, long_type);
+    assert!(long_type.is_category_2());
+    assert_eq!(2, long_type.slot_size());
+
+    assert_eq!(
+        Some(VerificationType::Integer),
+        class_file.ldc_result_type(12)
+    );
+    assert_eq!(None, class_file.ldc_result_type(19));
+    assert_eq!(None, class_file.ldc_result_type(24));
+}
+
+#[test]
+fn parse_class_dummy_record_offsets() {
+    let options = ParseOptions {
+        record_offsets: true,
+        ..ParseOptions::default()
+    };
+
+    let bytes = fs::read("classes/Dummy.class").unwrap();
+    let class_file = ClassFile::from_file_with_options(&mut &bytes[..], &options).unwrap();
+
+    let method = &class_file.methods[0];
+    let offset = method.file_offset().unwrap();
+
+    assert!(offset < bytes.len());
+    assert_eq!(
+        [0x00, method.name_index as u8],
+        bytes[offset + 2..offset + 4]
+    );
+
+    let code_attribute = &method.attributes.attributes[0];
+    let attr_offset = code_attribute.file_offset().unwrap();
+
+    assert_eq!(offset + 8, attr_offset);
+    assert_eq!(
+        [0x00, code_attribute.attribute_name_index as u8],
+        bytes[attr_offset..attr_offset + 2]
+    );
+
+    let source_file = &class_file.attributes.attributes[0];
+    assert_eq!(bytes.len() - 8, source_file.file_offset().unwrap());
+
+    // offsets are only recorded when asked for
+    let class_file = ClassFile::from_file(&mut &bytes[..]).unwrap();
+    assert_eq!(None, class_file.methods[0].file_offset());
+}
+
+/// A bitwise CRC-32, kept separate from the one used by the library so that
+/// the two can be checked against each other
+fn reference_crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+
+    for byte in bytes {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+
+    !crc
+}
+
+#[test]
+fn parse_class_with_crc32() {
+    for filepath in ["classes/Dummy.class", "classes/Lambda.class"].iter() {
+        let bytes = fs::read(filepath).unwrap();
+
+        let mut file = File::open(filepath).unwrap();
+        let (class_file, crc32) = ClassFile::from_file_with_crc32(&mut file).unwrap();
+
+        assert_eq!(reference_crc32(&bytes), crc32);
+        assert_eq!(ClassFile::from_file(&mut &bytes[..]).unwrap(), class_file);
+    }
+}
+
+#[test]
+fn parse_class_intbox_constructor_used_local_slots() {
+    let mut file = File::open("classes/IntBox.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let constructor = class_file.find_method("<init>", "(I)V").unwrap();
+    let code = constructor.get_code(&class_file).unwrap().unwrap();
+
+    let expected: BTreeSet<u16> = vec![0, 1].into_iter().collect();
+
+    assert_eq!(expected, code.used_local_slots().unwrap());
+    assert_eq!(expected, constructor.used_local_slots(&class_file).unwrap());
+}
+
+#[test]
+fn parse_class_locals_used_local_slots() {
+    let mut file = File::open("classes/Locals.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    // uses istore_2, lstore_3, dstore 5, lstore 7, iinc, and wide iinc, and
+    // so cannot be decoded by get_code
+    let sum = class_file.find_method("sum", "(I)D").unwrap();
+
+    let expected: BTreeSet<u16> = (0..9).collect();
+
+    assert_eq!(expected, sum.used_local_slots(&class_file).unwrap());
+}
+
+#[test]
+fn parse_class_intbox_methods_with_code() {
+    let mut file = File::open("classes/IntBox.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let instructions: usize = class_file
+        .methods_with_code()
+        .map(|(_, code)| code.unwrap().unwrap().code.len())
+        .sum();
+
+    // the constructor has 6 instructions and getValue has 3
+    assert_eq!(9, instructions);
+}
+
+#[test]
+fn parse_class_methods_with_code_reports_errors_per_method() {
+    let mut file = File::open("classes/IntBox.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    // truncate the code of the constructor
+    class_file.methods[0].attributes.attributes[0]
+        .info
+        .truncate(4);
+
+    let results: Vec<bool> = class_file
+        .methods_with_code()
+        .map(|(_, code)| code.is_ok())
+        .collect();
+
+    assert_eq!(vec![false, true], results);
+}
+
+#[test]
+fn parse_directory_of_fixtures() {
+    let results = parse_directory("classes");
+
+    let paths: Vec<&Path> = results.iter().map(|(path, _)| path.as_path()).collect();
+
+    // class files in subdirectories are found too
+    assert!(paths.contains(&Path::new("classes/Dummy.class")));
+    assert!(paths.contains(&Path::new("classes/debug/Dummy.class")));
+    assert!(paths.contains(&Path::new("classes/no_debug/IntBox.class")));
+    assert!(!paths.iter().any(|path| path.ends_with("Dummy.java")));
+
+    for (path, result) in results.iter() {
+        assert!(
+            result.is_ok(),
+            "Failed to parse {}: {}",
+            path.display(),
+            result.as_ref().unwrap_err()
+        );
+    }
+}
+
+#[test]
+fn parse_directory_records_failures() {
+    let dir = std::env::temp_dir().join("jvm_class_file_parser_parse_directory");
+    fs::create_dir_all(dir.join("nested")).unwrap();
+    fs::copy(
+        "classes/Dummy.class",
+        dir.join("nested").join("Dummy.class"),
+    )
+    .unwrap();
+    fs::write(dir.join("Broken.class"), [0xCA, 0xFE, 0xD0, 0x0D]).unwrap();
+    fs::write(dir.join("notes.txt"), "not a class").unwrap();
+
+    let results = parse_directory(&dir);
+
+    assert_eq!(2, results.len());
+
+    assert_eq!(dir.join("Broken.class"), results[0].0);
+    match results[0].1 {
+        Err(ParseError::InvalidMagic(0xCAFE_D00D)) => {}
+        ref other => panic!("Expected an invalid magic error, but found: {:?}", other),
+    }
+
+    assert_eq!(dir.join("nested").join("Dummy.class"), results[1].0);
+    assert_eq!("Dummy", results[1].1.as_ref().unwrap().get_class_name());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn parse_directory_does_not_follow_directory_symlinks() {
+    let dir = std::env::temp_dir().join("jvm_class_file_parser_parse_directory_symlinks");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("nested")).unwrap();
+    fs::copy("classes/Dummy.class", dir.join("Dummy.class")).unwrap();
+
+    // a link back to the directory being searched, along with a link to a
+    // class file, which is still parsed
+    std::os::unix::fs::symlink(&dir, dir.join("nested").join("loop")).unwrap();
+    std::os::unix::fs::symlink(dir.join("Dummy.class"), dir.join("Linked.class")).unwrap();
+
+    let results = parse_directory(&dir);
+
+    let paths: Vec<&Path> = results.iter().map(|(path, _)| path.as_path()).collect();
+    assert_eq!(
+        vec![
+            dir.join("Dummy.class").as_path(),
+            dir.join("Linked.class").as_path()
+        ],
+        paths
+    );
+    assert!(results.iter().all(|(_, result)| result.is_ok()));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn parse_class_object_overrides() {
+    let mut file = File::open("classes/Point.class").unwrap();
+    let point = ClassFile::from_file(&mut file).unwrap();
+
+    let to_string = point
+        .find_method("toString", "()Ljava/lang/String;")
+        .unwrap();
+    let equals = point
+        .find_method("equals", "(Ljava/lang/Object;)Z")
+        .unwrap();
+
+    assert!(to_string.is_object_override(&point));
+    assert!(equals.is_object_override(&point));
+
+    let mut file = File::open("classes/IntBox.class").unwrap();
+    let int_box = ClassFile::from_file(&mut file).unwrap();
+
+    let get_value = int_box.find_method("getValue", "()I").unwrap();
+
+    assert!(!get_value.is_object_override(&int_box));
+}
+
+#[test]
+fn invoke_arg_slots_includes_receiver() {
+    let mut file = File::open("classes/Color.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    // Enum."<init>":(Ljava/lang/String;I)V
+    assert_eq!(
+        Some(3),
+        class_file.invoke_arg_slots(&Bytecode::Invokespecial(28))
+    );
+
+    // Enum.valueOf:(Ljava/lang/Class;Ljava/lang/String;)Ljava/lang/Enum;
+    assert_eq!(
+        Some(2),
+        class_file.invoke_arg_slots(&Bytecode::Invokestatic(22))
+    );
+
+    assert_eq!(None, class_file.invoke_arg_slots(&Bytecode::Return));
+}
@@ -0,0 +1,297 @@
+extern crate jvm_class_file_parser;
+
+use std::fs::File;
+
+use jvm_class_file_parser::{
+    AttributeSet, Bytecode, ClassFile, Code, ConstantPoolEntry, CpIndex, ExceptionTableEntry,
+};
+
+fn code_of(bytecode: Vec<(usize, Bytecode)>) -> Code {
+    Code {
+        max_stack: 1,
+        max_locals: 1,
+        code: bytecode,
+        exception_table: vec![],
+        attributes: AttributeSet { attributes: vec![] },
+    }
+}
+
+#[test]
+fn validate_constant_loads_ldc_string() {
+    let mut file = File::open("classes/HelloWorld.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    // #3 of HelloWorld is a String
+    use Bytecode::*;
+    let code = code_of(vec![(0, Ldc(3)), (2, Return)]);
+
+    assert!(code.validate_constant_loads(&class_file).is_ok());
+}
+
+#[test]
+fn validate_branch_offsets_far_goto() {
+    use Bytecode::*;
+
+    // a goto meant to branch 40000 bytes forward, which wraps around to a
+    // negative offset
+    let mut bytecode = vec![(0, Goto(40000))];
+    bytecode.extend((3..40000).map(|pc| (pc, Aload_0)));
+    bytecode.push((40000, Return));
+    let code = code_of(bytecode);
+
+    let error = code.validate_branch_offsets().unwrap_err();
+
+    assert_eq!(
+        "The goto at pc 0 has an offset of 40000 to pc 40000, which does not fit in 2 bytes. Use goto_w instead.",
+        error.to_string()
+    );
+
+    let mut bytecode = vec![(0, Goto_w(40000))];
+    bytecode.extend((5..40000).map(|pc| (pc, Aload_0)));
+    bytecode.push((40000, Return));
+    let code = code_of(bytecode);
+
+    assert!(code.validate_branch_offsets().is_ok());
+}
+
+#[test]
+fn validate_branch_offsets_into_instruction() {
+    use Bytecode::*;
+
+    let code = code_of(vec![(0, Ifeq(4)), (3, Ldc(1)), (5, Return)]);
+
+    assert_eq!(
+        "The ifeq at pc 0 branches to 4, which is not the start of an instruction.",
+        code.validate_branch_offsets().unwrap_err().to_string()
+    );
+}
+
+#[test]
+fn validate_constant_loads_ldc_name_and_type() {
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    // #10 of Dummy is a NameAndType
+    use Bytecode::*;
+    let code = code_of(vec![(0, Ldc(10)), (2, Return)]);
+
+    assert!(code.validate_constant_loads(&class_file).is_err());
+
+    let code = code_of(vec![(0, Ldc2_w(10)), (3, Return)]);
+
+    assert!(code.validate_constant_loads(&class_file).is_err());
+}
+
+#[test]
+fn validate_constant_loads_ldc_long() {
+    let mut file = File::open("classes/ConstantValues.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    // #6 of ConstantValues is a Long
+    use Bytecode::*;
+    let code = code_of(vec![(0, Ldc2_w(6)), (3, Return)]);
+
+    assert!(code.validate_constant_loads(&class_file).is_ok());
+
+    let code = code_of(vec![(0, Ldc_w(6)), (3, Return)]);
+
+    assert!(code.validate_constant_loads(&class_file).is_err());
+}
+
+#[test]
+fn validate_constant_loads_dynamic_with_invalid_name_and_type() {
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let index = class_file.add_constant(ConstantPoolEntry::ConstantDynamic {
+        bootstrap_method_attr_index: 0,
+        name_and_type_index: 999,
+    });
+
+    use Bytecode::*;
+    let code = code_of(vec![(0, Ldc_w(index as u16)), (3, Return)]);
+
+    let error = code.validate_constant_loads(&class_file).unwrap_err();
+
+    assert!(error
+        .to_string()
+        .starts_with("Failed to resolve the dynamic constant loaded at pc 0."));
+}
+
+#[test]
+fn validate_max_locals_intbox() {
+    let mut file = File::open("classes/IntBox.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let constructor = &class_file.methods[0];
+    let code = constructor.get_code(&class_file).unwrap().unwrap();
+
+    assert_eq!(2, constructor.required_locals(&class_file).unwrap());
+    assert!(code.max_locals >= 2);
+    assert!(constructor.validate_max_locals(&class_file).is_ok());
+}
+
+#[test]
+fn validate_max_locals_too_few_locals() {
+    let mut file = File::open("classes/IntBox.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let mut constructor = class_file.methods[0].clone();
+
+    // set the max locals of the Code attribute to 1
+    let code_attribute = &mut constructor.attributes.attributes[0];
+    code_attribute.info[2] = 0;
+    code_attribute.info[3] = 1;
+
+    assert!(constructor.validate_max_locals(&class_file).is_err());
+}
+
+#[test]
+fn get_code_missing_code_attribute() {
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    class_file.methods[0].attributes.attributes.clear();
+
+    assert!(class_file.methods[0].get_code(&class_file).is_err());
+}
+
+#[test]
+fn get_code_abstract_method() {
+    let mut file = File::open("classes/Interface.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    assert!(class_file.methods[0]
+        .get_code(&class_file)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn validate_constant_references_fixtures() {
+    for filepath in [
+        "classes/Dummy.class",
+        "classes/Lambda.class",
+        "classes/ConstantValues.class",
+        "classes/module-info.class",
+    ]
+    .iter()
+    {
+        let mut file = File::open(filepath).unwrap();
+        let class_file = ClassFile::from_file(&mut file).unwrap();
+
+        assert!(class_file.validate_constant_references().is_ok());
+    }
+}
+
+#[test]
+fn validate_constant_references_self_referential_class() {
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    // #2 of Dummy is the Class constant of Dummy itself
+    class_file.constant_pool[1] = ConstantPoolEntry::ConstantClass { name_index: 2 };
+
+    let error = class_file.validate_constant_references().unwrap_err();
+
+    assert!(error.to_string().contains("constant #2"));
+}
+
+#[test]
+fn cp_index_resolve_out_of_range() {
+    let mut file = File::open("classes/ConstantValues.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    let last = class_file.constant_pool.len();
+
+    assert!(CpIndex::checked(last, &class_file).is_ok());
+    assert!(CpIndex::checked(last + 1, &class_file).is_err());
+    assert!(CpIndex::checked(0, &class_file).is_err());
+
+    // #7 of ConstantValues is the unusable slot after the long in #6
+    assert!(CpIndex::checked(6, &class_file).is_ok());
+    assert!(CpIndex::new(7).unwrap().resolve(&class_file).is_err());
+}
+
+#[test]
+fn validate_structure_of_fixtures() {
+    for filepath in [
+        "classes/Dummy.class",
+        "classes/IntBox.class",
+        "classes/Lambda.class",
+        "classes/Interface.class",
+        "classes/module-info.class",
+    ]
+    .iter()
+    {
+        let mut file = File::open(filepath).unwrap();
+        let class_file = ClassFile::from_file(&mut file).unwrap();
+
+        assert_eq!(Ok(()), class_file.validate_structure());
+    }
+}
+
+#[test]
+fn validate_structure_of_mutated_class() {
+    let mut file = File::open("classes/Dummy.class").unwrap();
+    let mut class_file = ClassFile::from_file(&mut file).unwrap();
+
+    // #4 of Dummy is a Utf8, and #10 is a NameAndType
+    class_file.interfaces.push(4);
+    class_file.methods[0].name_index = 10;
+    class_file.attributes.attributes[0].attribute_name_index = 100;
+
+    let errors = class_file.validate_structure().unwrap_err();
+
+    assert_eq!(3, errors.len());
+    assert!(errors[0].starts_with(
+        "Invalid interface 0. Expected constant #4 to be a Class constant, but found: ConstantUtf8"
+    ));
+    assert_eq!(
+        "Invalid name of attribute 0 of class. Constant pool index #100 is out of range for a constant pool of 12 entries.",
+        errors[1]
+    );
+    assert!(errors[2].starts_with(
+        "Invalid name of method 0. Expected constant #10 to be a Utf8 constant, but found: ConstantNameAndType"
+    ));
+}
+
+#[test]
+fn validate_catch_types_finally_and_class() {
+    let mut file = File::open("classes/TryFinally.class").unwrap();
+    let class_file = ClassFile::from_file(&mut file).unwrap();
+
+    use Bytecode::*;
+    let mut code = code_of(vec![(0, Return), (1, Athrow)]);
+    code.exception_table = vec![
+        // catch any, as done for finally blocks
+        ExceptionTableEntry {
+            start_pc: 0,
+            end_pc: 1,
+            handler_pc: 1,
+            catch_type: 0,
+        },
+        // #17 of TryFinally is the Class java/lang/Throwable
+        ExceptionTableEntry {
+            start_pc: 0,
+            end_pc: 1,
+            handler_pc: 1,
+            catch_type: 17,
+        },
+    ];
+
+    assert!(code.validate_catch_types(&class_file).is_ok());
+
+    // #18 of TryFinally is a Utf8
+    code.exception_table[1].catch_type = 18;
+    let error = code.validate_catch_types(&class_file).unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("has a catch type that is not a Class constant"));
+
+    code.exception_table[1].catch_type = 100;
+    let error = code.validate_catch_types(&class_file).unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("has an invalid catch type index: 100"));
+}